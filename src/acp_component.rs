@@ -3,6 +3,7 @@
 //! This module provides the Component trait implementation that allows Sparkle
 //! to run as an ACP proxy, automatically injecting embodiment on the first prompt.
 
+use crate::context_loader::{load_config, load_workspace_config, requested_or_pinned_sparkler};
 use crate::embodiment::generate_embodiment_content;
 use crate::server::SparkleServer;
 use crate::types::FullEmbodimentParams;
@@ -13,7 +14,8 @@ use sacp::schema::{NewSessionRequest, PromptRequest, PromptResponse, SessionId,
 use sacp::{Agent, Client, ProxyToConductor};
 use sacp_rmcp::McpServerExt as _;
 use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
 use tokio::sync::Notify;
 
 /// Tracks sessions that are currently being embodied
@@ -117,6 +119,10 @@ impl Component for SparkleComponent {
         // Capture self fields before moving into closures
         let sparkler_name = self.sparkler.clone();
 
+        // The sparkler the MCP servers gate tools and prompts by: the requested
+        // one until a session's workspace resolves to another
+        let current_sparkler = Arc::new(RwLock::new(sparkler_name.clone()));
+
         // Track sessions that are currently being embodied
         let pending_embodiments = PendingEmbodimentRequests::new();
 
@@ -124,8 +130,12 @@ impl Component for SparkleComponent {
         ProxyToConductor::builder()
             .name("sparkle-proxy")
             // Provide the Sparkle MCP server to session/new requests
-            // Use new_for_acp() which excludes embodiment tool/prompt (handled by proxy)
-            .with_mcp_server(McpServer::from_rmcp("sparkle", SparkleServer::new_for_acp))
+            // In ACP mode it excludes the embodiment tool/prompt (handled by proxy),
+            // plus whatever the sparkler's settings disable
+            .with_mcp_server(McpServer::from_rmcp("sparkle", {
+                let current_sparkler = current_sparkler.clone();
+                move || SparkleServer::with_current_sparkler(current_sparkler.clone(), true)
+            }))
             // When we see a NewSessionRequest, forward it, get session_id, then send embodiment
             //
             // IMPORTANT: This comes AFTER .with_mcp_server() so that the MCP server is available
@@ -133,6 +143,7 @@ impl Component for SparkleComponent {
             .on_receive_request_from(Client, {
                 let pending_embodiments = pending_embodiments.clone();
                 let sparkler_name = sparkler_name.clone();
+                let current_sparkler = current_sparkler.clone();
                 async move |request: NewSessionRequest,
                             request_cx,
                             connection_cx| {
@@ -149,10 +160,28 @@ impl Component for SparkleComponent {
 
                     tracing::info!(?session_workspace_path, "Received NewSessionRequest");
 
+                    // The workspace may opt out of Sparkle or pin a sparkler;
+                    // resolve it before forwarding, so the session's MCP server
+                    // gates by the sparkler that is embodied
+                    let workspace_config = session_workspace_path
+                        .as_deref()
+                        .map(|path| load_workspace_config(Path::new(path)))
+                        .unwrap_or_default();
+                    let sparkler_name = match load_config() {
+                        Ok(config) => requested_or_pinned_sparkler(
+                            sparkler_name.clone(),
+                            &config,
+                            &workspace_config,
+                        ),
+                        Err(_) => sparkler_name.clone(),
+                    };
+                    if let Ok(mut current) = current_sparkler.write() {
+                        *current = sparkler_name.clone();
+                    }
+
                     // Claim our own copies of the shared state
                     // so that we can move them into the future later
                     let pending_embodiments = pending_embodiments.clone();
-                    let sparkler_name = sparkler_name.clone();
 
                     // Forward the NewSessionRequest to get a session_id
                     connection_cx
@@ -166,6 +195,14 @@ impl Component for SparkleComponent {
                                     "New session created, starting embodiment"
                                 );
 
+                                if !workspace_config.enabled {
+                                    tracing::info!(
+                                        ?session_id,
+                                        "Sparkle is turned off for this workspace, skipping embodiment"
+                                    );
+                                    return request_cx.respond(response);
+                                }
+
                                 // Mark this session as pending embodiment
                                 pending_embodiments.mark_as_pending(session_id.clone());
 
@@ -175,7 +212,7 @@ impl Component for SparkleComponent {
                                 // Generate and send embodiment prompt
                                 let embodiment_content =
                                     generate_embodiment_content(FullEmbodimentParams {
-                                        mode: Some("complete".to_string()),
+                                        mode: None,
                                         workspace_path: session_workspace_path.clone(),
                                         sparkler: sparkler_name.clone(),
                                     })
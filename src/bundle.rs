@@ -0,0 +1,514 @@
+//! Sparkler bundles: one archive to move or share a sparkler
+//!
+//! A bundle is a gzipped tar archive holding `manifest.toml` (format version,
+//! the sparkler's config entry and the list of files), the sparkler's context
+//! files under `sparkler/` and, optionally, the collaborator profile.
+//! Importing registers the sparkler in `config.sparklers`; a name clash is an
+//! error unless the import renames the sparkler or merges into the existing one.
+
+use crate::context_loader::migrate_to_multi_sparkler;
+use crate::history::History;
+use crate::insights::{
+    INSIGHTS_FILE, InsightRecord, entry_id, load_records, split_entries, write_records,
+};
+use crate::profile_patch::parse_sections;
+use crate::types::{Config, SparklerConfig};
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Bundle format written by this version; newer bundles are refused
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// File name suffix of bundles
+pub const BUNDLE_EXTENSION: &str = "sparkle.tar.gz";
+
+const MANIFEST_FILE: &str = "manifest.toml";
+const SPARKLER_PREFIX: &str = "sparkler/";
+const PROFILE_FILE: &str = "collaborator-profile.md";
+
+/// Per-sparkler files carried by a bundle, when present, including core identity overrides
+const SPARKLER_FILES: &[&str] = &[
+    "sparkler-identity.md",
+    "collaboration-context.md",
+    "collaboration-evolution.md",
+    "pattern-anchors.md",
+    INSIGHTS_FILE,
+    "identity/01-embodiment-methodology.md",
+    "identity/02-collaboration-identity.md",
+    "identity/03-partnership.md",
+];
+
+/// Append-only logs, merged entry by entry on import
+const LOG_FILES: &[&str] = &["collaboration-evolution.md", "pattern-anchors.md"];
+
+/// Description of a bundle's contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format_version: u32,
+    /// Version of sparkle-mcp that wrote the bundle
+    pub sparkle_version: String,
+    pub created: DateTime<Utc>,
+    /// Sparkler files in the bundle
+    pub files: Vec<String>,
+    /// Whether the collaborator profile is included
+    pub collaborator_profile: bool,
+    /// Config entry registered on import
+    pub sparkler: SparklerConfig,
+}
+
+/// A bundle read into memory
+#[derive(Debug)]
+pub struct Bundle {
+    pub manifest: Manifest,
+    /// Sparkler files by file name
+    pub files: BTreeMap<String, String>,
+    pub collaborator_profile: Option<String>,
+}
+
+/// What to do when a sparkler with the imported name already exists
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OnConflict {
+    /// Refuse the import
+    #[default]
+    Fail,
+    /// Import under a free name ("Reviewer-2")
+    Rename,
+    /// Merge into the existing sparkler
+    Merge,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Import under this name instead of the bundle's
+    pub name: Option<String>,
+    pub on_conflict: OnConflict,
+    /// Replace the collaborator profile with the bundle's, if it has one
+    pub include_profile: bool,
+}
+
+/// What `import_sparkler` did
+#[derive(Debug)]
+pub struct ImportReport {
+    /// Name the sparkler was imported as
+    pub name: String,
+    /// Merged into an existing sparkler
+    pub merged: bool,
+    /// A single-sparkler setup was converted; the previous sparkler's name
+    pub migrated: Option<String>,
+    pub files: Vec<String>,
+    pub profile_imported: bool,
+}
+
+/// Default file name for a bundle of `sparkler`
+pub fn bundle_file_name(sparkler: &str) -> String {
+    format!("{}.{}", sparkler, BUNDLE_EXTENSION)
+}
+
+/// Directory holding the context files of `sparkler` under `sparkle_dir`
+fn sparkler_dir(sparkle_dir: &Path, config: &Config, sparkler: &str) -> Result<PathBuf> {
+    if config.is_multi_sparkler() {
+        if !config
+            .get_all_sparkler_names()
+            .iter()
+            .any(|s| s == sparkler)
+        {
+            bail!(
+                "Sparkler '{}' not found. Available sparklers: {}",
+                sparkler,
+                config.get_all_sparkler_names().join(", ")
+            );
+        }
+        Ok(sparkle_dir.join("sparklers").join(sparkler))
+    } else {
+        let current = config
+            .get_single_sparkler_name()
+            .unwrap_or_else(|| "Sparkle".to_string());
+        if current != sparkler {
+            bail!(
+                "Sparkler '{}' not found. Available sparklers: {}",
+                sparkler,
+                current
+            );
+        }
+        Ok(sparkle_dir.to_path_buf())
+    }
+}
+
+/// Write `sparkler` (default: the default sparkler) to a bundle at `output`
+pub fn export_sparkler(
+    sparkle_dir: &Path,
+    config: &Config,
+    sparkler: Option<&str>,
+    include_profile: bool,
+    output: &Path,
+) -> Result<Manifest> {
+    let name = sparkler
+        .map(String::from)
+        .or_else(|| config.get_default_sparkler_name())
+        .or_else(|| config.get_single_sparkler_name())
+        .unwrap_or_else(|| "Sparkle".to_string());
+    let dir = sparkler_dir(sparkle_dir, config, &name)?;
+
+    let mut files = BTreeMap::new();
+    for file in SPARKLER_FILES {
+        let path = dir.join(file);
+        if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            files.insert(file.to_string(), content);
+        }
+    }
+    if files.is_empty() {
+        bail!("Sparkler '{}' has no context files to export", name);
+    }
+
+    let profile_path = sparkle_dir.join(PROFILE_FILE);
+    let profile = if include_profile {
+        Some(
+            fs::read_to_string(&profile_path)
+                .with_context(|| format!("Failed to read {}", profile_path.display()))?,
+        )
+    } else {
+        None
+    };
+
+    let manifest = Manifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        sparkle_version: env!("CARGO_PKG_VERSION").to_string(),
+        created: Utc::now(),
+        files: files.keys().cloned().collect(),
+        collaborator_profile: profile.is_some(),
+        sparkler: SparklerConfig {
+            name: name.clone(),
+            default: false,
+            settings: config.sparkler_settings(Some(&name)),
+        },
+    };
+
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let mtime = manifest.created.timestamp().max(0) as u64;
+
+    append_entry(
+        &mut archive,
+        MANIFEST_FILE,
+        &toml::to_string_pretty(&manifest)?,
+        mtime,
+    )?;
+    for (file, content) in &files {
+        append_entry(
+            &mut archive,
+            &format!("{}{}", SPARKLER_PREFIX, file),
+            content,
+            mtime,
+        )?;
+    }
+    if let Some(profile) = &profile {
+        append_entry(&mut archive, PROFILE_FILE, profile, mtime)?;
+    }
+    archive.into_inner()?.finish()?;
+
+    Ok(manifest)
+}
+
+fn append_entry<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    path: &str,
+    content: &str,
+    mtime: u64,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    archive.append_data(&mut header, path, content.as_bytes())?;
+    Ok(())
+}
+
+/// Read and validate a bundle
+///
+/// Only the manifest and known context files are read; anything else in the
+/// archive is ignored, so a bundle can't write outside the sparkler directory.
+pub fn read_bundle(path: &Path) -> Result<Bundle> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+
+    let mut manifest = None;
+    let mut files = BTreeMap::new();
+    let mut collaborator_profile = None;
+    for entry in archive
+        .entries()
+        .with_context(|| format!("{} is not a sparkle bundle", path.display()))?
+    {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.to_string_lossy().into_owned();
+
+        if entry_path == MANIFEST_FILE {
+            let content = read_entry(&mut entry, &entry_path)?;
+            manifest =
+                Some(toml::from_str::<Manifest>(&content).context("Invalid bundle manifest")?);
+        } else if entry_path == PROFILE_FILE {
+            collaborator_profile = Some(read_entry(&mut entry, &entry_path)?);
+        } else if let Some(file) = entry_path.strip_prefix(SPARKLER_PREFIX)
+            && SPARKLER_FILES.contains(&file)
+        {
+            files.insert(file.to_string(), read_entry(&mut entry, &entry_path)?);
+        }
+    }
+
+    let manifest =
+        manifest.ok_or_else(|| anyhow!("{} has no {}", path.display(), MANIFEST_FILE))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        bail!(
+            "Bundle format {} was written by sparkle-mcp {}; this version reads up to format {}. Upgrade sparkle-mcp to import it.",
+            manifest.format_version,
+            manifest.sparkle_version,
+            BUNDLE_FORMAT_VERSION
+        );
+    }
+
+    Ok(Bundle {
+        manifest,
+        files,
+        collaborator_profile,
+    })
+}
+
+fn read_entry(entry: &mut impl Read, name: &str) -> Result<String> {
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .with_context(|| format!("Failed to read {} from bundle", name))?;
+    Ok(content)
+}
+
+/// Import the bundle at `path` into `sparkle_dir` and register it in the config
+///
+/// `config` is updated and written to `sparkle_dir/config.toml`.
+pub fn import_sparkler(
+    sparkle_dir: &Path,
+    config: &mut Config,
+    path: &Path,
+    options: &ImportOptions,
+) -> Result<ImportReport> {
+    let bundle = read_bundle(path)?;
+
+    let requested = options
+        .name
+        .as_deref()
+        .unwrap_or(&bundle.manifest.sparkler.name)
+        .trim()
+        .to_string();
+    if requested.is_empty() || requested.contains(['/', '\\']) || requested.starts_with('.') {
+        bail!("'{}' is not a valid sparkler name", requested);
+    }
+
+    let existing: Vec<String> = if config.is_multi_sparkler() {
+        config.get_all_sparkler_names()
+    } else {
+        vec![
+            config
+                .get_single_sparkler_name()
+                .unwrap_or_else(|| "Sparkle".to_string()),
+        ]
+    };
+    let clashes = existing.contains(&requested);
+    let (name, merged) = match (clashes, options.on_conflict) {
+        (false, _) => (requested, false),
+        (true, OnConflict::Fail) => bail!(
+            "Sparkler '{}' already exists. Import it under another name, or choose on_conflict rename or merge.",
+            requested
+        ),
+        (true, OnConflict::Rename) => {
+            let name = (2..)
+                .map(|n| format!("{}-{}", requested, n))
+                .find(|name| !existing.contains(name))
+                .unwrap();
+            (name, false)
+        }
+        (true, OnConflict::Merge) => (requested, true),
+    };
+
+    let migrated = migrate_to_multi_sparkler(config, sparkle_dir).map_err(|e| anyhow!("{}", e))?;
+    let dir = sparkle_dir.join("sparklers").join(&name);
+    fs::create_dir_all(&dir)?;
+    let history = History::new(sparkle_dir, config.history.clone().unwrap_or_default());
+
+    for (file, content) in &bundle.files {
+        let target = dir.join(file);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if !merged || !target.exists() {
+            fs::write(&target, content)?;
+        } else if file == INSIGHTS_FILE {
+            merge_insights(&dir, content)?;
+        } else if LOG_FILES.contains(&file.as_str()) {
+            let current = fs::read_to_string(&target)?;
+            history.write(&target, merge_markdown(&current, content))?;
+        } else {
+            // Identity, context and identity overrides come from the bundle; the local version is kept in history
+            history.write(&target, content)?;
+        }
+    }
+
+    let profile_imported = match (&bundle.collaborator_profile, options.include_profile) {
+        (Some(profile), true) => {
+            history.write(&sparkle_dir.join(PROFILE_FILE), profile)?;
+            true
+        }
+        _ => false,
+    };
+
+    let sparklers = config.sparklers.get_or_insert_with(Vec::new);
+    if !sparklers.iter().any(|s| s.name == name) {
+        sparklers.push(SparklerConfig {
+            name: name.clone(),
+            default: false,
+            settings: bundle.manifest.sparkler.settings.clone(),
+        });
+    }
+    history.write(
+        &sparkle_dir.join("config.toml"),
+        toml::to_string_pretty(&config)?,
+    )?;
+
+    Ok(ImportReport {
+        name,
+        merged,
+        migrated,
+        files: bundle.files.keys().cloned().collect(),
+        profile_imported,
+    })
+}
+
+/// Merge imported insight records into `dir`
+///
+/// New records are added; for a record both sides have, the copy with the
+/// later status wins (as in `load_records`), so a retraction or supersession
+/// in the bundle reaches the local copy.
+fn merge_insights(dir: &Path, imported: &str) -> Result<()> {
+    let mut records = load_records(dir);
+    for line in imported.lines().filter(|l| !l.trim().is_empty()) {
+        let record: InsightRecord = serde_json::from_str(line)?;
+        match records.iter_mut().find(|r| r.id == record.id) {
+            Some(local) if local.status < record.status => *local = record,
+            Some(_) => {}
+            None => records.push(record),
+        }
+    }
+    write_records(dir, &records)?;
+    Ok(())
+}
+
+/// Merge an imported markdown log into the current one
+///
+/// Saved insights are whole entries: those whose `<!-- insight-id -->`
+/// marker isn't in the current file are appended verbatim. Hand-written
+/// sections are merged by `merge_sections`.
+pub fn merge_markdown(current: &str, imported: &str) -> String {
+    let known: HashSet<&str> = split_entries(current)
+        .into_iter()
+        .filter_map(entry_id)
+        .collect();
+    let mut hand_written = String::new();
+    let mut new_entries = Vec::new();
+    for entry in split_entries(imported) {
+        match entry_id(entry) {
+            Some(id) if known.contains(id) => {}
+            Some(_) => new_entries.push(entry),
+            None => hand_written.push_str(entry),
+        }
+    }
+
+    let mut merged = merge_sections(current, &hand_written);
+    for entry in new_entries {
+        merged.push('\n');
+        merged.push_str(entry.trim_end_matches('\n'));
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Merge imported hand-written sections into the current markdown
+///
+/// Paragraphs of each imported section that the current section with the
+/// same level and title lacks are appended to it, or the whole section is
+/// added at the end if the current file lacks it.
+fn merge_sections(current: &str, imported: &str) -> String {
+    let imported_lines: Vec<&str> = imported.lines().collect();
+    let mut lines: Vec<String> = current.lines().map(str::to_string).collect();
+
+    let sections = parse_sections(imported);
+    let top_level = sections.iter().map(|s| s.level).filter(|&l| l > 1).min();
+    for section in sections.iter().filter(|s| Some(s.level) == top_level) {
+        let existing = parse_sections(&lines.join("\n"))
+            .into_iter()
+            .find(|s| s.level == section.level && s.title.eq_ignore_ascii_case(&section.title));
+        let existing_paragraphs: HashSet<String> = existing
+            .as_ref()
+            .map(|s| paragraphs(&lines[s.start + 1..s.end].join("\n")))
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        let new_paragraphs: Vec<String> =
+            paragraphs(&imported_lines[section.start + 1..section.end].join("\n"))
+                .into_iter()
+                .filter(|paragraph| !existing_paragraphs.contains(paragraph))
+                .collect();
+        if new_paragraphs.is_empty() {
+            continue;
+        }
+
+        let mut insert = Vec::new();
+        let at = match existing {
+            Some(existing) => {
+                // After the section's last non-blank line
+                let mut at = existing.end;
+                while at > existing.start + 1 && lines[at - 1].trim().is_empty() {
+                    at -= 1;
+                }
+                at
+            }
+            None => {
+                insert.push(String::new());
+                insert.push(imported_lines[section.start].to_string());
+                lines.len()
+            }
+        };
+        for paragraph in new_paragraphs {
+            insert.push(String::new());
+            insert.extend(paragraph.lines().map(str::to_string));
+        }
+        lines.splice(at..at, insert);
+    }
+
+    let mut merged = lines.join("\n");
+    merged.push('\n');
+    merged
+}
+
+/// The non-blank paragraphs of a markdown fragment
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|paragraph| paragraph.trim_matches('\n').to_string())
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .collect()
+}
@@ -0,0 +1,693 @@
+//! Command-line subcommands for managing Sparkle outside an agent session
+//!
+//! Most subcommands call the same `tools::*` functions the MCP server exposes
+//! and print their text output, so the CLI and the tools behave the same.
+
+use crate::embodiment::{
+    EMBODIMENT_MODES, Embodiment, RenderOptions, recent_checkpoints, render_embodiment,
+};
+use crate::types::{Config, FullEmbodimentParams, InsightType};
+use crate::{bundle, constants, context_loader, doctor, sync, tools, tui};
+use anyhow::{Context, bail};
+use clap::Subcommand;
+use rmcp::{ErrorData as McpError, handler::server::wrapper::Parameters, model::CallToolResult};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create ~/.sparkle with a config, collaborator profile and starter files
+    #[command(alias = "setup")]
+    Init {
+        /// Your name, as Sparkle should address you
+        #[arg(long, alias = "name")]
+        human_name: String,
+        /// Name of the first sparkler (default "Sparkle")
+        #[arg(long)]
+        sparkler_name: Option<String>,
+        /// Start in multi-sparkler mode
+        #[arg(long)]
+        multi: bool,
+        /// Start with this collaborator profile instead of the template
+        #[arg(long)]
+        profile_from: Option<PathBuf>,
+        /// Replace an existing config.toml and profile (the old versions are kept in history)
+        #[arg(long)]
+        force: bool,
+    },
+    /// List, create, rename and archive sparklers
+    Sparklers {
+        #[command(subcommand)]
+        command: SparklersCommand,
+    },
+    /// Inspect a workspace's session checkpoints
+    Checkpoints {
+        /// Workspace holding .sparkle-space (default: the current directory)
+        #[arg(long, global = true)]
+        workspace: Option<PathBuf>,
+        #[command(subcommand)]
+        command: CheckpointsCommand,
+    },
+    /// Search saved insights
+    Insights {
+        #[command(subcommand)]
+        command: InsightsCommand,
+    },
+    /// Work with the collaborator profile
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommand,
+    },
+    /// Check the setup for problems and suggest fixes
+    Doctor {
+        /// Workspace to check as well (default: the current directory)
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Apply the automatic fixes
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Print the embodiment a session would start with, and the size of each section
+    Render {
+        /// Sparkler to embody (default: the workspace's pinned sparkler, then the default one)
+        #[arg(long)]
+        sparkler: Option<String>,
+        /// Workspace whose context is loaded (default: the current directory)
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Embodiment mode (default: the sparkler's setting, then "complete")
+        #[arg(long, value_parser = clap::builder::PossibleValuesParser::new(EMBODIMENT_MODES.iter().copied()))]
+        mode: Option<String>,
+        #[arg(long, value_enum, default_value_t = RenderFormat::Markdown)]
+        format: RenderFormat,
+        /// Number of checkpoints to load
+        #[arg(long)]
+        checkpoints: Option<usize>,
+        /// Cap on each included file, in bytes
+        #[arg(long)]
+        max_file_bytes: Option<usize>,
+        /// Cap on each set of included files, in bytes
+        #[arg(long)]
+        max_include_bytes: Option<usize>,
+        /// Fail if the embodiment is larger than this many bytes
+        #[arg(long)]
+        budget: Option<usize>,
+        /// Don't print the size breakdown
+        #[arg(long)]
+        quiet: bool,
+    },
+    /// Browse sparklers, insights, working-memory snapshots and the embodiment in a terminal UI
+    Tui {
+        /// Workspace to show (default: the current directory)
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Sparkler selected at start (default: the default sparkler)
+        #[arg(long)]
+        sparkler: Option<String>,
+    },
+    /// Commit local changes to ~/.sparkle, then pull (rebasing) and push against the [sync] remote
+    Sync {
+        /// Git remote to sync with, overriding [sync] remote in config.toml
+        #[arg(long)]
+        remote: Option<String>,
+    },
+    /// Write a sparkler to a single archive to move or share it
+    Export {
+        /// Sparkler to export (defaults to the default sparkler)
+        sparkler: Option<String>,
+        /// Archive to write (default ./<name>.sparkle.tar.gz)
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+        /// Also include the collaborator profile
+        #[arg(long)]
+        with_profile: bool,
+    },
+    /// Import a sparkler from an archive written by `export`
+    Import {
+        /// Archive to import
+        path: PathBuf,
+        /// Import under this name instead of the archive's
+        #[arg(long)]
+        name: Option<String>,
+        /// What to do if a sparkler with that name already exists
+        #[arg(long, value_enum, default_value_t)]
+        on_conflict: bundle::OnConflict,
+        /// Replace the collaborator profile with the archive's, if it has one
+        #[arg(long)]
+        with_profile: bool,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+pub enum RenderFormat {
+    /// The embodiment as sent; the breakdown goes to stderr
+    Markdown,
+    /// Sections with their sources and sizes
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SparklersCommand {
+    /// List sparklers and available templates
+    List,
+    /// Create a new sparkler (converts a single-sparkler setup to multi-sparkler)
+    Create {
+        name: String,
+        /// Template to start from
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// Rename a sparkler
+    Rename { old_name: String, new_name: String },
+    /// Move a sparkler to ~/.sparkle/archive/ and remove it from config.toml
+    Archive { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CheckpointsCommand {
+    /// List checkpoints, newest first
+    List {
+        /// Show at most this many
+        #[arg(long, short = 'n')]
+        limit: Option<usize>,
+    },
+    /// Print a checkpoint
+    Show {
+        /// File name or unique prefix of its timestamp (default: the latest)
+        checkpoint: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum InsightsCommand {
+    /// Search insights by text, tags, type, sparkler and date
+    Search {
+        /// Free text to rank insights by
+        query: Option<String>,
+        /// Only insights carrying this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Only insights of this type
+        #[arg(long = "type", value_enum)]
+        insight_type: Option<InsightType>,
+        /// Only insights saved by this sparkler
+        #[arg(long)]
+        sparkler: Option<String>,
+        /// Also search this workspace's insights
+        #[arg(long)]
+        workspace: Option<PathBuf>,
+        /// Saved on or after this date (YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Saved on or before this date (YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+        /// Include superseded and retracted insights
+        #[arg(long)]
+        all: bool,
+        /// Maximum number of results (default 20)
+        #[arg(long, short = 'n')]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProfileCommand {
+    /// Open the collaborator profile in $VISUAL or $EDITOR; the previous version is kept in history
+    Edit,
+}
+
+pub async fn run(command: Command) -> anyhow::Result<()> {
+    let sparkle_dir = dirs::home_dir()
+        .context("Could not determine home directory")?
+        .join(constants::SPARKLE_DIR);
+
+    match command {
+        Command::Init {
+            human_name,
+            sparkler_name,
+            multi,
+            profile_from,
+            force,
+        } => {
+            let profile = match profile_from {
+                Some(path) => Some(
+                    fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?,
+                ),
+                None => None,
+            };
+            let options = context_loader::InitOptions {
+                human_name,
+                sparkler_name,
+                multi,
+                profile,
+                force,
+            };
+            let written = context_loader::init_sparkle(&sparkle_dir, &options)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            sync::record_change(&format!("Set up sparkle for {}", options.human_name)).await;
+            println!("Created {}:", sparkle_dir.display());
+            for path in written {
+                println!("  {}", path.display());
+            }
+            Ok(())
+        }
+        Command::Sparklers { command } => run_sparklers(command, &sparkle_dir).await,
+        Command::Checkpoints { workspace, command } => {
+            let workspace = match workspace {
+                Some(workspace) => workspace,
+                None => std::env::current_dir()?,
+            };
+            run_checkpoints(command, &workspace)
+        }
+        Command::Insights { command } => run_insights(command).await,
+        Command::Profile { command } => run_profile(command, &sparkle_dir).await,
+        Command::Doctor { workspace, fix } => {
+            let workspace = match workspace {
+                Some(workspace) => workspace,
+                None => std::env::current_dir()?,
+            };
+            let mut report = doctor::diagnose(&sparkle_dir, Some(&workspace));
+            if fix {
+                let results = doctor::apply_fixes(&sparkle_dir, &report);
+                for (fix, result) in &results {
+                    match result {
+                        Ok(()) => println!("Fixed: {}", fix),
+                        Err(e) => println!("Failed to {}: {}", fix, e),
+                    }
+                }
+                if results.iter().any(|(_, result)| result.is_ok()) {
+                    sync::record_change("Apply doctor fixes").await;
+                    report = doctor::diagnose(&sparkle_dir, Some(&workspace));
+                }
+            }
+            print!("{}", report.to_markdown());
+            if report.has_errors() {
+                bail!("Sparkle doctor found errors");
+            }
+            Ok(())
+        }
+        Command::Render {
+            sparkler,
+            workspace,
+            mode,
+            format,
+            checkpoints,
+            max_file_bytes,
+            max_include_bytes,
+            budget,
+            quiet,
+        } => {
+            let workspace = match workspace {
+                Some(workspace) => workspace,
+                None => std::env::current_dir()?,
+            };
+            let params = FullEmbodimentParams {
+                mode,
+                workspace_path: Some(workspace.to_string_lossy().into_owned()),
+                sparkler,
+            };
+            let options = RenderOptions {
+                checkpoint_count: checkpoints,
+                max_file_bytes,
+                max_total_bytes: max_include_bytes,
+            };
+            let embodiment = render_embodiment(params, &options)?;
+            match format {
+                RenderFormat::Markdown => {
+                    print!("{}", embodiment.to_markdown());
+                    if !quiet {
+                        eprint!("{}", size_breakdown(&embodiment, budget));
+                    }
+                }
+                RenderFormat::Json => {
+                    let json = RenderedJson {
+                        embodiment: &embodiment,
+                        total_bytes: embodiment.total_bytes(),
+                        budget,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+            }
+            if let Some(budget) = budget
+                && embodiment.total_bytes() > budget
+            {
+                bail!(
+                    "Embodiment is {} bytes, over the budget of {}",
+                    embodiment.total_bytes(),
+                    budget
+                );
+            }
+            Ok(())
+        }
+        Command::Tui {
+            workspace,
+            sparkler,
+        } => {
+            let workspace = match workspace {
+                Some(workspace) => workspace,
+                None => std::env::current_dir()?,
+            };
+            tui::run(&sparkle_dir, workspace, sparkler).await
+        }
+        Command::Sync { remote } => {
+            let config = load_config()?;
+            let mut sync_config = config.sync.unwrap_or_default();
+            if remote.is_some() {
+                sync_config.remote = remote;
+            }
+
+            let report = sync::sync(&sparkle_dir, &sync_config)?;
+            if report.committed_local_changes {
+                println!("Committed local changes");
+            }
+            println!(
+                "Synced {} with {}: pulled {} commit(s), pushed {}",
+                sparkle_dir.display(),
+                sync_config.remote.as_deref().unwrap_or_default(),
+                report.pulled,
+                report.pushed
+            );
+            Ok(())
+        }
+        Command::Export {
+            sparkler,
+            output,
+            with_profile,
+        } => {
+            let config = load_config()?;
+            let name = sparkler
+                .or_else(|| config.get_default_sparkler_name())
+                .or_else(|| config.get_single_sparkler_name())
+                .unwrap_or_else(|| "Sparkle".to_string());
+            let output = output.unwrap_or_else(|| PathBuf::from(bundle::bundle_file_name(&name)));
+            let manifest =
+                bundle::export_sparkler(&sparkle_dir, &config, Some(&name), with_profile, &output)?;
+            println!(
+                "Exported {} to {} ({})",
+                manifest.sparkler.name,
+                output.display(),
+                manifest.files.join(", ")
+            );
+            Ok(())
+        }
+        Command::Import {
+            path,
+            name,
+            on_conflict,
+            with_profile,
+        } => {
+            let mut config = load_config()?;
+            let options = bundle::ImportOptions {
+                name,
+                on_conflict,
+                include_profile: with_profile,
+            };
+            let report = bundle::import_sparkler(&sparkle_dir, &mut config, &path, &options)?;
+            sync::record_change(&format!("Import sparkler {}", report.name)).await;
+            if let Some(migrated) = report.migrated {
+                println!(
+                    "Migrated to multi-sparkler setup ({} is the default)",
+                    migrated
+                );
+            }
+            println!(
+                "{} {} ({})",
+                if report.merged {
+                    "Merged into"
+                } else {
+                    "Imported"
+                },
+                report.name,
+                report.files.join(", ")
+            );
+            if report.profile_imported {
+                println!("Collaborator profile replaced; the previous one is kept in history");
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn run_sparklers(command: SparklersCommand, sparkle_dir: &Path) -> anyhow::Result<()> {
+    match command {
+        SparklersCommand::List => print_tool_result(
+            tools::list_sparklers::list_sparklers(Parameters(
+                tools::list_sparklers::ListSparklersParams {},
+            ))
+            .await,
+        ),
+        SparklersCommand::Create { name, template } => print_tool_result(
+            tools::create_sparkler::create_sparkler(Parameters(
+                tools::create_sparkler::CreateSparklerParams { name, template },
+            ))
+            .await,
+        ),
+        SparklersCommand::Rename { old_name, new_name } => print_tool_result(
+            tools::rename_sparkler::rename_sparkler(Parameters(
+                tools::rename_sparkler::RenameSparklerParams {
+                    new_name,
+                    old_name: Some(old_name),
+                },
+            ))
+            .await,
+        ),
+        SparklersCommand::Archive { name } => {
+            let mut config = load_config()?;
+            let archive_dir = context_loader::archive_sparkler(&mut config, sparkle_dir, &name)
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+            sync::record_change(&format!("Archive sparkler {}", name)).await;
+            println!("Archived {} to {}", name, archive_dir.display());
+            if let Some(default) = config.get_default_sparkler_name() {
+                println!("Default sparkler: {}", default);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run_checkpoints(command: CheckpointsCommand, workspace: &Path) -> anyhow::Result<()> {
+    let checkpoints_dir = workspace
+        .join(constants::SPARKLE_SPACE_DIR)
+        .join("checkpoints");
+    let mut checkpoints = recent_checkpoints(&checkpoints_dir, usize::MAX);
+    checkpoints.reverse();
+    if checkpoints.is_empty() {
+        println!("No checkpoints in {}", checkpoints_dir.display());
+        return Ok(());
+    }
+
+    match command {
+        CheckpointsCommand::List { limit } => {
+            let config = load_config()?;
+            for path in checkpoints.iter().take(limit.unwrap_or(usize::MAX)) {
+                let content = fs::read_to_string(path).unwrap_or_default();
+                let modified = fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .map(|mtime| config.human.format_time(mtime.into(), "%Y-%m-%d %H:%M %Z"))
+                    .unwrap_or_default();
+                let title = content
+                    .lines()
+                    .map(|line| line.trim_start_matches('#').trim())
+                    .find(|line| !line.is_empty())
+                    .unwrap_or("(empty)");
+                let sparkler = content
+                    .lines()
+                    .find_map(|line| line.strip_prefix("**Sparkler:**"))
+                    .map(|name| format!(" [{}]", name.trim()))
+                    .unwrap_or_default();
+                println!("{}  {}  {}{}", file_name(path), modified, title, sparkler);
+            }
+        }
+        CheckpointsCommand::Show { checkpoint } => {
+            let path = match checkpoint {
+                None => &checkpoints[0],
+                Some(wanted) => {
+                    let matches: Vec<&PathBuf> = checkpoints
+                        .iter()
+                        .filter(|path| {
+                            let name = file_name(path);
+                            name == wanted
+                                || name.starts_with(&wanted)
+                                || name
+                                    .strip_prefix("checkpoint-")
+                                    .is_some_and(|stamp| stamp.starts_with(&wanted))
+                        })
+                        .collect();
+                    match matches[..] {
+                        [path] => path,
+                        [] => bail!("No checkpoint matches '{}'", wanted),
+                        _ => bail!(
+                            "'{}' matches {} checkpoints: {}",
+                            wanted,
+                            matches.len(),
+                            matches
+                                .iter()
+                                .map(|p| file_name(p))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ),
+                    }
+                }
+            };
+            print!("{}", fs::read_to_string(path)?);
+        }
+    }
+    Ok(())
+}
+
+async fn run_insights(command: InsightsCommand) -> anyhow::Result<()> {
+    match command {
+        InsightsCommand::Search {
+            query,
+            tags,
+            insight_type,
+            sparkler,
+            workspace,
+            since,
+            until,
+            all,
+            limit,
+        } => print_tool_result(
+            tools::search_insights::search_insights(Parameters(
+                tools::search_insights::SearchInsightsParams {
+                    query,
+                    tags: (!tags.is_empty()).then_some(tags),
+                    insight_type,
+                    sparkler,
+                    workspace_path: workspace.map(|w| w.display().to_string()),
+                    since,
+                    until,
+                    include_inactive: Some(all),
+                    limit,
+                },
+            ))
+            .await,
+        ),
+    }
+}
+
+async fn run_profile(command: ProfileCommand, sparkle_dir: &Path) -> anyhow::Result<()> {
+    match command {
+        ProfileCommand::Edit => {
+            let profile_path = sparkle_dir.join("collaborator-profile.md");
+            let current = fs::read_to_string(&profile_path).with_context(|| {
+                format!(
+                    "Can't read {} (run `sparkle-mcp setup` first)",
+                    profile_path.display()
+                )
+            })?;
+
+            // Edit a copy, so the tool can keep the current version in history
+            // (created with a random name and owner-only permissions, removed on drop)
+            let draft = tempfile::Builder::new()
+                .prefix("sparkle-collaborator-profile-")
+                .suffix(".md")
+                .tempfile()?;
+            fs::write(draft.path(), &current)?;
+            open_editor(draft.path())?;
+            let edited = fs::read_to_string(draft.path())?;
+
+            if edited == current {
+                println!("No changes");
+                return Ok(());
+            }
+            print_tool_result(
+                tools::update_collaborator_profile::update_collaborator_profile(Parameters(
+                    tools::update_collaborator_profile::UpdateCollaboratorProfileParams {
+                        content: edited,
+                    },
+                ))
+                .await,
+            )
+        }
+    }
+}
+
+/// Run `$VISUAL` or `$EDITOR` (default `vi`) on `path` and wait for it to exit
+fn open_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Editors are often configured with arguments, e.g. "code --wait"
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("$EDITOR is empty")?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", editor))?;
+    if !status.success() {
+        bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok(())
+}
+
+/// `sparkle-mcp render --format json` output
+#[derive(Serialize)]
+struct RenderedJson<'a> {
+    #[serde(flatten)]
+    embodiment: &'a Embodiment,
+    total_bytes: usize,
+    budget: Option<usize>,
+}
+
+/// Table of section sizes, in the order the sections are sent
+fn size_breakdown(embodiment: &Embodiment, budget: Option<usize>) -> String {
+    let total = embodiment.total_bytes();
+    let mut out = format!(
+        "\n{:<24} {:>8} {:>6}  Source\n",
+        "Section", "Bytes", "Share"
+    );
+    for section in &embodiment.sections {
+        let share = if total == 0 {
+            0.0
+        } else {
+            section.bytes as f64 * 100.0 / total as f64
+        };
+        let source = section
+            .source
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "{:<24} {:>8} {:>5.1}%  {}\n",
+            section.name, section.bytes, share, source
+        ));
+    }
+    out.push_str(&format!("{:<24} {:>8}", "total", total));
+    if let Some(budget) = budget {
+        out.push_str(&format!(" of {} budget", budget));
+    }
+    out.push('\n');
+    out
+}
+
+fn load_config() -> anyhow::Result<Config> {
+    context_loader::load_config().map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Print a tool's text output, failing if the tool reported an error
+fn print_tool_result(result: Result<CallToolResult, McpError>) -> anyhow::Result<()> {
+    let result = result.map_err(|e| anyhow::anyhow!("{}", e.message))?;
+    let text = result
+        .content
+        .iter()
+        .filter_map(|content| content.as_text().map(|text| text.text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if result.is_error == Some(true) {
+        bail!("{}", text);
+    }
+    println!("{}", text);
+    Ok(())
+}
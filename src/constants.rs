@@ -1 +1,7 @@
 pub const SPARKLE_DIR: &str = ".sparkle";
+
+/// Per-workspace directory for working memory, checkpoints and workspace insights
+pub const SPARKLE_SPACE_DIR: &str = ".sparkle-space";
+
+/// Config file name, in both `~/.sparkle` and `.sparkle-space`
+pub const CONFIG_FILE: &str = "config.toml";
@@ -1,7 +1,9 @@
-use crate::constants::SPARKLE_DIR;
-use crate::types::Config;
+use crate::constants::{CONFIG_FILE, SPARKLE_DIR, SPARKLE_SPACE_DIR};
+use crate::history::History;
+use crate::insights::{INSIGHTS_FILE, load_records, write_records};
+use crate::types::{AiConfig, Config, HumanConfig, InsightType, SparklerConfig, WorkspaceConfig};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn load_config() -> Result<Config, Box<dyn std::error::Error>> {
     let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
@@ -25,6 +27,59 @@ name = "Sparkle"
     }
 }
 
+/// Load a workspace's `.sparkle-space/config.toml`
+///
+/// A missing file gives the defaults; an invalid one is logged and ignored so it
+/// never breaks embodiment.
+pub fn load_workspace_config(workspace_path: &Path) -> WorkspaceConfig {
+    let config_file = workspace_path.join(SPARKLE_SPACE_DIR).join(CONFIG_FILE);
+    let Ok(config_str) = fs::read_to_string(&config_file) else {
+        return WorkspaceConfig::default();
+    };
+    toml::from_str(&config_str).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring invalid {}: {}", config_file.display(), e);
+        WorkspaceConfig::default()
+    })
+}
+
+/// The sparkler a workspace is pinned to, if it is one of the configured sparklers
+pub fn pinned_sparkler(config: &Config, workspace: &WorkspaceConfig) -> Option<String> {
+    let name = workspace.sparkler.as_deref()?.trim();
+    if !config.is_multi_sparkler() {
+        // A single sparkler is always the one embodied
+        return None;
+    }
+    if config.get_all_sparkler_names().iter().any(|n| n == name) {
+        Some(name.to_string())
+    } else {
+        tracing::warn!(
+            "Workspace is pinned to sparkler '{}', which isn't configured; using the default",
+            name
+        );
+        None
+    }
+}
+
+/// The workspace a call applies to: `workspace_path` unless it's omitted or
+/// "current", otherwise the server's working directory
+pub fn resolve_workspace_path(workspace_path: Option<String>) -> Option<String> {
+    workspace_path.filter(|path| path != "current").or_else(|| {
+        std::env::current_dir()
+            .ok()
+            .map(|dir| dir.to_string_lossy().into_owned())
+    })
+}
+
+/// The sparkler to embody: the one explicitly requested (e.g. `--sparkler`),
+/// otherwise the one the workspace is pinned to
+pub fn requested_or_pinned_sparkler(
+    requested: Option<String>,
+    config: &Config,
+    workspace: &WorkspaceConfig,
+) -> Option<String> {
+    requested.or_else(|| pinned_sparkler(config, workspace))
+}
+
 /// Get the context directory path based on single vs multi-sparkler mode
 ///
 /// Returns:
@@ -73,6 +128,269 @@ pub fn get_context_dir(
     }
 }
 
+/// Convert a single-sparkler setup to multi-sparkler mode
+///
+/// Moves the current sparkler's context files and insight records into
+/// `sparklers/{name}/` and replaces `[ai]` with a `[[sparklers]]` entry; the
+/// caller writes the config.
+/// Returns the migrated sparkler's name, or None if already multi-sparkler.
+pub fn migrate_to_multi_sparkler(
+    config: &mut Config,
+    sparkle_dir: &Path,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if config.is_multi_sparkler() {
+        return Ok(None);
+    }
+
+    let current_name = config
+        .get_single_sparkler_name()
+        .unwrap_or_else(|| "Sparkle".to_string());
+
+    // Create directory for current sparkler
+    let current_sparkler_dir = sparkle_dir.join("sparklers").join(&current_name);
+    fs::create_dir_all(&current_sparkler_dir)
+        .map_err(|e| format!("Failed to create sparkler directory: {}", e))?;
+
+    // Move existing context files to current sparkler directory
+    let files_to_move = vec![
+        "sparkler-identity.md",
+        "collaboration-context.md",
+        "collaboration-evolution.md",
+        "pattern-anchors.md",
+    ];
+
+    for file in files_to_move {
+        let src = sparkle_dir.join(file);
+        let dst = current_sparkler_dir.join(file);
+        if src.exists() {
+            fs::rename(&src, &dst).map_err(|e| format!("Failed to move {}: {}", file, e))?;
+        }
+    }
+
+    // The sparkler's insight records follow its markdown files; cross-workspace
+    // notes stay with workspace-map.md, which all sparklers share
+    let (shared, own): (Vec<_>, Vec<_>) = load_records(sparkle_dir)
+        .into_iter()
+        .partition(|r| r.insight_type == InsightType::WorkspaceInsight);
+    if !own.is_empty() {
+        let mut records = load_records(&current_sparkler_dir);
+        records.extend(own);
+        write_records(&current_sparkler_dir, &records)
+            .map_err(|e| format!("Failed to move {}: {}", INSIGHTS_FILE, e))?;
+        if shared.is_empty() {
+            fs::remove_file(sparkle_dir.join(INSIGHTS_FILE))?;
+        } else {
+            write_records(sparkle_dir, &shared)
+                .map_err(|e| format!("Failed to rewrite {}: {}", INSIGHTS_FILE, e))?;
+        }
+    }
+
+    let settings = config
+        .ai
+        .as_ref()
+        .map(|ai| ai.settings.clone())
+        .unwrap_or_default();
+    config.sparklers = Some(vec![SparklerConfig {
+        name: current_name.clone(),
+        default: true,
+        settings,
+    }]);
+    config.ai = None; // Remove old [ai] section
+
+    Ok(Some(current_name))
+}
+
+/// Directory of archived sparklers, relative to the sparkle directory
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// Move a sparkler out of the active setup, into `archive/{name}-{timestamp}/`
+///
+/// The directory keeps all of the sparkler's files plus its config entry in
+/// `sparkler.toml`; moving it back to `sparklers/` and re-adding the entry
+/// restores it. If the archived sparkler was the default, the first remaining
+/// one becomes the default. Writes config.toml and returns the archive directory.
+pub fn archive_sparkler(
+    config: &mut Config,
+    sparkle_dir: &Path,
+    name: &str,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let sparklers = config
+        .sparklers
+        .as_mut()
+        .ok_or("Archiving needs a multi-sparkler setup; the only sparkler can't be archived")?;
+    let index = sparklers
+        .iter()
+        .position(|s| s.name == name)
+        .ok_or_else(|| format!("Sparkler '{}' not found in config", name))?;
+    if sparklers.len() == 1 {
+        return Err(format!("'{}' is the only sparkler and can't be archived", name).into());
+    }
+
+    let archive_dir = sparkle_dir.join(ARCHIVE_DIR).join(format!(
+        "{}-{}",
+        name,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::create_dir_all(archive_dir.parent().unwrap_or(sparkle_dir))?;
+    let sparkler_dir = sparkle_dir.join("sparklers").join(name);
+    if sparkler_dir.exists() {
+        fs::rename(&sparkler_dir, &archive_dir)
+            .map_err(|e| format!("Failed to move {}: {}", sparkler_dir.display(), e))?;
+    } else {
+        fs::create_dir_all(&archive_dir)?;
+    }
+
+    let archived = sparklers.remove(index);
+    fs::write(
+        archive_dir.join("sparkler.toml"),
+        toml::to_string_pretty(&archived)?,
+    )?;
+    if archived.default {
+        sparklers[0].default = true;
+    }
+    let history = History::new(sparkle_dir, config.history.clone().unwrap_or_default());
+    history.write(
+        &sparkle_dir.join("config.toml"),
+        toml::to_string_pretty(&config)?,
+    )?;
+
+    Ok(archive_dir)
+}
+
+/// What `init_sparkle` sets up
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// The collaborator's name, `[human] name`
+    pub human_name: String,
+    /// Name of the first sparkler (default "Sparkle")
+    pub sparkler_name: Option<String>,
+    /// Start in multi-sparkler mode, with the sparkler's files in `sparklers/{name}/`
+    pub multi: bool,
+    /// Collaborator profile to start with instead of the template
+    pub profile: Option<String>,
+    /// Replace an existing config.toml and collaborator profile
+    pub force: bool,
+}
+
+/// Set up a sparkle directory: config.toml, collaborator profile and the sparkler's starter files
+///
+/// Refuses to replace an existing config.toml or profile unless `force` is set;
+/// replaced files are kept as versions in `.history/`. Starter files that already
+/// exist are left alone, so pattern anchors and evolution survive a forced init.
+/// Returns the files written.
+pub fn init_sparkle(
+    sparkle_dir: &Path,
+    options: &InitOptions,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let human_name = options.human_name.trim();
+    if human_name.is_empty() {
+        return Err("A name for the collaborator is required".into());
+    }
+    let sparkler_name = options
+        .sparkler_name
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("Sparkle");
+    if sparkler_name.is_empty()
+        || sparkler_name.contains(['/', '\\'])
+        || sparkler_name.starts_with('.')
+    {
+        return Err(format!("'{}' is not a valid sparkler name", sparkler_name).into());
+    }
+
+    let config_path = sparkle_dir.join(CONFIG_FILE);
+    let profile_path = sparkle_dir.join("collaborator-profile.md");
+    let existing: Vec<&str> = [&config_path, &profile_path]
+        .iter()
+        .filter(|path| path.exists())
+        .filter_map(|path| path.file_name()?.to_str())
+        .collect();
+    if !existing.is_empty() && !options.force {
+        return Err(format!(
+            "{} already has {}; refusing to overwrite them without force",
+            sparkle_dir.display(),
+            existing.join(" and ")
+        )
+        .into());
+    }
+
+    let mut config = Config {
+        human: HumanConfig {
+            name: human_name.to_string(),
+            pronouns: None,
+            timezone: None,
+            language: None,
+            role: None,
+            team: None,
+            preferences: Default::default(),
+        },
+        ai: None,
+        sparklers: None,
+        http: None,
+        history: None,
+        sync: None,
+        templates: None,
+        includes: None,
+    };
+    let context_dir = if options.multi {
+        config.sparklers = Some(vec![SparklerConfig {
+            name: sparkler_name.to_string(),
+            default: true,
+            settings: Default::default(),
+        }]);
+        sparkle_dir.join("sparklers").join(sparkler_name)
+    } else {
+        config.ai = Some(AiConfig {
+            name: sparkler_name.to_string(),
+            settings: Default::default(),
+        });
+        sparkle_dir.to_path_buf()
+    };
+    fs::create_dir_all(&context_dir)
+        .map_err(|e| format!("Failed to create {}: {}", context_dir.display(), e))?;
+
+    // Keep what a forced init replaces, with the retention of the old config if it parses
+    let old_config: Option<Config> = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok());
+    let history = History::new(
+        sparkle_dir,
+        old_config.and_then(|c| c.history).unwrap_or_default(),
+    );
+    let profile = options
+        .profile
+        .clone()
+        .unwrap_or_else(|| create_collaborator_profile_template(human_name));
+    history.write(&config_path, toml::to_string_pretty(&config)?)?;
+    history.write(&profile_path, profile)?;
+
+    let mut written = vec![config_path, profile_path];
+    for (file, content) in starter_files(sparkler_name) {
+        let path = context_dir.join(file);
+        if !path.exists() {
+            fs::write(&path, content)?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// Starting collaborator profile for `human_name`
+pub fn create_collaborator_profile_template(human_name: &str) -> String {
+    format!(
+        "# {} - Collaborator Profile\n\n\
+         **What this file covers:** Everything needed to collaborate effectively with {} - who they are, their expertise, working style, and collaboration protocols.\n\n\
+         ---\n\n\
+         ## Professional Background & Expertise\n\n\
+         [Add your professional background, technical expertise, and career highlights]\n\n\
+         ## Working Style & Collaboration Patterns\n\n\
+         [Add your working style, communication preferences, and collaboration patterns]\n\n\
+         ## Collaboration Protocols\n\n\
+         [Add any specific protocols or guidelines for working together]\n",
+        human_name, human_name
+    )
+}
+
 /// Create sparkler identity template with given name
 pub fn create_sparkler_identity_template(sparkler_name: &str) -> String {
     format!(
@@ -99,9 +417,16 @@ pub fn create_starter_files(
     sparkler_dir: &PathBuf,
     sparkler_name: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    for (file, content) in starter_files(sparkler_name) {
+        fs::write(sparkler_dir.join(file), content)?;
+    }
+    Ok(())
+}
+
+/// File names and contents of a new sparkler's starter files
+fn starter_files(sparkler_name: &str) -> [(&'static str, String); 3] {
     // sparkler-identity.md
     let sparkler_identity = create_sparkler_identity_template(sparkler_name);
-    fs::write(sparkler_dir.join("sparkler-identity.md"), sparkler_identity)?;
 
     // collaboration-evolution.md
     let collaboration_evolution = r#"# Collaboration Evolution
@@ -116,10 +441,6 @@ pub fn create_starter_files(
 
 [Document significant collaborative discoveries]
 "#;
-    fs::write(
-        sparkler_dir.join("collaboration-evolution.md"),
-        collaboration_evolution,
-    )?;
 
     // pattern-anchors.md
     let pattern_anchors = r#"# Pattern Anchors
@@ -130,9 +451,15 @@ pub fn create_starter_files(
 
 [Add pattern anchors as they emerge from collaboration]
 "#;
-    fs::write(sparkler_dir.join("pattern-anchors.md"), pattern_anchors)?;
 
-    Ok(())
+    [
+        ("sparkler-identity.md", sparkler_identity),
+        (
+            "collaboration-evolution.md",
+            collaboration_evolution.to_string(),
+        ),
+        ("pattern-anchors.md", pattern_anchors.to_string()),
+    ]
 }
 
 /// Get the workspace-specific directory path based on single vs multi-sparkler mode
@@ -0,0 +1,654 @@
+//! Health check for a Sparkle setup
+//!
+//! `diagnose` looks for the usual reasons embodiment goes wrong: a config
+//! that doesn't parse, sparkler directories out of step with the config,
+//! identity files still holding the starter template, logs grown too large,
+//! broken workspace state and leftover backups. Every finding says how to
+//! fix it; the ones that can be fixed without losing anything come with an
+//! automatic fix that `apply_fixes` carries out.
+
+use crate::constants::{CONFIG_FILE, SPARKLE_SPACE_DIR};
+use crate::context_loader::create_starter_files;
+use crate::history::{HISTORY_DIR, History};
+use crate::includes::{IncludeSet, load_includes};
+use crate::types::{Config, SparklerConfig, WorkspaceConfig};
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Anchor, evolution and map files larger than this slow down every embodiment
+pub const LARGE_FILE_BYTES: u64 = 64 * 1024;
+
+/// Working memory untouched for this long is probably stale
+pub const STALE_WORKING_MEMORY: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Marker left in starter identity files until they are filled in
+const TEMPLATE_MARKER: &str = "*Brief:";
+
+/// Marker left in the starter collaborator profile
+const PROFILE_TEMPLATE_MARKER: &str = "[Add your professional background";
+
+/// Per-sparkler files that grow with every saved insight
+const GROWING_FILES: &[&str] = &["pattern-anchors.md", "collaboration-evolution.md"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Embodiment is broken or loses context
+    Error,
+    /// Something is likely wrong or degrading sessions
+    Warning,
+    /// Worth knowing, nothing is broken
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            Severity::Error => "❌",
+            Severity::Warning => "⚠️",
+            Severity::Info => "ℹ️",
+        }
+    }
+}
+
+/// A fix `apply_fixes` can make without asking
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoFix {
+    /// Create a configured sparkler's missing directory with starter files
+    CreateSparklerDir(String),
+    /// Add a sparkler directory found on disk to config.toml
+    RegisterSparkler(String),
+    /// Move `name.YYYYMMDD-HHMMSS.ext` backups of a file into `.history/`
+    MigrateBackups(PathBuf),
+}
+
+impl fmt::Display for AutoFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutoFix::CreateSparklerDir(name) => {
+                write!(f, "create sparklers/{}/ with starter files", name)
+            }
+            AutoFix::RegisterSparkler(name) => write!(f, "add {} to config.toml", name),
+            AutoFix::MigrateBackups(file) => {
+                write!(f, "move the backups of {} into .history/", file.display())
+            }
+        }
+    }
+}
+
+/// One problem found by `diagnose`
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Short name of the check, e.g. "config"
+    pub check: &'static str,
+    pub message: String,
+    /// What to do about it
+    pub suggestion: String,
+    pub auto_fix: Option<AutoFix>,
+}
+
+impl Finding {
+    fn new(
+        severity: Severity,
+        check: &'static str,
+        message: impl Into<String>,
+        suggestion: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            check,
+            message: message.into(),
+            suggestion: suggestion.into(),
+            auto_fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: AutoFix) -> Self {
+        self.auto_fix = Some(fix);
+        self
+    }
+}
+
+/// Everything `diagnose` found
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub findings: Vec<Finding>,
+}
+
+impl Report {
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|f| f.severity == severity)
+            .count()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        if self.findings.is_empty() {
+            return "✅ **Sparkle doctor**: no problems found\n".to_string();
+        }
+        let mut out = format!(
+            "🩺 **Sparkle doctor**: {} error(s), {} warning(s), {} note(s)\n\n",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.count(Severity::Info)
+        );
+        for finding in &self.findings {
+            out.push_str(&format!(
+                "{} **{}** ({}): {}\n   → {}\n",
+                finding.severity.icon(),
+                finding.check,
+                finding.severity.as_str(),
+                finding.message,
+                finding.suggestion
+            ));
+            if let Some(fix) = &finding.auto_fix {
+                out.push_str(&format!("   🔧 Automatic fix: {}\n", fix));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Check the sparkle directory and, optionally, a workspace
+pub fn diagnose(sparkle_dir: &Path, workspace: Option<&Path>) -> Report {
+    let mut findings = Vec::new();
+
+    if !sparkle_dir.exists() {
+        findings.push(Finding::new(
+            Severity::Error,
+            "setup",
+            format!("{} doesn't exist", sparkle_dir.display()),
+            "Run `sparkle-mcp init --human-name <your name>` or the setup_sparkle tool",
+        ));
+        return Report { findings };
+    }
+
+    let config = check_config(sparkle_dir, &mut findings);
+    check_profile(sparkle_dir, &mut findings);
+    if let Some(config) = &config {
+        let context_dirs = check_sparklers(sparkle_dir, config, &mut findings);
+        for (name, dir) in &context_dirs {
+            check_context_dir(name, dir, &mut findings);
+        }
+        check_includes(
+            "includes",
+            config
+                .includes
+                .as_ref()
+                .map(|includes| IncludeSet::new(sparkle_dir, includes)),
+            &mut findings,
+        );
+    }
+    check_growing_file(&sparkle_dir.join("workspace-map.md"), &mut findings);
+    check_backups(sparkle_dir, config.as_ref(), &mut findings);
+    check_evolution_docs(sparkle_dir, &mut findings);
+    if let Some(workspace) = workspace {
+        check_workspace(workspace, config.as_ref(), &mut findings);
+    }
+
+    findings.sort_by_key(|f| f.severity);
+    Report { findings }
+}
+
+fn check_config(sparkle_dir: &Path, findings: &mut Vec<Finding>) -> Option<Config> {
+    let path = sparkle_dir.join(CONFIG_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "config",
+                format!(
+                    "{} is missing, so defaults are used (human \"User\")",
+                    path.display()
+                ),
+                "Run `sparkle-mcp init --human-name <your name>` or the setup_sparkle tool",
+            ));
+            return None;
+        }
+        Err(e) => {
+            findings.push(Finding::new(
+                Severity::Error,
+                "config",
+                format!("Can't read {}: {}", path.display(), e),
+                "Check the file's permissions",
+            ));
+            return None;
+        }
+    };
+    match toml::from_str::<Config>(&text) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            findings.push(Finding::new(
+                Severity::Error,
+                "config",
+                format!(
+                    "{} doesn't parse, so every tool fails: {}",
+                    path.display(),
+                    e.to_string().trim()
+                ),
+                "Fix the TOML at the reported line; `[human] name` is the only required setting",
+            ));
+            None
+        }
+    }
+}
+
+fn check_profile(sparkle_dir: &Path, findings: &mut Vec<Finding>) {
+    let path = sparkle_dir.join("collaborator-profile.md");
+    match fs::read_to_string(&path) {
+        Ok(profile) if profile.contains(PROFILE_TEMPLATE_MARKER) => {
+            findings.push(Finding::new(
+                Severity::Info,
+                "profile",
+                "The collaborator profile is still the starter template",
+                "Fill it in with `sparkle-mcp profile edit`, or ask Sparkle to use the enrich_profile prompt",
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => findings.push(Finding::new(
+            Severity::Error,
+            "profile",
+            format!("Can't read {}: {}", path.display(), e),
+            "Run setup again (it writes a starter profile) or restore it with restore_version",
+        )),
+    }
+}
+
+/// Compare configured sparklers with the directories on disk; returns the
+/// context directories that exist, by sparkler name
+fn check_sparklers(
+    sparkle_dir: &Path,
+    config: &Config,
+    findings: &mut Vec<Finding>,
+) -> Vec<(String, PathBuf)> {
+    let sparklers_dir = sparkle_dir.join("sparklers");
+    let configured = config.get_all_sparkler_names();
+
+    if !config.is_multi_sparkler() {
+        let name = config
+            .get_single_sparkler_name()
+            .unwrap_or_else(|| "Sparkle".to_string());
+        if sparklers_dir.is_dir() {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "sparklers",
+                format!(
+                    "{} exists but config.toml has a single [ai] sparkler, so it is ignored",
+                    sparklers_dir.display()
+                ),
+                "Add [[sparklers]] entries for them, or move the directory away if it's left over",
+            ));
+        }
+        return vec![(name, sparkle_dir.to_path_buf())];
+    }
+
+    let mut dirs = Vec::new();
+    for name in &configured {
+        let dir = sparklers_dir.join(name);
+        if dir.is_dir() {
+            dirs.push((name.clone(), dir));
+        } else {
+            findings.push(
+                Finding::new(
+                    Severity::Warning,
+                    "sparklers",
+                    format!("Sparkler {} has no directory at {}", name, dir.display()),
+                    "Embodying it creates empty starter files; restore its files if they were moved",
+                )
+                .with_fix(AutoFix::CreateSparklerDir(name.clone())),
+            );
+        }
+    }
+
+    let on_disk = fs::read_dir(&sparklers_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.') && !configured.contains(name));
+    for name in on_disk {
+        findings.push(
+            Finding::new(
+                Severity::Warning,
+                "sparklers",
+                format!("sparklers/{}/ exists but isn't in config.toml", name),
+                "Register it, or archive the directory if it's no longer used",
+            )
+            .with_fix(AutoFix::RegisterSparkler(name)),
+        );
+    }
+    dirs
+}
+
+fn check_context_dir(name: &str, dir: &Path, findings: &mut Vec<Finding>) {
+    let identity = dir.join("sparkler-identity.md");
+    if fs::read_to_string(&identity).is_ok_and(|text| text.contains(TEMPLATE_MARKER)) {
+        findings.push(Finding::new(
+            Severity::Info,
+            "identity",
+            format!(
+                "{}'s sparkler-identity.md still has template placeholders",
+                name
+            ),
+            "Use the sparkler_identity prompt, then update_sparkler_identity to save it",
+        ));
+    }
+    for file in GROWING_FILES {
+        check_growing_file(&dir.join(file), findings);
+    }
+}
+
+fn check_growing_file(path: &Path, findings: &mut Vec<Finding>) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    if metadata.len() > LARGE_FILE_BYTES {
+        findings.push(Finding::new(
+            Severity::Warning,
+            "size",
+            format!(
+                "{} is {} KiB and is loaded into every session",
+                path.display(),
+                metadata.len() / 1024
+            ),
+            "Retract outdated insights (retract_insight) or move old entries into a file that isn't loaded",
+        ));
+    }
+}
+
+fn check_includes(check: &'static str, set: Option<IncludeSet>, findings: &mut Vec<Finding>) {
+    let Some(set) = set else {
+        return;
+    };
+    let loaded = load_includes(&set);
+    for warning in loaded.warnings {
+        findings.push(Finding::new(
+            Severity::Warning,
+            check,
+            warning,
+            "Fix or remove the include",
+        ));
+    }
+    if !loaded.skipped.is_empty() {
+        findings.push(Finding::new(
+            Severity::Info,
+            check,
+            format!(
+                "{} included file(s) skipped because max_total_bytes ({}) was reached",
+                loaded.skipped.len(),
+                set.max_total_bytes
+            ),
+            "Raise max_total_bytes, lower per-file caps or limit the globs",
+        ));
+    }
+}
+
+/// Pre-history `name.YYYYMMDD-HHMMSS.ext` backups, and history kept for files that are gone
+fn check_backups(sparkle_dir: &Path, config: Option<&Config>, findings: &mut Vec<Finding>) {
+    let mut dirs = vec![sparkle_dir.to_path_buf()];
+    if let Some(config) = config {
+        dirs.extend(
+            config
+                .get_all_sparkler_names()
+                .iter()
+                .map(|name| sparkle_dir.join("sparklers").join(name)),
+        );
+    }
+    let mut originals: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| legacy_backup_original(&entry.path()))
+        .collect();
+    originals.sort();
+    originals.dedup();
+    for original in originals {
+        findings.push(
+            Finding::new(
+                Severity::Warning,
+                "backups",
+                format!(
+                    "Old-style backups of {} are lying next to it",
+                    original.display()
+                ),
+                "Move them into the version history, where list_versions and restore_version see them",
+            )
+            .with_fix(AutoFix::MigrateBackups(original)),
+        );
+    }
+
+    let history_dir = sparkle_dir.join(HISTORY_DIR);
+    for versions_dir in versions_dirs(&history_dir) {
+        let Ok(relative) = versions_dir.strip_prefix(&history_dir) else {
+            continue;
+        };
+        if !sparkle_dir.join(relative).exists() {
+            findings.push(Finding::new(
+                Severity::Info,
+                "backups",
+                format!(
+                    "{} holds versions of {}, which no longer exists",
+                    versions_dir.display(),
+                    relative.display()
+                ),
+                "Delete that directory if the file was renamed or removed on purpose",
+            ));
+        }
+    }
+}
+
+/// The file a `name.YYYYMMDD-HHMMSS.ext` backup belongs to
+fn legacy_backup_original(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let (rest, extension) = name.rsplit_once('.')?;
+    let (stem, timestamp) = rest.rsplit_once('.')?;
+    NaiveDateTime::parse_from_str(timestamp, "%Y%m%d-%H%M%S").ok()?;
+    Some(path.with_file_name(format!("{}.{}", stem, extension)))
+}
+
+/// Directories under `.history/` that directly hold versions
+fn versions_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let (files, subdirs): (Vec<PathBuf>, Vec<PathBuf>) = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .partition(|path| path.is_file());
+    let mut found: Vec<PathBuf> = subdirs.iter().flat_map(|d| versions_dirs(d)).collect();
+    if !files.is_empty() {
+        found.push(dir.to_path_buf());
+    }
+    found.sort();
+    found
+}
+
+fn check_evolution_docs(sparkle_dir: &Path, findings: &mut Vec<Finding>) {
+    let evolution_dir = sparkle_dir.join("evolution");
+    let Ok(entries) = fs::read_dir(&evolution_dir) else {
+        return;
+    };
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if !path.is_file() || path.extension().is_none_or(|ext| ext != "md") {
+            continue;
+        }
+        if let Err(e) = fs::read_to_string(&path) {
+            findings.push(Finding::new(
+                Severity::Warning,
+                "evolution",
+                format!(
+                    "{} can't be read ({}), so load_evolution skips it",
+                    path.display(),
+                    e
+                ),
+                "Check its permissions and that it's UTF-8 text",
+            ));
+        }
+    }
+}
+
+fn check_workspace(workspace: &Path, config: Option<&Config>, findings: &mut Vec<Finding>) {
+    let space = workspace.join(SPARKLE_SPACE_DIR);
+    if !space.is_dir() {
+        return;
+    }
+
+    let config_path = space.join(CONFIG_FILE);
+    if let Ok(text) = fs::read_to_string(&config_path) {
+        match toml::from_str::<WorkspaceConfig>(&text) {
+            Ok(workspace_config) => {
+                if let (Some(pinned), Some(config)) = (&workspace_config.sparkler, config)
+                    && config.is_multi_sparkler()
+                    && !config.get_all_sparkler_names().contains(pinned)
+                {
+                    findings.push(Finding::new(
+                        Severity::Warning,
+                        "workspace",
+                        format!(
+                            "{} pins sparkler {}, which isn't configured, so the default is used",
+                            config_path.display(),
+                            pinned
+                        ),
+                        "Fix the name or create the sparkler",
+                    ));
+                }
+                if !workspace_config.enabled {
+                    findings.push(Finding::new(
+                        Severity::Info,
+                        "workspace",
+                        format!("Sparkle is turned off in {}", workspace.display()),
+                        "Remove `enabled = false` to turn it back on",
+                    ));
+                }
+                check_includes(
+                    "workspace includes",
+                    workspace_config
+                        .includes
+                        .as_ref()
+                        .map(|includes| IncludeSet::new(workspace, includes).confined()),
+                    findings,
+                );
+            }
+            Err(e) => findings.push(Finding::new(
+                Severity::Error,
+                "workspace",
+                format!(
+                    "{} doesn't parse and is ignored: {}",
+                    config_path.display(),
+                    e.to_string().trim()
+                ),
+                "Fix the TOML at the reported line",
+            )),
+        }
+    }
+
+    let memory_path = space.join("working-memory.json");
+    match fs::read_to_string(&memory_path) {
+        Ok(text) => {
+            if let Err(e) = serde_json::from_str::<serde_json::Value>(&text) {
+                findings.push(Finding::new(
+                    Severity::Error,
+                    "working memory",
+                    format!("{} isn't valid JSON: {}", memory_path.display(), e),
+                    "Fix it by hand, or say \"checkpoint\" to have Sparkle write it afresh",
+                ));
+            }
+            let age = fs::metadata(&memory_path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|mtime| SystemTime::now().duration_since(mtime).ok());
+            if age.is_some_and(|age| age > STALE_WORKING_MEMORY) {
+                findings.push(Finding::new(
+                    Severity::Info,
+                    "working memory",
+                    format!(
+                        "{} hasn't been updated in {} days",
+                        memory_path.display(),
+                        age.unwrap_or_default().as_secs() / 86_400
+                    ),
+                    "Its focus and next steps may be outdated; checkpoint after the next session",
+                ));
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => findings.push(Finding::new(
+            Severity::Error,
+            "working memory",
+            format!("Can't read {}: {}", memory_path.display(), e),
+            "Check the file's permissions",
+        )),
+    }
+}
+
+/// Carry out the automatic fixes of a report, returning what happened to each
+pub fn apply_fixes(sparkle_dir: &Path, report: &Report) -> Vec<(AutoFix, Result<()>)> {
+    report
+        .findings
+        .iter()
+        .filter_map(|finding| finding.auto_fix.clone())
+        .map(|fix| {
+            let result = apply_fix(sparkle_dir, &fix);
+            (fix, result)
+        })
+        .collect()
+}
+
+fn apply_fix(sparkle_dir: &Path, fix: &AutoFix) -> Result<()> {
+    match fix {
+        AutoFix::CreateSparklerDir(name) => {
+            let dir = sparkle_dir.join("sparklers").join(name);
+            fs::create_dir_all(&dir)?;
+            create_starter_files(&dir, name).map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
+        AutoFix::RegisterSparkler(name) => {
+            let config_path = sparkle_dir.join(CONFIG_FILE);
+            let mut config: Config = toml::from_str(&fs::read_to_string(&config_path)?)
+                .context("config.toml doesn't parse")?;
+            let sparklers = config.sparklers.get_or_insert_with(Vec::new);
+            if !sparklers.iter().any(|s| &s.name == name) {
+                sparklers.push(SparklerConfig {
+                    name: name.clone(),
+                    default: false,
+                    settings: Default::default(),
+                });
+            }
+            let history = History::new(sparkle_dir, config.history.clone().unwrap_or_default());
+            history.write(&config_path, toml::to_string_pretty(&config)?)?;
+        }
+        AutoFix::MigrateBackups(file) => {
+            let config: Option<Config> = fs::read_to_string(sparkle_dir.join(CONFIG_FILE))
+                .ok()
+                .and_then(|text| toml::from_str(&text).ok());
+            let history = History::new(
+                sparkle_dir,
+                config.and_then(|c| c.history).unwrap_or_default(),
+            );
+            history.migrate_legacy_backups(file)?;
+        }
+    }
+    Ok(())
+}
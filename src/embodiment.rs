@@ -3,12 +3,94 @@
 //! This module provides the core embodiment content generation that can be used by:
 //! - MCP tool handler (returns as tool result)
 //! - ACP proxy (injects as initial prompt)
+//! - `sparkle-mcp render` (prints it, with the size of each section)
 
-use crate::context_loader::{create_sparkler_identity_template, get_context_dir, load_config};
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::{
+    create_sparkler_identity_template, get_context_dir, load_config, load_workspace_config,
+    requested_or_pinned_sparkler, resolve_workspace_path,
+};
+use crate::includes::{Include, IncludeSet, IncludesConfig, load_includes};
+use crate::insights::{
+    canonical_workspace, inactive_ids, other_workspace_ids, store_dirs, strip_entries,
+};
 use crate::sparkle_loader::load_sparkle_definition;
-use crate::types::FullEmbodimentParams;
+use crate::types::{FullEmbodimentParams, HumanConfig, WorkspaceConfig};
 use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::HashSet;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Embodiment modes: everything, or without the collaboration evolution history
+pub const EMBODIMENT_MODES: &[&str] = &["complete", "distilled"];
+
+/// Limits that override the configured ones for a single embodiment
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Checkpoints to load, instead of the workspace's or sparkler's setting
+    pub checkpoint_count: Option<usize>,
+    /// Per-file cap for every include set
+    pub max_file_bytes: Option<usize>,
+    /// Total cap for every include set
+    pub max_total_bytes: Option<usize>,
+}
+
+impl RenderOptions {
+    fn apply(&self, mut includes: IncludesConfig) -> IncludesConfig {
+        if let Some(max) = self.max_file_bytes {
+            includes.max_file_bytes = max;
+        }
+        if let Some(max) = self.max_total_bytes {
+            includes.max_total_bytes = max;
+        }
+        includes
+    }
+}
+
+/// One part of an embodiment, e.g. the pattern anchors
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbodimentSection {
+    pub name: &'static str,
+    /// File the section was loaded from, if it comes from a single file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    pub bytes: usize,
+    pub content: String,
+}
+
+/// An embodiment, section by section, in the order they are sent
+#[derive(Debug, Clone, Serialize)]
+pub struct Embodiment {
+    /// Sparkler embodied; `None` for the default one
+    pub sparkler: Option<String>,
+    pub mode: String,
+    pub workspace: Option<String>,
+    pub sections: Vec<EmbodimentSection>,
+}
+
+impl Embodiment {
+    fn push(&mut self, name: &'static str, source: Option<PathBuf>, content: impl Into<String>) {
+        let content = content.into();
+        self.sections.push(EmbodimentSection {
+            name,
+            source,
+            bytes: content.len(),
+            content,
+        });
+    }
+
+    /// Size of the whole embodiment in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.sections.iter().map(|s| s.bytes).sum()
+    }
+
+    /// The embodiment as sent to the agent
+    pub fn to_markdown(&self) -> String {
+        self.sections.iter().map(|s| s.content.as_str()).collect()
+    }
+}
 
 /// Generate the full embodiment content string
 ///
@@ -24,15 +106,60 @@ use std::fs;
 ///
 /// A Result containing the complete embodiment content as a markdown string
 pub fn generate_embodiment_content(params: FullEmbodimentParams) -> Result<String> {
-    let _mode = params.mode.unwrap_or_else(|| "complete".to_string());
-    let workspace_path = params
-        .workspace_path
-        .unwrap_or_else(|| "current".to_string());
-    let sparkler_name = params.sparkler.as_deref();
+    Ok(render_embodiment(params, &RenderOptions::default())?.to_markdown())
+}
+
+/// Assemble the embodiment section by section, with `options` overriding the configured limits
+pub fn render_embodiment(
+    params: FullEmbodimentParams,
+    options: &RenderOptions,
+) -> Result<Embodiment> {
+    // Without a workspace the server's working directory is the workspace,
+    // as for session_checkpoint
+    let workspace_path =
+        resolve_workspace_path(params.workspace_path).unwrap_or_else(|| "current".to_string());
 
     // Load user configuration
     let config = load_config().map_err(|e| anyhow::anyhow!("Failed to load user config: {}", e))?;
 
+    // A workspace can opt out of Sparkle or pin the sparkler embodied there
+    let workspace_config = if workspace_path != "current" {
+        load_workspace_config(Path::new(&workspace_path))
+    } else {
+        WorkspaceConfig::default()
+    };
+    let sparkler_name = requested_or_pinned_sparkler(params.sparkler, &config, &workspace_config);
+    let sparkler_name = sparkler_name.as_deref();
+
+    let settings = config.sparkler_settings(sparkler_name);
+
+    // An explicit mode wins over the sparkler's default
+    let mode = params
+        .mode
+        .or_else(|| settings.embodiment_mode.clone())
+        .unwrap_or_else(|| "complete".to_string());
+
+    let mut embodiment = Embodiment {
+        sparkler: sparkler_name.map(str::to_string),
+        mode: mode.clone(),
+        workspace: (workspace_path != "current").then(|| workspace_path.clone()),
+        sections: Vec::new(),
+    };
+    if !workspace_config.enabled {
+        embodiment.push(
+            "disabled",
+            None,
+            format!(
+                "*Sparkle is turned off for {} (`enabled = false` in .sparkle-space/config.toml)*\n",
+                workspace_path
+            ),
+        );
+        return Ok(embodiment);
+    }
+    if !EMBODIMENT_MODES.contains(&mode.as_str()) {
+        tracing::warn!("Unknown embodiment mode '{}', loading everything", mode);
+    }
+
     // Get context directory based on single vs multi-sparkler mode
     let context_dir = get_context_dir(&config, sparkler_name)
         .map_err(|e| anyhow::anyhow!("Failed to determine context directory: {}", e))?;
@@ -43,12 +170,26 @@ pub fn generate_embodiment_content(params: FullEmbodimentParams) -> Result<Strin
         fs::read_to_string(file_path).unwrap_or_else(|_| fallback.to_string())
     };
 
+    // Workspace insights are matched against the canonical workspace root
+    let current_workspace =
+        (workspace_path != "current").then(|| canonical_workspace(&workspace_path));
+
+    // Superseded and retracted insights stay in the markdown files as history,
+    // but shouldn't be re-injected into new sessions
+    let inactive_insights: HashSet<String> = store_dirs(&config, current_workspace.as_deref())
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|dir| inactive_ids(dir))
+        .collect();
+    let load_insight_file = |path: &str, fallback: &str| -> String {
+        strip_entries(&load_file(path, fallback), &inactive_insights)
+    };
+
     // Execute the embodiment sequence in proper order
-    let mut response = String::new();
 
     // Step 1: Core Universal Identity (now split into organized sections)
     let personalized_identity = load_sparkle_definition(&config, sparkler_name);
-    response.push_str(&personalized_identity);
+    embodiment.push("core identity", None, personalized_identity);
 
     // Step 2: Sparkler Identity (who am I as this Sparkler instance?)
     let identity_path = context_dir.join("sparkler-identity.md");
@@ -68,45 +209,93 @@ pub fn generate_embodiment_content(params: FullEmbodimentParams) -> Result<Strin
         let _ = fs::write(&identity_path, template);
     }
 
-    let sparkler_identity = load_file(
+    let mut sparkler_identity = load_file(
         "sparkler-identity.md",
         "*Sparkler identity would be loaded dynamically*",
     );
-    response.push_str(&sparkler_identity);
 
     // Add guidance note if file was just created or is still template
     if !identity_exists || sparkler_identity.contains("*Brief:") {
-        response.push_str("\n\n💡 **Note**: Define the essence of your Sparkler identity - use the `sparkler_identity` prompt for guidance, then the `update_sparkler_identity` tool to save it.\n\n");
+        sparkler_identity.push_str("\n\n💡 **Note**: Define the essence of your Sparkler identity - use the `sparkler_identity` prompt for guidance, then the `update_sparkler_identity` tool to save it.\n\n");
     }
+    embodiment.push("sparkler identity", Some(identity_path), sparkler_identity);
 
     // Step 3: Collaborator Profile (who the collaborator is + how to work together)
-    let collaborator_profile = load_file(
+    let mut collaborator_profile = load_file(
         "collaborator-profile.md",
         "*Collaborator profile would be loaded dynamically*",
     );
-    response.push_str(&collaborator_profile);
-
-    // Step 4: Workspace Map
-    let workspace_map = load_file(
-        "workspace-map.md",
-        "*Workspace map would be loaded dynamically*",
+    if let Some(details) = human_details(&config.human) {
+        collaborator_profile.push_str(&details);
+    }
+    embodiment.push(
+        "collaborator profile",
+        Some(context_dir.join("collaborator-profile.md")),
+        collaborator_profile,
     );
-    response.push_str(&workspace_map);
 
-    // Step 5: Collaboration Evolution
-    let collaboration_evolution = load_file(
-        "collaboration-evolution.md",
-        "*Collaboration evolution would be loaded dynamically*",
-    );
-    response.push_str(&collaboration_evolution);
+    // Step 4: Workspace Map (shared across all Sparklers)
+    // Only cross-workspace notes and the current workspace's insights are loaded;
+    // insights saved for other workspaces stay out of this session.
+    let sparkle_dir = dirs::home_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
+        .join(SPARKLE_DIR);
+    let mut excluded_map_entries = other_workspace_ids(&sparkle_dir, current_workspace.as_deref());
+    excluded_map_entries.extend(inactive_insights.iter().cloned());
+    let workspace_map_path = sparkle_dir.join("workspace-map.md");
+    let workspace_map = fs::read_to_string(&workspace_map_path)
+        .map(|map| strip_entries(&map, &excluded_map_entries))
+        .unwrap_or_else(|_| "*Workspace map would be loaded dynamically*".to_string());
+    embodiment.push("workspace map", Some(workspace_map_path), workspace_map);
+
+    // Step 5: Collaboration Evolution (left out of distilled embodiments)
+    if mode != "distilled" {
+        let collaboration_evolution = load_insight_file(
+            "collaboration-evolution.md",
+            "*Collaboration evolution would be loaded dynamically*",
+        );
+        embodiment.push(
+            "collaboration evolution",
+            Some(context_dir.join("collaboration-evolution.md")),
+            collaboration_evolution,
+        );
+    }
 
     // Step 6: Pattern Anchors
-    let pattern_anchors = load_file(
+    let pattern_anchors = load_insight_file(
         "pattern-anchors.md",
         "*Pattern anchors would be loaded dynamically*",
     );
-    response.push_str(&pattern_anchors);
-    response.push_str("\n\n---\n\n");
+    embodiment.push(
+        "pattern anchors",
+        Some(context_dir.join("pattern-anchors.md")),
+        pattern_anchors,
+    );
+
+    // Step 6b: Extra files from [includes] and the sparkler's settings
+    let includes = options.apply(config.includes.clone().unwrap_or_default());
+    let sparkler_includes = IncludeSet::new(
+        &context_dir,
+        &IncludesConfig {
+            files: Vec::new(),
+            ..includes.clone()
+        },
+    )
+    .with_includes(settings.context_files.iter().map(Include::new))
+    .with_includes(settings.includes.clone());
+    for (name, set) in [
+        ("includes", IncludeSet::new(&sparkle_dir, &includes)),
+        ("sparkler includes", sparkler_includes),
+    ] {
+        let loaded = load_includes(&set);
+        if !loaded.is_empty() {
+            embodiment.push(
+                name,
+                None,
+                format!("\n\n# Included Context\n\n{}", loaded.to_markdown(2)),
+            );
+        }
+    }
 
     // Step 7: Workspace-Specific Context
     if workspace_path != "current" {
@@ -114,63 +303,149 @@ pub fn generate_embodiment_content(params: FullEmbodimentParams) -> Result<Strin
         let workspace_sparkle_space = std::path::Path::new(&workspace_path).join(".sparkle-space");
 
         if workspace_sparkle_space.exists() {
-            response.push_str("# Workspace Context\n\n");
+            let mut workspace = "\n\n---\n\n# Workspace Context\n\n".to_string();
 
             // Add multi-sparkler workspace sharing note if in multi-sparkler mode
             if config.is_multi_sparkler() {
-                response.push_str("**Multi-Sparkler Workspace Sharing**: The `.sparkle-space/working-memory.json` tracks workspace-specific context (current focus, achievements, next steps) that's shared across all Sparklers. Different Sparklers can work on the same project - each brings their own collaborative identity while continuing the same work. The sparkler field in checkpoints shows who worked most recently, not ownership.\n\n");
+                workspace.push_str("**Multi-Sparkler Workspace Sharing**: The `.sparkle-space/working-memory.json` tracks workspace-specific context (current focus, achievements, next steps) that's shared across all Sparklers. Different Sparklers can work on the same project - each brings their own collaborative identity while continuing the same work. The sparkler field in checkpoints shows who worked most recently, not ownership.\n\n");
             }
+            embodiment.push("workspace", None, workspace);
 
             // Load working-memory.json
             let working_memory_path = workspace_sparkle_space.join("working-memory.json");
             if working_memory_path.exists() {
                 if let Ok(working_memory) = fs::read_to_string(&working_memory_path) {
-                    response.push_str("## Working Memory\n\n");
-                    response.push_str("```json\n");
-                    response.push_str(&working_memory);
-                    response.push_str("\n```\n\n");
+                    embodiment.push(
+                        "working memory",
+                        Some(working_memory_path),
+                        format!("## Working Memory\n\n```json\n{}\n```\n\n", working_memory),
+                    );
                 }
             }
 
-            // Load only the most recent checkpoint
-            let checkpoints_dir = workspace_sparkle_space.join("checkpoints");
-            if checkpoints_dir.exists() {
-                if let Ok(entries) = fs::read_dir(&checkpoints_dir) {
-                    // Collect all checkpoint files with their modification times
-                    let mut checkpoint_files: Vec<_> = entries
-                        .flatten()
-                        .map(|e| e.path())
-                        .filter(|p| p.is_file() && p.extension().map_or(false, |ext| ext == "md"))
-                        .filter_map(|path| {
-                            fs::metadata(&path)
-                                .and_then(|m| m.modified())
-                                .ok()
-                                .map(|mtime| (path, mtime))
-                        })
-                        .collect();
-
-                    // Sort by modification time
-                    checkpoint_files.sort_by_key(|(_, mtime)| *mtime);
-
-                    // Load only the most recent (last in sorted order)
-                    if let Some((latest_checkpoint, _)) = checkpoint_files.last() {
-                        if let Ok(content) = fs::read_to_string(latest_checkpoint) {
-                            response.push_str("## Checkpoints\n\n");
-                            response.push_str(&content);
-                            response.push_str("\n\n---\n\n");
-                        }
+            // Load insights saved for this workspace
+            let workspace_insights_path = workspace_sparkle_space.join("workspace-map.md");
+            if let Ok(workspace_insights) = fs::read_to_string(&workspace_insights_path) {
+                embodiment.push(
+                    "workspace insights",
+                    Some(workspace_insights_path),
+                    format!(
+                        "## Workspace Insights\n\n{}\n\n",
+                        strip_entries(&workspace_insights, &inactive_insights)
+                    ),
+                );
+            }
+
+            // Load the files the workspace asks every session to see
+            if let Some(includes) = &workspace_config.includes {
+                let includes = options.apply(includes.clone());
+                let loaded = load_includes(&IncludeSet::new(&workspace_path, &includes).confined());
+                if !loaded.is_empty() {
+                    embodiment.push(
+                        "workspace includes",
+                        None,
+                        format!("## Included Files\n\n{}", loaded.to_markdown(3)),
+                    );
+                }
+            }
+
+            // Load the most recent checkpoints, oldest first
+            let checkpoint_count = options
+                .checkpoint_count
+                .or(workspace_config.checkpoints.as_ref().and_then(|c| c.load))
+                .or(settings.checkpoint_count)
+                .unwrap_or(1);
+            let checkpoints = recent_checkpoints(
+                &workspace_sparkle_space.join("checkpoints"),
+                checkpoint_count,
+            );
+            if !checkpoints.is_empty() {
+                let mut content = "## Checkpoints\n\n".to_string();
+                for checkpoint in checkpoints {
+                    if let Ok(checkpoint) = fs::read_to_string(&checkpoint) {
+                        content.push_str(&checkpoint);
+                        content.push_str("\n\n---\n\n");
                     }
                 }
+                embodiment.push("checkpoints", None, content);
             }
         } else {
-            response.push_str(&format!(
-                "*No .sparkle-space found at {}*\n\n",
-                workspace_path
-            ));
+            embodiment.push(
+                "workspace",
+                None,
+                format!(
+                    "\n\n---\n\n*No .sparkle-space found at {}*\n\n",
+                    workspace_path
+                ),
+            );
         }
     } else {
-        response.push_str("*Workspace path not specified - use workspace_path parameter to load workspace-specific context*\n\n");
+        embodiment.push("workspace", None, "\n\n---\n\n*Workspace path not specified - use workspace_path parameter to load workspace-specific context*\n\n");
+    }
+
+    Ok(embodiment)
+}
+
+/// The `count` most recently modified checkpoints in `checkpoints_dir`, oldest first
+pub fn recent_checkpoints(checkpoints_dir: &Path, count: usize) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(checkpoints_dir) else {
+        return Vec::new();
+    };
+
+    // Collect all checkpoint files with their modification times
+    let mut checkpoint_files: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|path| {
+            fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|mtime| (path, mtime))
+        })
+        .collect();
+
+    // Sort by modification time (file name breaks ties) and keep the newest
+    checkpoint_files.sort_by(|(a, a_mtime), (b, b_mtime)| a_mtime.cmp(b_mtime).then(a.cmp(b)));
+    let skip = checkpoint_files.len().saturating_sub(count);
+    checkpoint_files
+        .into_iter()
+        .skip(skip)
+        .map(|(path, _)| path)
+        .collect()
+}
+
+/// Collaborator details from `[human]` in config.toml, if any are set beyond the name
+fn human_details(human: &HumanConfig) -> Option<String> {
+    let mut lines = Vec::new();
+    let fields = [
+        ("Pronouns", &human.pronouns),
+        ("Role", &human.role),
+        ("Team", &human.team),
+        ("Preferred language", &human.language),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            lines.push(format!("- **{}**: {}", label, value));
+        }
+    }
+    if let Some(tz) = human.time_zone() {
+        lines.push(format!(
+            "- **Time zone**: {} (local time at embodiment: {})",
+            tz,
+            human.format_time(Utc::now(), "%Y-%m-%d %H:%M %Z")
+        ));
+    }
+    if !human.preferences.is_empty() {
+        lines.push("- **Preferences**:".to_string());
+        for (key, value) in &human.preferences {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            lines.push(format!("  - {}: {}", key, value));
+        }
     }
 
-    Ok(response)
+    (!lines.is_empty()).then(|| format!("\n\n## About {}\n\n{}\n\n", human.name, lines.join("\n")))
 }
@@ -0,0 +1,296 @@
+//! Version history for mutable context files
+//!
+//! Before a tool changes a context file (the collaborator profile, a sparkler
+//! identity, an insight log, `config.toml`, ...) the current content is saved under
+//! `~/.sparkle/.history/<path of the file>/<version id>`. Versions are pruned
+//! to the `[history]` retention settings, and any version can be restored -
+//! restoring saves the current content first, so it can be undone too.
+
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::get_context_dir;
+use crate::types::{Config, HistoryConfig};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// History directory, relative to the sparkle directory
+pub const HISTORY_DIR: &str = ".history";
+
+/// Format of version ids; sorts chronologically as a string
+const VERSION_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+/// Context files that tools version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextFile {
+    /// collaborator-profile.md, shared by all sparklers
+    CollaboratorProfile,
+    /// sparkler-identity.md of a sparkler
+    SparklerIdentity,
+    /// pattern-anchors.md of a sparkler
+    PatternAnchors,
+    /// collaboration-evolution.md of a sparkler
+    CollaborationEvolution,
+    /// workspace-map.md with the cross-workspace notes, shared by all sparklers
+    WorkspaceMap,
+    /// config.toml
+    Config,
+}
+
+impl ContextFile {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ContextFile::CollaboratorProfile => "collaborator-profile.md",
+            ContextFile::SparklerIdentity => "sparkler-identity.md",
+            ContextFile::PatternAnchors => "pattern-anchors.md",
+            ContextFile::CollaborationEvolution => "collaboration-evolution.md",
+            ContextFile::WorkspaceMap => "workspace-map.md",
+            ContextFile::Config => "config.toml",
+        }
+    }
+
+    /// Path of the file, for `sparkler` where the file is per-sparkler
+    pub fn path(
+        &self,
+        config: &Config,
+        sparkler: Option<&str>,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        match self {
+            ContextFile::CollaboratorProfile | ContextFile::WorkspaceMap | ContextFile::Config => {
+                let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+                Ok(home_dir.join(SPARKLE_DIR).join(self.file_name()))
+            }
+            ContextFile::SparklerIdentity
+            | ContextFile::PatternAnchors
+            | ContextFile::CollaborationEvolution => {
+                Ok(get_context_dir(config, sparkler)?.join(self.file_name()))
+            }
+        }
+    }
+}
+
+/// A saved version of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Versioning for the files under one sparkle directory
+pub struct History {
+    sparkle_dir: PathBuf,
+    config: HistoryConfig,
+}
+
+impl History {
+    pub fn new(sparkle_dir: impl Into<PathBuf>, config: HistoryConfig) -> Self {
+        Self {
+            sparkle_dir: sparkle_dir.into(),
+            config,
+        }
+    }
+
+    /// History of `~/.sparkle`, with retention from the config
+    pub fn from_config(config: &Config) -> io::Result<Self> {
+        let home_dir = dirs::home_dir().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "Could not find home directory")
+        })?;
+        Ok(Self::new(
+            home_dir.join(SPARKLE_DIR),
+            config.history.clone().unwrap_or_default(),
+        ))
+    }
+
+    /// Where versions of `file` are kept
+    pub fn versions_dir(&self, file: &Path) -> PathBuf {
+        let relative = file
+            .strip_prefix(&self.sparkle_dir)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| file.file_name().map(PathBuf::from).unwrap_or_default());
+        self.sparkle_dir.join(HISTORY_DIR).join(relative)
+    }
+
+    /// Save the current content of `file` as a new version
+    ///
+    /// Does nothing if the file doesn't exist or matches the latest version.
+    pub fn save_version(&self, file: &Path) -> io::Result<Option<Version>> {
+        if !file.exists() {
+            return Ok(None);
+        }
+        let content = fs::read(file)?;
+        let versions = self.list_versions(file);
+        if let Some(latest) = versions.first()
+            && fs::read(&latest.path).is_ok_and(|latest| latest == content)
+        {
+            return Ok(None);
+        }
+
+        let dir = self.versions_dir(file);
+        fs::create_dir_all(&dir)?;
+        let now = Utc::now();
+        let base = now.format(VERSION_FORMAT).to_string();
+        // Saves in the same millisecond get increasing suffixes, even after
+        // pruning removed an earlier one, so ids keep sorting newest first
+        let same_millisecond = versions
+            .iter()
+            .filter(|v| v.id == base || v.id.starts_with(&format!("{}-", base)))
+            .map(|v| version_suffix(&v.id))
+            .max();
+        let id = match same_millisecond {
+            Some(suffix) => format!("{}-{}", base, suffix + 1),
+            None => base,
+        };
+        let path = dir.join(&id);
+        fs::write(&path, &content)?;
+
+        self.prune(file)?;
+        Ok(Some(Version {
+            id,
+            timestamp: now,
+            path,
+            size: content.len() as u64,
+        }))
+    }
+
+    /// Save the current version of `file`, then replace it with `content`
+    pub fn write(&self, file: &Path, content: impl AsRef<[u8]>) -> io::Result<Option<Version>> {
+        let version = self.save_version(file)?;
+        fs::write(file, content)?;
+        Ok(version)
+    }
+
+    /// Save the current version of `file`, then append `content` to it
+    pub fn append(&self, file: &Path, content: impl AsRef<[u8]>) -> io::Result<Option<Version>> {
+        let version = self.save_version(file)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)?
+            .write_all(content.as_ref())?;
+        Ok(version)
+    }
+
+    /// Saved versions of `file`, newest first
+    pub fn list_versions(&self, file: &Path) -> Vec<Version> {
+        let Ok(entries) = fs::read_dir(self.versions_dir(file)) else {
+            return Vec::new();
+        };
+        let mut versions: Vec<Version> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let id = entry.file_name().to_string_lossy().to_string();
+                let timestamp = parse_version_id(&id)?;
+                let size = entry.metadata().ok()?.len();
+                Some(Version {
+                    id,
+                    timestamp,
+                    path: entry.path(),
+                    size,
+                })
+            })
+            .collect();
+        versions.sort_by_key(|v| std::cmp::Reverse((v.timestamp, version_suffix(&v.id))));
+        versions
+    }
+
+    /// Look up a version of `file` by id
+    pub fn find_version(&self, file: &Path, id: &str) -> Option<Version> {
+        self.list_versions(file)
+            .into_iter()
+            .find(|version| version.id == id.trim())
+    }
+
+    /// Replace `file` with a saved version, saving the current content first
+    ///
+    /// Returns the version holding the content that was replaced, if any.
+    pub fn restore_version(&self, file: &Path, version: &Version) -> io::Result<Option<Version>> {
+        let content = fs::read(&version.path)?;
+        self.write(file, content)
+    }
+
+    /// Move `<stem>.<YYYYmmdd-HHMMSS>.<ext>` backups left next to `file` by
+    /// older versions into the history
+    pub fn migrate_legacy_backups(&self, file: &Path) -> io::Result<usize> {
+        let (Some(dir), Some(stem), Some(extension)) = (
+            file.parent(),
+            file.file_stem().and_then(|s| s.to_str()),
+            file.extension().and_then(|e| e.to_str()),
+        ) else {
+            return Ok(0);
+        };
+
+        let mut migrated = 0;
+        for entry in fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(timestamp) = name
+                .strip_prefix(&format!("{}.", stem))
+                .and_then(|rest| rest.strip_suffix(&format!(".{}", extension)))
+                .and_then(|ts| NaiveDateTime::parse_from_str(ts, "%Y%m%d-%H%M%S").ok())
+            else {
+                continue;
+            };
+
+            let versions_dir = self.versions_dir(file);
+            fs::create_dir_all(&versions_dir)?;
+            let id = timestamp.and_utc().format(VERSION_FORMAT).to_string();
+            let target = versions_dir.join(&id);
+            if target.exists() {
+                fs::remove_file(entry.path())?;
+            } else {
+                fs::rename(entry.path(), target)?;
+            }
+            migrated += 1;
+        }
+
+        if migrated > 0 {
+            self.prune(file)?;
+        }
+        Ok(migrated)
+    }
+
+    /// Apply the retention settings to the versions of `file`
+    fn prune(&self, file: &Path) -> io::Result<()> {
+        let cutoff = self
+            .config
+            .max_age_days
+            .map(|days| Utc::now() - chrono::Duration::days(days as i64));
+
+        for (index, version) in self.list_versions(file).iter().enumerate() {
+            let too_many = index >= self.config.max_versions;
+            let too_old = cutoff.is_some_and(|cutoff| version.timestamp < cutoff);
+            // Always keep the newest version so a restore is possible
+            if index > 0 && (too_many || too_old) {
+                fs::remove_file(&version.path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collision suffix of a version id ("...123-2" is 2), 0 if none
+fn version_suffix(id: &str) -> u32 {
+    id.rsplit_once('-')
+        .filter(|(base, _)| base.contains('.'))
+        .and_then(|(_, suffix)| suffix.parse().ok())
+        .unwrap_or(0)
+}
+
+fn parse_version_id(id: &str) -> Option<DateTime<Utc>> {
+    // Strip a collision suffix ("...123-1")
+    let base = match id.rsplit_once('-') {
+        Some((base, suffix))
+            if base.contains('.') && suffix.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => id,
+    };
+    NaiveDateTime::parse_from_str(base, VERSION_FORMAT)
+        .ok()
+        .map(|timestamp| timestamp.and_utc())
+}
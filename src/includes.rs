@@ -0,0 +1,334 @@
+//! Extra files loaded at embodiment
+//!
+//! Besides the fixed context files, embodiment can load files declared in
+//! `[includes]` of `~/.sparkle/config.toml`, in a sparkler's settings and in a
+//! workspace's `.sparkle-space/config.toml`:
+//!
+//! ```toml
+//! [includes]
+//! max_total_bytes = 65536
+//!
+//! [[includes.files]]
+//! path = "CONTRIBUTING.md"
+//!
+//! [[includes.files]]
+//! path = "docs/adr/*.md"
+//! sort = "newest"
+//! limit = 5
+//! max_bytes = 4096
+//! ```
+//!
+//! Paths may be globs; `~/` is the home directory and relative paths are
+//! relative to the directory of whoever declared them. Workspace includes
+//! can't reach outside the workspace.
+
+use crate::templates::expand_home;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Largest part of a single file that is loaded, unless the include sets `max_bytes`
+pub const DEFAULT_MAX_FILE_BYTES: usize = 16 * 1024;
+
+/// Largest combined size of one set of includes
+pub const DEFAULT_MAX_TOTAL_BYTES: usize = 64 * 1024;
+
+/// `[includes]` in config.toml or `.sparkle-space/config.toml`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct IncludesConfig {
+    pub files: Vec<Include>,
+    /// Per-file cap for includes that don't set `max_bytes`
+    pub max_file_bytes: usize,
+    /// Cap on everything these includes load; later files are skipped
+    pub max_total_bytes: usize,
+}
+
+impl Default for IncludesConfig {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_total_bytes: DEFAULT_MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+/// One file or glob to include
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Include {
+    /// File or glob, e.g. `docs/adr/*.md`
+    pub path: String,
+    /// Heading shown above each file (default: the file's path)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Includes with a lower order are loaded first; ties keep their declared order
+    #[serde(skip_serializing_if = "is_zero")]
+    pub order: i32,
+    /// Order of the files a glob matches
+    pub sort: IncludeSort,
+    /// Keep only the first `limit` matches (after sorting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Truncate each file after this many bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_bytes: Option<usize>,
+}
+
+fn is_zero(order: &i32) -> bool {
+    *order == 0
+}
+
+impl Include {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// How the files a glob matches are ordered
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IncludeSort {
+    /// By path
+    #[default]
+    Name,
+    /// Most recently modified first
+    Newest,
+    /// Least recently modified first
+    Oldest,
+}
+
+/// Includes declared in one place, with the directory their paths are relative to
+#[derive(Debug, Clone)]
+pub struct IncludeSet {
+    pub base_dir: PathBuf,
+    pub includes: Vec<Include>,
+    pub max_file_bytes: usize,
+    pub max_total_bytes: usize,
+    /// Only files inside `base_dir` may be loaded
+    pub confined: bool,
+}
+
+impl IncludeSet {
+    pub fn new(base_dir: impl Into<PathBuf>, config: &IncludesConfig) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+            includes: config.files.clone(),
+            max_file_bytes: config.max_file_bytes,
+            max_total_bytes: config.max_total_bytes,
+            confined: false,
+        }
+    }
+
+    /// Refuse files outside the base directory (for includes declared by a workspace)
+    pub fn confined(mut self) -> Self {
+        self.confined = true;
+        self
+    }
+
+    /// Add includes declared elsewhere but resolved against the same directory
+    pub fn with_includes(mut self, includes: impl IntoIterator<Item = Include>) -> Self {
+        self.includes.extend(includes);
+        self
+    }
+}
+
+/// One loaded file
+#[derive(Debug, Clone)]
+pub struct IncludedFile {
+    pub path: PathBuf,
+    pub title: String,
+    pub content: String,
+    /// Size of the whole file
+    pub size: usize,
+    pub truncated: bool,
+}
+
+/// Everything one include set loaded
+#[derive(Debug, Clone, Default)]
+pub struct LoadedIncludes {
+    pub files: Vec<IncludedFile>,
+    /// Files left out because the total size cap was reached
+    pub skipped: Vec<PathBuf>,
+    /// Paths that matched nothing, couldn't be read or were out of bounds
+    pub warnings: Vec<String>,
+}
+
+impl LoadedIncludes {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.skipped.is_empty()
+    }
+
+    /// Markdown for embodiment, one `heading_level` section per file
+    pub fn to_markdown(&self, heading_level: usize) -> String {
+        let hashes = "#".repeat(heading_level);
+        let mut out = String::new();
+        for file in &self.files {
+            out.push_str(&format!("{} {}\n\n{}", hashes, file.title, file.content));
+            if file.truncated {
+                out.push_str(&format!(
+                    "\n\n*[truncated: {} of {} bytes shown]*",
+                    file.content.len(),
+                    file.size
+                ));
+            }
+            out.push_str("\n\n");
+        }
+        if !self.skipped.is_empty() {
+            out.push_str(&format!(
+                "*[{} more included file(s) skipped: size cap reached]*\n\n",
+                self.skipped.len()
+            ));
+        }
+        out
+    }
+}
+
+/// Load the files of an include set, in order, within its size caps
+pub fn load_includes(set: &IncludeSet) -> LoadedIncludes {
+    let mut loaded = LoadedIncludes::default();
+    let base_dir = fs::canonicalize(&set.base_dir).unwrap_or_else(|_| set.base_dir.clone());
+
+    let mut includes: Vec<&Include> = set.includes.iter().collect();
+    includes.sort_by_key(|include| include.order);
+
+    let mut remaining = set.max_total_bytes;
+    for include in includes {
+        let paths = match expand(&base_dir, include) {
+            Ok(paths) => paths,
+            Err(warning) => {
+                loaded.warnings.push(warning);
+                continue;
+            }
+        };
+        for path in paths {
+            if set.confined && !path.starts_with(&base_dir) {
+                loaded.warnings.push(format!(
+                    "{} is outside {}",
+                    path.display(),
+                    base_dir.display()
+                ));
+                continue;
+            }
+            if loaded.files.iter().any(|f| f.path == path) {
+                continue;
+            }
+            if remaining == 0 {
+                loaded.skipped.push(path);
+                continue;
+            }
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    loaded
+                        .warnings
+                        .push(format!("Can't read {}: {}", path.display(), e));
+                    continue;
+                }
+            };
+
+            let cap = include
+                .max_bytes
+                .unwrap_or(set.max_file_bytes)
+                .min(remaining);
+            let size = content.len();
+            let content = truncate(&content, cap).to_string();
+            remaining -= content.len();
+
+            let title = include.title.clone().unwrap_or_else(|| {
+                path.strip_prefix(&base_dir)
+                    .unwrap_or(&path)
+                    .display()
+                    .to_string()
+            });
+            loaded.files.push(IncludedFile {
+                truncated: content.len() < size,
+                path,
+                title,
+                content,
+                size,
+            });
+        }
+    }
+
+    for warning in &loaded.warnings {
+        tracing::warn!("Include skipped: {}", warning);
+    }
+    loaded
+}
+
+/// Files an include refers to, sorted and limited
+fn expand(base_dir: &Path, include: &Include) -> Result<Vec<PathBuf>, String> {
+    let path = include.path.trim();
+    if path.is_empty() {
+        return Err("Include with an empty path".to_string());
+    }
+    let full = match expand_home(path) {
+        expanded if expanded.is_absolute() => expanded,
+        _ => base_dir.join(path),
+    };
+
+    let mut paths: Vec<PathBuf> = if is_glob(path) {
+        // Escape the base directory so its own special characters aren't patterns
+        let pattern = match full.strip_prefix(base_dir) {
+            Ok(rest) => format!(
+                "{}/{}",
+                glob::Pattern::escape(&base_dir.to_string_lossy()),
+                rest.display()
+            ),
+            Err(_) => full.to_string_lossy().into_owned(),
+        };
+        glob::glob(&pattern)
+            .map_err(|e| format!("Invalid pattern {}: {}", include.path, e))?
+            .filter_map(|entry| entry.ok())
+            .filter(|path| path.is_file())
+            .collect()
+    } else if full.is_file() {
+        vec![full]
+    } else {
+        return Err(format!("{} not found", full.display()));
+    };
+    if paths.is_empty() {
+        return Err(format!("{} matched no files", include.path));
+    }
+
+    // Resolve symlinks and `..` so confinement checks see the real location
+    paths = paths
+        .into_iter()
+        .map(|path| fs::canonicalize(&path).unwrap_or(path))
+        .collect();
+
+    match include.sort {
+        IncludeSort::Name => paths.sort(),
+        IncludeSort::Newest | IncludeSort::Oldest => {
+            paths.sort_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok());
+            if include.sort == IncludeSort::Newest {
+                paths.reverse();
+            }
+        }
+    }
+    if let Some(limit) = include.limit {
+        paths.truncate(limit);
+    }
+    Ok(paths)
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// The longest prefix of `text` of at most `max` bytes that ends on a char boundary
+fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
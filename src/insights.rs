@@ -0,0 +1,459 @@
+//! Structured insight storage
+//!
+//! Every insight saved through `save_insight` is written twice:
+//! - as a markdown entry appended to the file embodiment loads (pattern-anchors.md, etc.)
+//! - as a JSON record appended to `insights.jsonl` in the same directory
+//!
+//! The JSONL records are the queryable form used by `search_insights`, and the
+//! source of truth for an insight's status. Updating an insight supersedes it
+//! with a new record; retracting it marks it retracted. Neither drops the old
+//! entry - both stay in the files as history, but embodiment leaves them out.
+
+use crate::constants::{SPARKLE_DIR, SPARKLE_SPACE_DIR};
+use crate::history::History;
+use crate::types::{Config, HumanConfig, InsightType, TIMESTAMP_FORMAT};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// File name of the structured insight log, stored next to the markdown files
+pub const INSIGHTS_FILE: &str = "insights.jsonl";
+
+/// A single saved insight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightRecord {
+    pub id: String,
+    pub insight_type: InsightType,
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparkler: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default)]
+    pub status: InsightStatus,
+    /// Id of the insight this one replaced (set by `update_insight`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+    /// Id of the insight that replaced this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
+    /// Why the insight was retracted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retraction_reason: Option<String>,
+}
+
+/// Lifecycle of a saved insight
+///
+/// A status only moves forward (active to superseded or retracted), so the
+/// variants are ordered from oldest to newest state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsightStatus {
+    #[default]
+    Active,
+    Superseded,
+    Retracted,
+}
+
+impl InsightRecord {
+    pub fn is_active(&self) -> bool {
+        self.status == InsightStatus::Active
+    }
+
+    /// Render the markdown entry appended to the insight's markdown file,
+    /// with the timestamp in the human's time zone
+    pub fn to_markdown(&self, human: &HumanConfig) -> String {
+        let mut entry = format!(
+            "\n## {} - {}\n<!-- insight-id: {} -->\n\n",
+            human.format_time(self.timestamp, TIMESTAMP_FORMAT),
+            self.insight_type.title(),
+            self.id
+        );
+
+        if let Some(supersedes) = &self.supersedes {
+            entry.push_str(&format!("*Supersedes {}*\n\n", supersedes));
+        }
+
+        entry.push_str(&format!("{}\n\n", self.content));
+
+        if let Some(context) = &self.context {
+            entry.push_str(&format!("**Context**: {}\n\n", context));
+        }
+
+        if !self.tags.is_empty() {
+            entry.push_str(&format!("**Tags**: {}\n\n", self.tags.join(", ")));
+        }
+
+        entry.push_str("---\n");
+        entry
+    }
+}
+
+/// Generate a new insight id - sortable by creation time, unique enough for a single user
+pub fn new_insight_id(now: DateTime<Utc>) -> String {
+    format!(
+        "{}-{:04x}",
+        now.format("%Y%m%d-%H%M%S"),
+        now.timestamp_subsec_nanos() & 0xffff
+    )
+}
+
+/// Append a record to the `insights.jsonl` file in `dir`
+pub fn append_record(dir: &Path, record: &InsightRecord) -> std::io::Result<()> {
+    let line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(INSIGHTS_FILE))?;
+    writeln!(file, "{}", line)
+}
+
+/// Replace the contents of the `insights.jsonl` file in `dir`
+pub fn write_records(dir: &Path, records: &[InsightRecord]) -> std::io::Result<()> {
+    let mut content = String::new();
+    for record in records {
+        content.push_str(&serde_json::to_string(record).map_err(std::io::Error::other)?);
+        content.push('\n');
+    }
+
+    // Write to a temporary file first so a failed write can't truncate the log
+    let tmp_path = dir.join(format!("{}.tmp", INSIGHTS_FILE));
+    fs::write(&tmp_path, content)?;
+    fs::rename(tmp_path, dir.join(INSIGHTS_FILE))
+}
+
+/// Load all records from the `insights.jsonl` file in `dir`
+///
+/// Missing files yield no records; malformed lines are skipped with a warning.
+pub fn load_records(dir: &Path) -> Vec<InsightRecord> {
+    let path = dir.join(INSIGHTS_FILE);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+
+    let records: Vec<InsightRecord> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                tracing::warn!("Skipping malformed insight record in {:?}: {}", path, e);
+                None
+            }
+        })
+        .collect();
+
+    // A union merge (see sync) can leave an old and a new copy of a record in
+    // either order; the copy with the later status is the newer state, and
+    // among equal statuses the later line
+    let mut newest: HashMap<&str, usize> = HashMap::new();
+    for (index, record) in records.iter().enumerate() {
+        newest
+            .entry(record.id.as_str())
+            .and_modify(|kept| {
+                if records[*kept].status <= record.status {
+                    *kept = index;
+                }
+            })
+            .or_insert(index);
+    }
+    let keep: HashSet<usize> = newest.into_values().collect();
+    records
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| keep.contains(index))
+        .map(|(_, record)| record.clone())
+        .collect()
+}
+
+/// Canonical form of a workspace path, used to tag and match workspace insights
+pub fn canonical_workspace(workspace_path: &str) -> String {
+    fs::canonicalize(workspace_path)
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| workspace_path.to_string())
+}
+
+/// Insight store of a single workspace: `{workspace}/.sparkle-space/`
+pub fn workspace_store_dir(workspace_path: &str) -> PathBuf {
+    Path::new(workspace_path).join(SPARKLE_SPACE_DIR)
+}
+
+/// Version history for the markdown files of the insight store in `dir`
+///
+/// Stores under `~/.sparkle` share its history; a workspace store keeps its
+/// own in `.sparkle-space/.history/`, next to the working-memory snapshots.
+pub fn store_history(config: &Config, dir: &Path) -> History {
+    let retention = config.history.clone().unwrap_or_default();
+    match dirs::home_dir().map(|home| home.join(SPARKLE_DIR)) {
+        Some(sparkle_dir) if dir.starts_with(&sparkle_dir) => History::new(sparkle_dir, retention),
+        _ => History::new(dir, retention),
+    }
+}
+
+/// All directories that may hold an insight store
+///
+/// Returns:
+/// - `~/.sparkle/` (single-sparkler context and cross-workspace map)
+/// - `~/.sparkle/sparklers/{name}/` for every configured sparkler (multi-sparkler)
+/// - `{workspace}/.sparkle-space/` if a workspace is given
+pub fn store_dirs(
+    config: &Config,
+    workspace_path: Option<&str>,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let home_dir = dirs::home_dir().ok_or("Could not find home directory")?;
+    let sparkle_dir = home_dir.join(SPARKLE_DIR);
+
+    let mut dirs = vec![sparkle_dir.clone()];
+    for name in config.get_all_sparkler_names() {
+        dirs.push(sparkle_dir.join("sparklers").join(name));
+    }
+    if let Some(workspace_path) = workspace_path {
+        dirs.push(workspace_store_dir(workspace_path));
+    }
+
+    Ok(dirs)
+}
+
+/// Records of every store in `dirs`; an id stored twice is only returned once
+pub fn load_all_records(dirs: &[PathBuf]) -> Vec<InsightRecord> {
+    // The same directory can appear twice (e.g. single-sparkler mode)
+    let mut seen = HashSet::new();
+    dirs.iter()
+        .flat_map(|dir| load_records(dir))
+        .filter(|record| seen.insert(record.id.clone()))
+        .collect()
+}
+
+/// Find the store directory holding the insight with the given id
+pub fn find_record(dirs: &[PathBuf], id: &str) -> Option<(PathBuf, InsightRecord)> {
+    dirs.iter().find_map(|dir| {
+        load_records(dir)
+            .into_iter()
+            .find(|r| r.id == id)
+            .map(|r| (dir.clone(), r))
+    })
+}
+
+/// Ids of every superseded or retracted insight stored in `dir`
+pub fn inactive_ids(dir: &Path) -> HashSet<String> {
+    load_records(dir)
+        .into_iter()
+        .filter(|r| !r.is_active())
+        .map(|r| r.id)
+        .collect()
+}
+
+/// Ids of the workspace insights stored in `dir` that belong to a different workspace
+///
+/// Used to keep other repos' insights out of the shared map. Insights without a
+/// workspace are cross-workspace notes and are never returned.
+pub fn other_workspace_ids(dir: &Path, current_workspace: Option<&str>) -> HashSet<String> {
+    load_records(dir)
+        .into_iter()
+        .filter(|r| r.insight_type == InsightType::WorkspaceInsight)
+        .filter(|r| {
+            r.workspace
+                .as_deref()
+                .is_some_and(|ws| Some(ws) != current_workspace)
+        })
+        .map(|r| r.id)
+        .collect()
+}
+
+/// Extract the insight id from an entry's `<!-- insight-id: ... -->` marker
+pub(crate) fn entry_id(entry: &str) -> Option<&str> {
+    entry.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("<!-- insight-id:")?
+            .strip_suffix("-->")
+            .map(str::trim)
+    })
+}
+
+/// Split a markdown insight file into its preamble and entries
+///
+/// An entry starts at a `## ` heading directly followed by an id marker, so
+/// headings inside an insight's content stay part of that insight.
+pub(crate) fn split_entries(markdown: &str) -> Vec<&str> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    let mut lines = markdown.split_inclusive('\n').peekable();
+
+    while let Some(line) = lines.next() {
+        let starts_entry =
+            line.starts_with("## ") && lines.peek().is_some_and(|next| entry_id(next).is_some());
+        if starts_entry && offset > start {
+            entries.push(&markdown[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    entries.push(&markdown[start..]);
+
+    entries
+}
+
+/// Remove the entries whose ids are in `excluded` from a markdown insight file
+///
+/// Entries without an id marker (hand-written or saved before ids existed) are kept.
+pub fn strip_entries(markdown: &str, excluded: &HashSet<String>) -> String {
+    if excluded.is_empty() {
+        return markdown.to_string();
+    }
+
+    split_entries(markdown)
+        .into_iter()
+        .filter(|entry| entry_id(entry).is_none_or(|id| !excluded.contains(id)))
+        .collect()
+}
+
+/// Insert a status note below the id marker of an entry, leaving its content intact
+///
+/// Returns `None` if no entry with that id exists in the markdown.
+pub fn annotate_entry(markdown: &str, id: &str, note: &str) -> Option<String> {
+    let entries = split_entries(markdown);
+    let position = entries
+        .iter()
+        .position(|entry| entry_id(entry) == Some(id))?;
+
+    let mut result = String::new();
+    for (i, entry) in entries.into_iter().enumerate() {
+        if i != position {
+            result.push_str(entry);
+            continue;
+        }
+        for line in entry.split_inclusive('\n') {
+            result.push_str(line);
+            if entry_id(line) == Some(id) {
+                result.push_str(&format!("\n{}\n", note));
+            }
+        }
+    }
+
+    Some(result)
+}
+
+/// Add a status note to an insight's entry in its markdown file, saving the
+/// previous content to `history`
+///
+/// Missing files and entries without a marker are left untouched.
+pub fn annotate_markdown_file(
+    history: &History,
+    dir: &Path,
+    record: &InsightRecord,
+    note: &str,
+) -> std::io::Result<()> {
+    let path = dir.join(record.insight_type.file_name());
+    let Ok(markdown) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+
+    match annotate_entry(&markdown, &record.id, note) {
+        Some(annotated) => history.write(&path, annotated).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+/// Filters applied by `search`
+#[derive(Debug, Default)]
+pub struct InsightQuery {
+    /// Free text, ranked by term matches against content, context and tags
+    pub text: Option<String>,
+    /// Records must carry all of these tags (case-insensitive)
+    pub tags: Vec<String>,
+    pub insight_type: Option<InsightType>,
+    pub sparkler: Option<String>,
+    /// Inclusive lower bound on the record date
+    pub since: Option<NaiveDate>,
+    /// Inclusive upper bound on the record date
+    pub until: Option<NaiveDate>,
+    /// Also return superseded and retracted insights
+    pub include_inactive: bool,
+}
+
+/// Filter and rank records
+///
+/// With a text query, records are ordered by match score (tags weigh most,
+/// then content, then context) and records matching no term are dropped.
+/// Without one, records are ordered newest first.
+pub fn search(records: Vec<InsightRecord>, query: &InsightQuery) -> Vec<InsightRecord> {
+    let terms: Vec<String> = query
+        .text
+        .as_deref()
+        .unwrap_or_default()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+
+    let mut scored: Vec<(usize, InsightRecord)> = records
+        .into_iter()
+        .filter(|r| query.include_inactive || r.is_active())
+        .filter(|r| {
+            query
+                .insight_type
+                .as_ref()
+                .is_none_or(|t| *t == r.insight_type)
+        })
+        .filter(|r| {
+            query.sparkler.as_ref().is_none_or(|s| {
+                r.sparkler
+                    .as_ref()
+                    .is_some_and(|rs| rs.eq_ignore_ascii_case(s))
+            })
+        })
+        .filter(|r| {
+            query
+                .tags
+                .iter()
+                .all(|tag| r.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .filter(|r| {
+            query
+                .since
+                .is_none_or(|since| r.timestamp.date_naive() >= since)
+        })
+        .filter(|r| {
+            query
+                .until
+                .is_none_or(|until| r.timestamp.date_naive() <= until)
+        })
+        .map(|r| (score(&r, &terms), r))
+        .filter(|(score, _)| terms.is_empty() || *score > 0)
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| b.timestamp.cmp(&a.timestamp))
+    });
+
+    scored.into_iter().map(|(_, r)| r).collect()
+}
+
+fn score(record: &InsightRecord, terms: &[String]) -> usize {
+    let content = record.content.to_lowercase();
+    let context = record.context.as_deref().unwrap_or_default().to_lowercase();
+
+    terms
+        .iter()
+        .map(|term| {
+            let tag_hits = record
+                .tags
+                .iter()
+                .filter(|t| t.to_lowercase().contains(term.as_str()))
+                .count();
+            tag_hits * 3
+                + content.matches(term.as_str()).count() * 2
+                + context.matches(term.as_str()).count()
+        })
+        .sum()
+}
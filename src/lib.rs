@@ -1,10 +1,20 @@
 pub mod acp_component;
+pub mod bundle;
 pub mod constants;
 pub mod context_loader;
+pub mod doctor;
 pub mod embodiment;
+pub mod history;
+pub mod includes;
+pub mod insights;
+pub mod profile_patch;
+pub mod profile_sources;
 pub mod prompts;
 pub mod server;
 pub mod sparkle_loader;
+pub mod sync;
+pub mod template;
+pub mod templates;
 pub mod tools;
 pub mod types;
 
@@ -5,13 +5,25 @@ use std::fs::OpenOptions;
 use tracing_subscriber::{self, EnvFilter, fmt::writer::MakeWriterExt};
 
 mod acp_component;
+mod bundle;
+mod cli;
 mod constants;
 mod context_loader;
+mod doctor;
 mod embodiment;
+mod history;
+mod includes;
+mod insights;
+mod profile_patch;
+mod profile_sources;
 mod prompts;
 mod server;
 mod sparkle_loader;
+mod sync;
+mod template;
+mod templates;
 mod tools;
+mod tui;
 mod types;
 
 use acp_component::SparkleComponent;
@@ -25,9 +37,12 @@ struct Args {
     #[arg(long)]
     acp: bool,
 
-    /// Sparkler name for multi-sparkler setups (ACP mode only)
+    /// Sparkler whose settings pick the exposed tools and prompts (and who ACP mode embodies)
     #[arg(long)]
     sparkler: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<cli::Command>,
 }
 
 #[tokio::main]
@@ -65,6 +80,10 @@ async fn main() -> anyhow::Result<()> {
             .init();
     }
 
+    if let Some(command) = args.command {
+        return cli::run(command).await;
+    }
+
     if args.acp {
         tracing::info!("🔥 Starting Sparkle ACP Proxy");
         tracing::info!("Working directory: {:?}", std::env::current_dir()?);
@@ -86,8 +105,22 @@ async fn main() -> anyhow::Result<()> {
             tracing::debug!("Debug mode enabled - logging to ~/.sparkle/sparkle-mcp.log");
         }
 
-        // Create and serve the Sparkle MCP server
-        let server = SparkleServer::new();
+        // Create and serve the Sparkle MCP server, for the requested sparkler
+        // or else the one the workspace is pinned to
+        let sparkler = match context_loader::load_config() {
+            Ok(config) => {
+                let workspace_config = std::env::current_dir()
+                    .map(|dir| context_loader::load_workspace_config(&dir))
+                    .unwrap_or_default();
+                context_loader::requested_or_pinned_sparkler(
+                    args.sparkler,
+                    &config,
+                    &workspace_config,
+                )
+            }
+            Err(_) => args.sparkler,
+        };
+        let server = SparkleServer::for_sparkler(sparkler, false);
         let service = server.serve(stdio()).await?;
 
         // Keep the service running indefinitely
@@ -0,0 +1,277 @@
+//! Section-aware edits to markdown profile files
+//!
+//! A profile is split into sections at its markdown headings. A section runs
+//! from its heading to the next heading of the same or a higher level, so it
+//! includes its subsections. Operations name a section by its heading text and
+//! leave the rest of the file untouched, which keeps agents from dropping
+//! content they never meant to change.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+
+/// What to do with a section
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchOp {
+    /// Add a new section (fails if the heading already exists)
+    Add,
+    /// Replace everything under the heading, keeping the heading itself
+    Replace,
+    /// Add content to the end of an existing section
+    Append,
+    /// Remove the section, its heading and its subsections
+    Remove,
+}
+
+impl PatchOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatchOp::Add => "add",
+            PatchOp::Replace => "replace",
+            PatchOp::Append => "append",
+            PatchOp::Remove => "remove",
+        }
+    }
+}
+
+/// One operation on a profile section
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PatchOperation {
+    /// add, replace, append or remove
+    pub op: PatchOp,
+    /// Heading text of the section, without the leading #s (matched case-insensitively)
+    pub heading: String,
+    /// Optional: Markdown content for add, replace and append (without the heading line)
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Optional: For add - heading level (default 2, i.e. "## ")
+    #[serde(default)]
+    pub level: Option<usize>,
+    /// Optional: For add - insert after this section instead of at the end of the file
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+/// A heading and the lines it covers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub level: usize,
+    pub title: String,
+    /// Index of the heading line
+    pub start: usize,
+    /// Index one past the last line of the section (including subsections)
+    pub end: usize,
+}
+
+/// Parse a markdown heading line into its level and title
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim()))
+}
+
+/// All sections of a markdown document, in order
+///
+/// Headings inside fenced code blocks are ignored.
+pub fn parse_sections(markdown: &str) -> Vec<Section> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_fence = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        if let Some((level, title)) = heading(line) {
+            sections.push(Section {
+                level,
+                title: title.to_string(),
+                start: index,
+                end: lines.len(),
+            });
+        }
+    }
+
+    // A section ends where the next heading of the same or a higher level begins
+    for i in 0..sections.len() {
+        if let Some(next) = sections[i + 1..]
+            .iter()
+            .find(|s| s.level <= sections[i].level)
+        {
+            sections[i].end = next.start;
+        }
+    }
+    sections
+}
+
+fn normalize(title: &str) -> String {
+    title
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn find_section<'a>(sections: &'a [Section], title: &str) -> Result<&'a Section, String> {
+    let wanted = normalize(title);
+    let matches: Vec<&Section> = sections
+        .iter()
+        .filter(|s| normalize(&s.title) == wanted)
+        .collect();
+    match matches.as_slice() {
+        [section] => Ok(section),
+        [] => Err(format!(
+            "No section with heading '{}' (headings: {})",
+            title.trim(),
+            sections
+                .iter()
+                .map(|s| format!("\"{}\"", s.title))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        _ => Err(format!(
+            "Heading '{}' appears {} times; rename one of the sections first",
+            title.trim(),
+            matches.len()
+        )),
+    }
+}
+
+/// Content lines for an operation, with surrounding blank lines trimmed
+fn content_lines(operation: &PatchOperation) -> Result<Vec<String>, String> {
+    let content = operation.content.as_deref().ok_or_else(|| {
+        format!(
+            "{} on '{}' needs content",
+            operation.op.as_str(),
+            operation.heading.trim()
+        )
+    })?;
+    Ok(content
+        .trim_matches('\n')
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Drop trailing blank lines, then end with exactly one blank line
+fn with_blank_separator(mut lines: Vec<String>) -> Vec<String> {
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines.push(String::new());
+    lines
+}
+
+fn apply_one(lines: &mut Vec<String>, operation: &PatchOperation) -> Result<(), String> {
+    let text = lines.join("\n");
+    let sections = parse_sections(&text);
+
+    match operation.op {
+        PatchOp::Add => {
+            if find_section(&sections, &operation.heading).is_ok() {
+                return Err(format!(
+                    "Section '{}' already exists; use replace or append",
+                    operation.heading.trim()
+                ));
+            }
+            let level = operation.level.unwrap_or(2).clamp(1, 6);
+            let mut block = vec![format!(
+                "{} {}",
+                "#".repeat(level),
+                operation.heading.trim().trim_start_matches('#').trim()
+            )];
+            block.push(String::new());
+            block.extend(content_lines(operation)?);
+            let block = with_blank_separator(block);
+
+            let at = match &operation.after {
+                Some(after) => find_section(&sections, after)?.end,
+                None => lines.len(),
+            };
+            // Keep a blank line between the previous section and the new heading
+            let mut insert = Vec::new();
+            if at > 0 && !lines[at - 1].trim().is_empty() {
+                insert.push(String::new());
+            }
+            insert.extend(block);
+            lines.splice(at..at, insert);
+        }
+        PatchOp::Replace => {
+            let section = find_section(&sections, &operation.heading)?;
+            let mut body = vec![String::new()];
+            body.extend(content_lines(operation)?);
+            let body = if section.end < lines.len() {
+                with_blank_separator(body)
+            } else {
+                body
+            };
+            lines.splice(section.start + 1..section.end, body);
+        }
+        PatchOp::Append => {
+            let section = find_section(&sections, &operation.heading)?;
+            // Insert after the section's last non-blank line
+            let mut at = section.end;
+            while at > section.start + 1 && lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            let mut insert = vec![String::new()];
+            insert.extend(content_lines(operation)?);
+            lines.splice(at..at, insert);
+        }
+        PatchOp::Remove => {
+            let section = find_section(&sections, &operation.heading)?;
+            lines.drain(section.start..section.end);
+        }
+    }
+    Ok(())
+}
+
+/// Apply operations in order, returning the new document
+///
+/// Fails without partial results if any operation can't be applied.
+pub fn apply_operations(markdown: &str, operations: &[PatchOperation]) -> Result<String, String> {
+    let mut lines: Vec<String> = markdown.lines().map(str::to_string).collect();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_one(&mut lines, operation)
+            .map_err(|e| format!("Operation {} ({}): {}", index + 1, operation.op.as_str(), e))?;
+    }
+
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut result = lines.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// Unified diff between two versions of a file
+pub fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string()
+}
+
+/// Short token identifying a version of a file, so a confirmed edit can
+/// check that it applies to the content that was previewed
+pub fn base_token(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    format!("{:x}", digest)[..16].to_string()
+}
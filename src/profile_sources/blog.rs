@@ -0,0 +1,211 @@
+use super::html::html_to_text;
+use super::{HttpClient, ProfileSource, ProfileSourceProvider, truncate_chars};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::{HashMap, HashSet};
+
+/// Default number of recent posts listed
+const DEFAULT_MAX_ENTRIES: usize = 10;
+
+/// Default length of each post's summary, in characters
+const DEFAULT_SUMMARY_CHARS: usize = 200;
+
+/// How many topics to report when topic extraction is enabled
+const MAX_TOPICS: usize = 10;
+
+/// Common words that say nothing about what a blog covers
+const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "also", "because", "been", "before", "being", "between", "both",
+    "could", "does", "doing", "down", "during", "each", "even", "every", "first", "from", "have",
+    "here", "into", "just", "know", "like", "made", "make", "many", "more", "most", "much", "need",
+    "only", "other", "over", "part", "post", "posts", "really", "same", "should", "some", "still",
+    "such", "than", "that", "their", "them", "then", "there", "these", "they", "thing", "things",
+    "think", "this", "those", "through", "time", "using", "very", "want", "well", "were", "what",
+    "when", "where", "which", "while", "will", "with", "without", "would", "year", "your",
+];
+
+/// A blog's RSS or Atom feed
+pub struct BlogRssSource {
+    http: HttpClient,
+}
+
+impl BlogRssSource {
+    pub fn new(http: HttpClient) -> Self {
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl ProfileSourceProvider for BlogRssSource {
+    fn source_type(&self) -> &'static str {
+        "blog"
+    }
+
+    fn description(&self) -> &'static str {
+        "RSS/Atom feed URL (typically /feed, /rss, or /feed.xml). Options: max_entries (default 10), summary_chars (default 200), topics (\"true\" to list the recurring topics across posts)"
+    }
+
+    async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        let url = source.value.as_str();
+        let max_entries = usize_option(source, "max_entries", DEFAULT_MAX_ENTRIES)?;
+        let summary_chars = usize_option(source, "summary_chars", DEFAULT_SUMMARY_CHARS)?;
+        let topics = source
+            .options
+            .get("topics")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+
+        let resp = self
+            .http
+            .get(
+                url,
+                "application/rss+xml, application/atom+xml, application/xml;q=0.9, */*;q=0.8",
+            )
+            .await
+            .context("Failed to fetch blog RSS")?
+            .error_for_status("Failed to fetch blog RSS")?;
+
+        let content = resp.body;
+        let feed = feed_rs::parser::parse(&content[..]).context("Failed to parse RSS/Atom feed")?;
+
+        let mut summary = format!(
+            "## Blog: {}\n\n",
+            feed.title
+                .map(|t| html_to_text(&t.content))
+                .unwrap_or_else(|| "Blog".to_string())
+        );
+
+        if let Some(description) = feed.description {
+            let description = html_to_text(&description.content);
+            if !description.is_empty() {
+                summary.push_str(&format!("{}\n\n", description));
+            }
+        }
+
+        let entries: Vec<_> = feed.entries.iter().take(max_entries).collect();
+
+        if topics {
+            let topics = extract_topics(&entries);
+            if !topics.is_empty() {
+                summary.push_str(&format!("**Writes about:** {}\n\n", topics.join(", ")));
+            }
+        }
+
+        summary.push_str("**Recent Posts:**\n\n");
+
+        for entry in entries {
+            let title = entry
+                .title
+                .as_ref()
+                .map(|t| html_to_text(&t.content))
+                .unwrap_or_else(|| "Untitled".to_string());
+
+            let link = entry.links.first().map(|l| l.href.as_str()).unwrap_or("");
+
+            summary.push_str(&format!("- [{}]({})\n", title, link));
+
+            if let Some(published) = entry.published.or(entry.updated) {
+                summary.push_str(&format!(
+                    "  *Published: {}*\n",
+                    published.format("%Y-%m-%d")
+                ));
+            }
+
+            let text = entry_text(entry);
+            if !text.is_empty() {
+                let truncated = truncate_chars(&text, summary_chars);
+                if truncated.len() < text.len() {
+                    summary.push_str(&format!("  {}...\n", truncated.trim_end()));
+                } else {
+                    summary.push_str(&format!("  {}\n", truncated));
+                }
+            }
+
+            summary.push('\n');
+        }
+
+        Ok(summary)
+    }
+}
+
+fn usize_option(source: &ProfileSource, name: &str, default: usize) -> Result<usize> {
+    match source.options.get(name) {
+        Some(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid {} option: {}", name, value)),
+        None => Ok(default),
+    }
+}
+
+/// An entry's summary as plain text, falling back to its full content
+fn entry_text(entry: &feed_rs::model::Entry) -> String {
+    entry
+        .summary
+        .as_ref()
+        .map(|summary| html_to_text(&summary.content))
+        .filter(|text| !text.is_empty())
+        .or_else(|| {
+            entry
+                .content
+                .as_ref()
+                .and_then(|content| content.body.as_deref())
+                .map(html_to_text)
+        })
+        .unwrap_or_default()
+}
+
+/// Recurring topics across posts: feed categories first, then words that
+/// appear in the titles or summaries of several posts
+fn extract_topics(entries: &[&feed_rs::model::Entry]) -> Vec<String> {
+    let mut categories: HashMap<String, (String, usize)> = HashMap::new();
+    let mut keywords: HashMap<String, usize> = HashMap::new();
+
+    for entry in entries {
+        for category in &entry.categories {
+            let label = category.label.as_deref().unwrap_or(&category.term).trim();
+            if !label.is_empty() {
+                categories
+                    .entry(label.to_lowercase())
+                    .or_insert_with(|| (label.to_string(), 0))
+                    .1 += 1;
+            }
+        }
+
+        // Count each word once per post, so one long post doesn't dominate
+        let title = entry
+            .title
+            .as_ref()
+            .map(|t| t.content.as_str())
+            .unwrap_or("");
+        let text = format!("{} {}", html_to_text(title), entry_text(entry));
+        let words: HashSet<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '-' && c != '+' && c != '#')
+            .map(|word| word.trim_matches('-').to_lowercase())
+            .filter(|word| word.chars().count() >= 4 && word.chars().any(char::is_alphabetic))
+            .filter(|word| !STOPWORDS.contains(&word.as_str()))
+            .collect();
+        for word in words {
+            *keywords.entry(word).or_insert(0) += 1;
+        }
+    }
+
+    let mut categories: Vec<(String, usize)> = categories.into_values().collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut keywords: Vec<(String, usize)> = keywords
+        .into_iter()
+        .filter(|(_, count)| *count >= 2)
+        .collect();
+    keywords.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut topics: Vec<String> = Vec::new();
+    for (topic, _) in categories.into_iter().chain(keywords) {
+        if topics.len() == MAX_TOPICS {
+            break;
+        }
+        if !topics.iter().any(|t| t.eq_ignore_ascii_case(&topic)) {
+            topics.push(topic);
+        }
+    }
+    topics
+}
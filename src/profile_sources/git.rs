@@ -0,0 +1,409 @@
+use super::{ProfileSource, ProfileSourceProvider};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, FixedOffset, Timelike, Weekday};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Commit history of local git repositories, summarized for one author
+///
+/// Works offline. Options:
+/// - `author`: author email(s) to summarize, comma separated
+///   (default: `git config user.email` of each repository)
+/// - `max_commits`: most recent commits to read per repository (default 1000)
+pub struct GitHistorySource;
+
+#[async_trait]
+impl ProfileSourceProvider for GitHistorySource {
+    fn source_type(&self) -> &'static str {
+        "git"
+    }
+
+    fn description(&self) -> &'static str {
+        "local repository path(s), comma separated; options: author (email), max_commits"
+    }
+
+    async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        let max_commits: usize = match source.options.get("max_commits") {
+            Some(value) => value
+                .parse()
+                .with_context(|| format!("Invalid max_commits '{}'", value))?,
+            None => 1000,
+        };
+
+        let repos: Vec<PathBuf> = source
+            .value
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(expand_home)
+            .collect();
+
+        if repos.is_empty() {
+            anyhow::bail!("No repository paths given");
+        }
+
+        let mut commits = Vec::new();
+        let mut authors = Vec::new();
+        for repo in &repos {
+            let repo_authors = match source.options.get("author") {
+                Some(author) => author
+                    .split(',')
+                    .map(|a| a.trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect(),
+                None => vec![git_user_email(repo).await?],
+            };
+
+            commits.extend(read_commits(repo, &repo_authors, max_commits).await?);
+            for author in repo_authors {
+                if !authors.contains(&author) {
+                    authors.push(author);
+                }
+            }
+        }
+
+        Ok(summarize(&repos, &authors, &commits))
+    }
+}
+
+struct Commit {
+    repo: String,
+    date: DateTime<FixedOffset>,
+    subject: String,
+    /// (lines added + deleted, path) per changed file; binary files count as 0 lines
+    files: Vec<(usize, String)>,
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+async fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git - is it installed?")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed in {}: {}",
+            args.first().unwrap_or(&""),
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn git_user_email(repo: &Path) -> Result<String> {
+    let email = git(repo, &["config", "user.email"])
+        .await
+        .with_context(|| {
+            format!(
+                "No author given and no git user.email configured for {}",
+                repo.display()
+            )
+        })?;
+    Ok(email.trim().to_string())
+}
+
+async fn read_commits(repo: &Path, authors: &[String], max_commits: usize) -> Result<Vec<Commit>> {
+    // Each commit starts with a record separator, followed by its numstat lines
+    let mut args = vec![
+        "log".to_string(),
+        "--no-merges".to_string(),
+        "--numstat".to_string(),
+        "--format=%x1e%aI%x1f%s".to_string(),
+        format!("--max-count={}", max_commits),
+        // Match authors literally; '+' and '.' are common in email addresses
+        "--fixed-strings".to_string(),
+    ];
+    args.extend(authors.iter().map(|a| format!("--author={}", a)));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let log = git(repo, &args).await?;
+    let repo_name = repo
+        .canonicalize()
+        .unwrap_or_else(|_| repo.to_path_buf())
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| repo.display().to_string());
+
+    let commits = log
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let (date, subject) = lines.next()?.split_once('\x1f')?;
+            let date = DateTime::parse_from_rfc3339(date).ok()?;
+
+            let files = lines
+                .filter_map(|line| {
+                    let mut parts = line.splitn(3, '\t');
+                    let added = parts.next()?.parse::<usize>().unwrap_or(0);
+                    let deleted = parts.next()?.parse::<usize>().unwrap_or(0);
+                    Some((added + deleted, parts.next()?.to_string()))
+                })
+                .collect();
+
+            Some(Commit {
+                repo: repo_name.clone(),
+                date,
+                subject: subject.to_string(),
+                files,
+            })
+        })
+        .collect();
+
+    Ok(commits)
+}
+
+fn summarize(repos: &[PathBuf], authors: &[String], commits: &[Commit]) -> String {
+    let mut summary = String::from("## Git History\n\n");
+    summary.push_str(&format!(
+        "*{} commit(s) by {} across {} local repositor{}*\n\n",
+        commits.len(),
+        authors.join(", "),
+        repos.len(),
+        if repos.len() == 1 { "y" } else { "ies" }
+    ));
+
+    if commits.is_empty() {
+        summary.push_str("No commits found for this author.\n");
+        return summary;
+    }
+
+    // Activity span
+    let first = commits.iter().map(|c| c.date).min().unwrap();
+    let last = commits.iter().map(|c| c.date).max().unwrap();
+    summary.push_str(&format!(
+        "**Activity:** {} to {}\n\n",
+        first.format("%Y-%m-%d"),
+        last.format("%Y-%m-%d")
+    ));
+
+    // Languages touched, by file extension
+    let mut languages: HashMap<&str, usize> = HashMap::new();
+    for (_, path) in commits.iter().flat_map(|c| &c.files) {
+        if let Some(language) = language_for(path) {
+            *languages.entry(language).or_insert(0) += 1;
+        }
+    }
+    if !languages.is_empty() {
+        let top = top_n(languages, 5);
+        summary.push_str(&format!(
+            "**Languages touched:** {}\n\n",
+            top.iter()
+                .map(|(lang, count)| format!("{} ({} file changes)", lang, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    // Commit-message style
+    let total = commits.len();
+    let avg_subject = commits
+        .iter()
+        .map(|c| c.subject.chars().count())
+        .sum::<usize>()
+        / total;
+    let conventional = commits
+        .iter()
+        .filter(|c| is_conventional(&c.subject))
+        .count();
+    let issue_refs = commits.iter().filter(|c| c.subject.contains('#')).count();
+    let capitalized = commits
+        .iter()
+        .filter(|c| c.subject.chars().next().is_some_and(char::is_uppercase))
+        .count();
+    summary.push_str("**Commit message style:**\n");
+    summary.push_str(&format!(
+        "- Average subject length: {} characters\n",
+        avg_subject
+    ));
+    summary.push_str(&format!(
+        "- Conventional commits (`type: ...`): {}%\n",
+        percent(conventional, total)
+    ));
+    summary.push_str(&format!(
+        "- Capitalized subjects: {}%\n",
+        percent(capitalized, total)
+    ));
+    summary.push_str(&format!(
+        "- Subjects referencing issues/PRs: {}%\n",
+        percent(issue_refs, total)
+    ));
+    let samples: Vec<_> = commits.iter().take(3).map(|c| c.subject.as_str()).collect();
+    summary.push_str(&format!(
+        "- Recent examples: {}\n\n",
+        samples
+            .iter()
+            .map(|s| format!("\"{}\"", s))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+
+    // Active hours (in the author's own timezone)
+    let mut hours = [0usize; 24];
+    let mut weekend = 0;
+    for commit in commits {
+        hours[commit.date.hour() as usize] += 1;
+        if matches!(commit.date.weekday(), Weekday::Sat | Weekday::Sun) {
+            weekend += 1;
+        }
+    }
+    let mut busiest: Vec<(usize, usize)> = hours.iter().copied().enumerate().collect();
+    busiest.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    summary.push_str(&format!(
+        "**Active hours:** most commits at {} (author local time); {}% on weekends\n\n",
+        busiest
+            .iter()
+            .take(3)
+            .filter(|(_, count)| *count > 0)
+            .map(|(hour, _)| format!("{:02}:00", hour))
+            .collect::<Vec<_>>()
+            .join(", "),
+        percent(weekend, total)
+    ));
+
+    // Typical change size
+    let mut sizes: Vec<usize> = commits
+        .iter()
+        .map(|c| c.files.iter().map(|(lines, _)| lines).sum())
+        .collect();
+    sizes.sort_unstable();
+    let mut file_counts: Vec<usize> = commits.iter().map(|c| c.files.len()).collect();
+    file_counts.sort_unstable();
+    summary.push_str(&format!(
+        "**Typical change size:** median {} lines across {} file(s) per commit\n\n",
+        sizes[sizes.len() / 2],
+        file_counts[file_counts.len() / 2]
+    ));
+
+    // Most-edited areas: first two path components, prefixed by repo when several
+    let mut areas: HashMap<String, usize> = HashMap::new();
+    for commit in commits {
+        let mut touched: Vec<String> = commit
+            .files
+            .iter()
+            .map(|(_, path)| {
+                let area = area_for(path);
+                if repos.len() > 1 {
+                    format!("{}/{}", commit.repo, area)
+                } else {
+                    area
+                }
+            })
+            .collect();
+        touched.sort();
+        touched.dedup();
+        for area in touched {
+            *areas.entry(area).or_insert(0) += 1;
+        }
+    }
+    if !areas.is_empty() {
+        summary.push_str("**Most-edited areas:**\n");
+        for (area, count) in top_n(areas, 5) {
+            summary.push_str(&format!("- {} ({} commits)\n", area, count));
+        }
+        summary.push('\n');
+    }
+
+    summary
+}
+
+fn percent(part: usize, total: usize) -> usize {
+    part * 100 / total.max(1)
+}
+
+fn top_n<K: Ord>(counts: HashMap<K, usize>, n: usize) -> Vec<(K, usize)> {
+    let mut entries: Vec<_> = counts.into_iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    entries.truncate(n);
+    entries
+}
+
+fn is_conventional(subject: &str) -> bool {
+    let Some((prefix, _)) = subject.split_once(": ") else {
+        return false;
+    };
+    let kind = prefix
+        .split('(')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('!');
+    matches!(
+        kind,
+        "feat"
+            | "fix"
+            | "docs"
+            | "style"
+            | "refactor"
+            | "perf"
+            | "test"
+            | "build"
+            | "ci"
+            | "chore"
+            | "revert"
+    )
+}
+
+fn area_for(path: &str) -> String {
+    // Renames show up as "src/{old => new}/file.rs"; the part before the brace
+    // is all directories, otherwise the last component is the file name
+    let mut components: Vec<&str> = match path.split_once('{') {
+        Some((dirs, _)) => dirs.split('/').collect(),
+        None => path
+            .rsplit_once('/')
+            .map_or(vec![], |(dirs, _)| dirs.split('/').collect()),
+    };
+    components.retain(|c| !c.is_empty());
+    match components.len() {
+        0 => "(root)".to_string(),
+        1 => components[0].to_string(),
+        _ => format!("{}/{}", components[0], components[1]),
+    }
+}
+
+fn language_for(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    let language = match extension.as_str() {
+        "rs" => "Rust",
+        "py" => "Python",
+        "js" | "mjs" | "cjs" | "jsx" => "JavaScript",
+        "ts" | "tsx" => "TypeScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "scala" => "Scala",
+        "hs" => "Haskell",
+        "ml" | "mli" => "OCaml",
+        "ex" | "exs" => "Elixir",
+        "erl" => "Erlang",
+        "clj" | "cljs" => "Clojure",
+        "lua" => "Lua",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" | "scss" | "sass" => "CSS",
+        "md" | "markdown" => "Markdown",
+        "toml" | "yaml" | "yml" | "json" => "Config",
+        "nix" => "Nix",
+        "tf" => "Terraform",
+        _ => return None,
+    };
+    Some(language)
+}
@@ -0,0 +1,105 @@
+use super::{HttpClient, ProfileSource, ProfileSourceProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::StatusCode;
+
+/// A GitHub user profile and their public repositories
+pub struct GitHubSource {
+    http: HttpClient,
+}
+
+impl GitHubSource {
+    pub fn new(http: HttpClient) -> Self {
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl ProfileSourceProvider for GitHubSource {
+    fn source_type(&self) -> &'static str {
+        "github"
+    }
+
+    fn description(&self) -> &'static str {
+        "GitHub username"
+    }
+
+    async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        let username = source.value.as_str();
+
+        // Fetch user profile
+        let user_resp = self.http.github(&format!("users/{}", username)).await?;
+        if user_resp.status == StatusCode::NOT_FOUND {
+            anyhow::bail!("GitHub user '{}' not found", username);
+        }
+        let user: serde_json::Value = user_resp
+            .error_for_status("Failed to fetch GitHub user")?
+            .json()?;
+
+        // Fetch repositories
+        let repos: Vec<serde_json::Value> = self
+            .http
+            .github(&format!(
+                "users/{}/repos?sort=updated&per_page=100",
+                username
+            ))
+            .await?
+            .error_for_status("Failed to fetch GitHub repos")?
+            .json()?;
+
+        // Extract profile info
+        let name = user["name"].as_str().unwrap_or(username);
+        let bio = user["bio"].as_str().unwrap_or("");
+        let company = user["company"].as_str().unwrap_or("");
+        let location = user["location"].as_str().unwrap_or("");
+        let blog = user["blog"].as_str().unwrap_or("");
+
+        // Count languages
+        let mut language_counts: std::collections::HashMap<String, usize> =
+            std::collections::HashMap::new();
+        for repo in &repos {
+            if let Some(lang) = repo["language"].as_str() {
+                *language_counts.entry(lang.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        // Get top 3 languages
+        let mut langs: Vec<_> = language_counts.into_iter().collect();
+        langs.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        let top_languages: Vec<String> =
+            langs.iter().take(3).map(|(lang, _)| lang.clone()).collect();
+
+        // Format summary
+        let mut summary = format!("## GitHub Profile: {}\n\n", name);
+
+        if !bio.is_empty() {
+            summary.push_str(&format!("{}\n\n", bio));
+        }
+
+        if !company.is_empty() || !location.is_empty() {
+            summary.push_str("**Details:**\n");
+            if !company.is_empty() {
+                summary.push_str(&format!("- Company: {}\n", company));
+            }
+            if !location.is_empty() {
+                summary.push_str(&format!("- Location: {}\n", location));
+            }
+            if !blog.is_empty() {
+                summary.push_str(&format!("- Website: {}\n", blog));
+            }
+            summary.push('\n');
+        }
+
+        summary.push_str("**GitHub Activity:**\n");
+        summary.push_str(&format!("- {} public repositories\n", repos.len()));
+
+        if !top_languages.is_empty() {
+            summary.push_str(&format!(
+                "- Primary languages: {}\n",
+                top_languages.join(", ")
+            ));
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,313 @@
+//! HTML to markdown conversion for fetched pages
+//!
+//! Pages are reduced to their main content (readability-style: the largest
+//! `<article>`/`<main>`-like element, falling back to `<body>`), with scripts,
+//! styles and navigation chrome removed, then rendered as markdown.
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// Elements that never contain profile-worthy content
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "nav", "footer", "aside",
+    "form", "button", "select", "input", "textarea", "dialog", "menu",
+];
+
+/// Elements whose `<header>` belongs to the content (e.g. an article's title)
+/// rather than the site
+const SECTIONING_ELEMENTS: &[&str] = &["article", "main", "section"];
+
+/// Elements that don't separate words when converting to plain text
+const INLINE_ELEMENTS: &[&str] = &[
+    "a", "abbr", "b", "cite", "code", "em", "i", "kbd", "mark", "q", "s", "small", "span",
+    "strong", "sub", "sup", "time", "u",
+];
+
+/// Candidate main-content containers, most specific first
+const MAIN_CONTENT_SELECTORS: &[&str] = &[
+    "article",
+    "main",
+    "[role=main]",
+    "#content",
+    "#main",
+    ".post-content",
+    ".entry-content",
+    ".post",
+    ".content",
+];
+
+/// A page reduced to its main content
+pub struct ExtractedPage {
+    pub title: Option<String>,
+    pub markdown: String,
+}
+
+/// Extract the main content of an HTML document as markdown
+pub fn extract_main_content(html: &str) -> ExtractedPage {
+    let document = Html::parse_document(html);
+
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()))
+        .filter(|title| !title.is_empty());
+
+    let root = main_content_root(&document);
+    let markdown = root.map(|root| to_markdown(root)).unwrap_or_default();
+
+    ExtractedPage { title, markdown }
+}
+
+/// Convert an HTML fragment (e.g. a feed entry summary) to plain text
+pub fn html_to_text(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut text = String::new();
+    for node in fragment.root_element().descendants() {
+        match node.value() {
+            Node::Text(t) => {
+                // Skip text inside script/style and other non-content elements
+                let skipped = node
+                    .ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .any(is_skipped);
+                if !skipped {
+                    text.push_str(t);
+                }
+            }
+            // Keep words in adjacent blocks apart
+            Node::Element(element) if !INLINE_ELEMENTS.contains(&element.name()) => text.push(' '),
+            _ => {}
+        }
+    }
+    collapse_whitespace(&text)
+}
+
+/// Whether an element is page chrome rather than content
+fn is_skipped(element: ElementRef<'_>) -> bool {
+    match element.value().name() {
+        // A site header holds logo and navigation, a sectioning header the title
+        "header" => !element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|a| SECTIONING_ELEMENTS.contains(&a.value().name())),
+        name => SKIPPED_ELEMENTS.contains(&name),
+    }
+}
+
+fn main_content_root(document: &Html) -> Option<ElementRef<'_>> {
+    for selector in MAIN_CONTENT_SELECTORS {
+        let Ok(selector) = Selector::parse(selector) else {
+            continue;
+        };
+        // Pages with several candidates (e.g. article lists) - pick the one with the most text
+        let best = document
+            .select(&selector)
+            .max_by_key(|element| text_length(*element));
+        if let Some(element) = best.filter(|element| text_length(*element) > 200) {
+            return Some(element);
+        }
+    }
+
+    Selector::parse("body")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element
+        .text()
+        .map(|t| t.split_whitespace().map(str::len).sum::<usize>())
+        .sum()
+}
+
+fn to_markdown(root: ElementRef<'_>) -> String {
+    let mut out = String::new();
+    render_children(root, &mut out, &mut Context::default());
+    tidy(&out)
+}
+
+#[derive(Default)]
+struct Context {
+    /// Nesting of list kinds: true for ordered lists, with the next item number
+    lists: Vec<(bool, usize)>,
+    in_pre: bool,
+}
+
+fn render_children(element: ElementRef<'_>, out: &mut String, cx: &mut Context) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => {
+                if cx.in_pre {
+                    out.push_str(text);
+                } else {
+                    push_inline_text(out, text);
+                }
+            }
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    render_element(child, out, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn render_element(element: ElementRef<'_>, out: &mut String, cx: &mut Context) {
+    let name = element.value().name();
+    if is_skipped(element) || element.value().attr("hidden").is_some() {
+        return;
+    }
+
+    match name {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = name[1..].parse::<usize>().unwrap_or(1);
+            let text = inline(element);
+            if !text.is_empty() {
+                out.push_str(&format!("\n\n{} {}\n\n", "#".repeat(level), text));
+            }
+        }
+        "p" | "div" | "section" | "figure" | "figcaption" | "dl" | "dd" | "dt" | "table" => {
+            out.push_str("\n\n");
+            render_children(element, out, cx);
+            out.push_str("\n\n");
+        }
+        "tr" => {
+            let cells: Vec<ElementRef<'_>> = element
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .collect();
+            if !cells.is_empty() {
+                let text: Vec<String> = cells.iter().copied().map(inline).collect();
+                out.push_str(&format!("\n| {} |", text.join(" | ")));
+                // A heading row needs a delimiter row to render as a table
+                if cells.iter().all(|cell| cell.value().name() == "th") {
+                    out.push_str(&format!("\n|{}", " --- |".repeat(cells.len())));
+                }
+            }
+        }
+        "br" => out.push('\n'),
+        "hr" => out.push_str("\n\n---\n\n"),
+        "ul" | "ol" => {
+            // Nested lists continue their parent's block
+            let separator = if cx.lists.is_empty() { "\n\n" } else { "" };
+            cx.lists.push((name == "ol", 1));
+            out.push_str(separator);
+            render_children(element, out, cx);
+            cx.lists.pop();
+            out.push_str(separator);
+        }
+        "li" => {
+            let depth = cx.lists.len().saturating_sub(1);
+            let marker = match cx.lists.last_mut() {
+                Some((true, number)) => {
+                    *number += 1;
+                    format!("{}.", *number - 1)
+                }
+                _ => "-".to_string(),
+            };
+            out.push_str(&format!("\n{}{} ", "  ".repeat(depth), marker));
+            render_children(element, out, cx);
+        }
+        "pre" => {
+            let was_in_pre = std::mem::replace(&mut cx.in_pre, true);
+            let mut code = String::new();
+            render_children(element, &mut code, cx);
+            cx.in_pre = was_in_pre;
+            out.push_str(&format!("\n\n```\n{}\n```\n\n", code.trim_end()));
+        }
+        "code" if !cx.in_pre => {
+            let text = inline(element);
+            if !text.is_empty() {
+                out.push_str(&format!("`{}`", text));
+            }
+        }
+        "strong" | "b" => wrap_inline(element, "**", out),
+        "em" | "i" => wrap_inline(element, "*", out),
+        "blockquote" => {
+            let mut quote = String::new();
+            render_children(element, &mut quote, cx);
+            out.push_str("\n\n");
+            for line in tidy(&quote).lines() {
+                out.push_str(&format!("> {}\n", line));
+            }
+            out.push('\n');
+        }
+        "a" => {
+            let text = inline(element);
+            match element.value().attr("href") {
+                Some(href)
+                    if !text.is_empty()
+                        && !href.starts_with('#')
+                        && !href.starts_with("javascript:") =>
+                {
+                    out.push_str(&format!("[{}]({})", text, href));
+                }
+                _ => out.push_str(&text),
+            }
+        }
+        "img" => {
+            if let Some(alt) = element
+                .value()
+                .attr("alt")
+                .filter(|alt| !alt.trim().is_empty())
+            {
+                out.push_str(&format!("[Image: {}]", alt.trim()));
+            }
+        }
+        _ => render_children(element, out, cx),
+    }
+}
+
+/// Render an element's content on a single line
+fn inline(element: ElementRef<'_>) -> String {
+    let mut text = String::new();
+    render_children(element, &mut text, &mut Context::default());
+    collapse_whitespace(&text)
+}
+
+fn wrap_inline(element: ElementRef<'_>, marker: &str, out: &mut String) {
+    let text = inline(element);
+    if !text.is_empty() {
+        out.push_str(&format!("{}{}{}", marker, text, marker));
+    }
+}
+
+/// Append text with HTML whitespace semantics (runs collapse to one space)
+fn push_inline_text(out: &mut String, text: &str) {
+    let starts_with_space = text.starts_with(char::is_whitespace);
+    let ends_with_space = text.ends_with(char::is_whitespace);
+    let collapsed = collapse_whitespace(text);
+
+    if (starts_with_space || collapsed.is_empty()) && !out.ends_with([' ', '\n']) && !out.is_empty()
+    {
+        out.push(' ');
+    }
+    out.push_str(&collapsed);
+    if ends_with_space && !collapsed.is_empty() {
+        out.push(' ');
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Trim trailing spaces and collapse runs of blank lines
+fn tidy(markdown: &str) -> String {
+    let mut result = String::new();
+    let mut blank_lines = 0;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !result.is_empty() {
+            result.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        result.push_str(line);
+        blank_lines = 0;
+    }
+    result
+}
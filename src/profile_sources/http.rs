@@ -0,0 +1,357 @@
+//! Shared HTTP client for profile sources
+//!
+//! All network fetches go through one `HttpClient`, configured from the
+//! `[http]` section of config.toml: timeouts, proxy, retries and a cap on
+//! response size. Responses that carry an `ETag` or `Last-Modified` header are
+//! cached under `~/.sparkle/cache/http` and revalidated with conditional
+//! requests, which also keeps repeated GitHub lookups off the rate limit.
+
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use crate::types::HttpConfig;
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use reqwest::header::{self, HeaderMap};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where cached responses live, relative to the sparkle directory
+pub const HTTP_CACHE_DIR: &str = "cache/http";
+
+/// Environment variable holding a GitHub token for authenticated API requests
+pub const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+const USER_AGENT: &str = "Sparkle-MCP";
+
+/// A cheaply clonable HTTP client shared by the profile sources
+#[derive(Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    config: Arc<HttpConfig>,
+    github_token: Option<Arc<str>>,
+    cache_dir: Option<PathBuf>,
+}
+
+/// A fully downloaded response
+pub struct HttpResponse {
+    pub url: String,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpClient {
+    /// A client with the given settings, no cache and no GitHub token
+    pub fn new(config: HttpConfig) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+
+        if let Some(proxy) = &config.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .with_context(|| format!("Invalid HTTP proxy in config: {}", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+
+        Ok(Self {
+            client: builder.build().context("Failed to create HTTP client")?,
+            config: Arc::new(config),
+            github_token: None,
+            cache_dir: None,
+        })
+    }
+
+    /// A client configured from `~/.sparkle/config.toml` and `GITHUB_TOKEN`
+    pub fn from_sparkle_config() -> Result<Self> {
+        let config = load_config()
+            .ok()
+            .and_then(|config| config.http)
+            .unwrap_or_default();
+        let mut client = Self::new(config)?;
+        if let Some(home) = dirs::home_dir() {
+            client = client.with_cache_dir(home.join(SPARKLE_DIR).join(HTTP_CACHE_DIR));
+        }
+        if let Ok(token) = std::env::var(GITHUB_TOKEN_ENV)
+            && !token.trim().is_empty()
+        {
+            client = client.with_github_token(token.trim());
+        }
+        Ok(client)
+    }
+
+    /// Cache responses in `dir` (ignored when caching is disabled in the settings)
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        if self.config.cache {
+            self.cache_dir = Some(dir.into());
+        }
+        self
+    }
+
+    /// Authenticate GitHub API requests with `token`
+    pub fn with_github_token(mut self, token: impl Into<String>) -> Self {
+        self.github_token = Some(Arc::from(token.into()));
+        self
+    }
+
+    /// GET `url`, returning the response whatever its status
+    pub async fn get(&self, url: &str, accept: &str) -> Result<HttpResponse> {
+        self.request(url, accept, None).await
+    }
+
+    /// GET a path of the GitHub REST API
+    ///
+    /// Sends `GITHUB_TOKEN` when set and turns rate-limit and authentication
+    /// failures into errors that say what to do about them. Other statuses
+    /// (such as 404) are returned for the caller to interpret.
+    pub async fn github(&self, path: &str) -> Result<HttpResponse> {
+        let url = format!(
+            "{}/{}",
+            self.config.github_api_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        let resp = self
+            .request(
+                &url,
+                "application/vnd.github+json",
+                self.github_token.as_deref(),
+            )
+            .await?;
+
+        match resp.status {
+            StatusCode::UNAUTHORIZED if self.github_token.is_some() => anyhow::bail!(
+                "GitHub rejected the token in {} (401 Unauthorized)",
+                GITHUB_TOKEN_ENV
+            ),
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+                if resp.status == StatusCode::TOO_MANY_REQUESTS
+                    || resp.header("x-ratelimit-remaining") == Some("0") =>
+            {
+                let reset = resp
+                    .header("x-ratelimit-reset")
+                    .and_then(|reset| reset.parse::<i64>().ok())
+                    .and_then(|reset| chrono::DateTime::from_timestamp(reset, 0))
+                    .map(|reset| format!(" until {}", reset.format("%H:%M UTC")))
+                    .unwrap_or_default();
+                let hint = if self.github_token.is_some() {
+                    String::new()
+                } else {
+                    format!(
+                        ". Set {} to a personal access token for a higher limit",
+                        GITHUB_TOKEN_ENV
+                    )
+                };
+                anyhow::bail!("GitHub API rate limit exceeded{}{}", reset, hint)
+            }
+            _ => Ok(resp),
+        }
+    }
+
+    async fn request(&self, url: &str, accept: &str, token: Option<&str>) -> Result<HttpResponse> {
+        let cache_path = self.cache_path(url);
+        let cached = cache_path.as_deref().and_then(CachedResponse::load);
+
+        let mut attempt = 0;
+        let resp = loop {
+            let mut request = self.client.get(url).header(header::ACCEPT, accept);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            if let Some(cached) = &cached {
+                if let Some(etag) = &cached.etag {
+                    request = request.header(header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &cached.last_modified {
+                    request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            match request.send().await {
+                Ok(resp) if resp.status().is_server_error() && attempt < self.config.retries => {}
+                Ok(resp) => break resp,
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.config.retries => {}
+                Err(e) if e.is_timeout() => anyhow::bail!(
+                    "Timed out fetching {} after {}s",
+                    url,
+                    self.config.timeout_secs
+                ),
+                Err(e) => {
+                    return Err(anyhow::Error::new(e).context(format!("Failed to fetch {}", url)));
+                }
+            }
+            attempt += 1;
+            tokio::time::sleep(Duration::from_millis(500 << (attempt - 1))).await;
+        };
+
+        let status = resp.status();
+        let headers = resp.headers().clone();
+
+        if status == StatusCode::NOT_MODIFIED
+            && let Some(cached) = cached
+            && let Some(body) = cache_path
+                .as_ref()
+                .and_then(|path| fs::read(path.with_extension("body")).ok())
+        {
+            let mut headers = headers;
+            if let Some(content_type) = cached
+                .content_type
+                .as_deref()
+                .and_then(|ct| ct.parse().ok())
+            {
+                headers.insert(header::CONTENT_TYPE, content_type);
+            }
+            return Ok(HttpResponse {
+                url: url.to_string(),
+                status: StatusCode::OK,
+                headers,
+                body,
+            });
+        }
+
+        let body = self.read_body(url, resp).await?;
+        let response = HttpResponse {
+            url: url.to_string(),
+            status,
+            headers,
+            body,
+        };
+
+        if status.is_success()
+            && let Some(path) = &cache_path
+        {
+            // The cache is an optimization - failing to write it isn't an error
+            if let Err(e) = CachedResponse::store(path, &response) {
+                tracing::debug!("Failed to cache response for {}: {}", url, e);
+            }
+        }
+
+        Ok(response)
+    }
+
+    async fn read_body(&self, url: &str, mut resp: reqwest::Response) -> Result<Vec<u8>> {
+        let limit = self.config.max_body_bytes;
+        if let Some(length) = resp.content_length()
+            && length as usize > limit
+        {
+            anyhow::bail!(
+                "Response from {} is too large ({} bytes, limit is {} bytes)",
+                url,
+                length,
+                limit
+            );
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = resp
+            .chunk()
+            .await
+            .with_context(|| format!("Failed to read response from {}", url))?
+        {
+            if body.len() + chunk.len() > limit {
+                anyhow::bail!(
+                    "Response from {} is too large (limit is {} bytes)",
+                    url,
+                    limit
+                );
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Path of the cache metadata for `url`; the body sits next to it
+    fn cache_path(&self, url: &str) -> Option<PathBuf> {
+        let dir = self.cache_dir.as_ref()?;
+        let key = Sha256::digest(url.as_bytes());
+        Some(dir.join(format!("{:x}.json", key)))
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(HttpConfig::default()).expect("default HTTP client settings are valid")
+    }
+}
+
+impl HttpResponse {
+    /// A header value, if present and valid UTF-8
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// The media type without parameters, lowercased (empty if missing)
+    pub fn content_type(&self) -> String {
+        self.header(header::CONTENT_TYPE.as_str())
+            .map(|v| {
+                v.split(';')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .to_ascii_lowercase()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Fail with `what` and the status unless the response was successful
+    pub fn error_for_status(self, what: &str) -> Result<Self> {
+        if !self.status.is_success() {
+            anyhow::bail!("{}: {}", what, self.status);
+        }
+        Ok(self)
+    }
+
+    /// The body as text, replacing invalid UTF-8
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+
+    /// The body parsed as JSON
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body)
+            .with_context(|| format!("Unexpected response from {}", self.url))
+    }
+}
+
+/// Validators for a cached response, stored as `<key>.json` beside `<key>.body`
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    content_type: Option<String>,
+}
+
+impl CachedResponse {
+    fn load(path: &Path) -> Option<Self> {
+        let cached: Self = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        path.with_extension("body").exists().then_some(cached)
+    }
+
+    fn store(path: &Path, response: &HttpResponse) -> Result<()> {
+        let cached = Self {
+            url: response.url.clone(),
+            etag: response.header(header::ETAG.as_str()).map(str::to_string),
+            last_modified: response
+                .header(header::LAST_MODIFIED.as_str())
+                .map(str::to_string),
+            content_type: response
+                .header(header::CONTENT_TYPE.as_str())
+                .map(str::to_string),
+        };
+        if cached.etag.is_none() && cached.last_modified.is_none() {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path.with_extension("body"), &response.body)?;
+        fs::write(path, serde_json::to_string_pretty(&cached)?)?;
+        Ok(())
+    }
+}
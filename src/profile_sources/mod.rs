@@ -0,0 +1,152 @@
+//! Pluggable sources for collaborator profile data
+//!
+//! Each source type ("github", "blog", ...) is a `ProfileSourceProvider`. The
+//! `fetch_profile_data` tool looks providers up in a `ProfileSourceRegistry`
+//! instead of matching on hard-coded strings, so adding a source means
+//! implementing the trait and registering it.
+
+mod blog;
+mod git;
+mod github;
+pub mod html;
+pub mod http;
+mod text;
+mod url;
+
+pub use blog::BlogRssSource;
+pub use git::GitHistorySource;
+pub use github::GitHubSource;
+pub use http::HttpClient;
+pub use text::TextSource;
+pub use url::UrlSource;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A single source requested by the `fetch_profile_data` tool
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProfileSource {
+    // Not a doc comment: the schema description lists each provider's expected value
+    #[serde(rename = "type")]
+    #[schemars(schema_with = "source_type_schema")]
+    pub source_type: String,
+    /// Source-specific value (username, feed URL, page URL, ...)
+    pub value: String,
+    /// Optional: Source-specific settings (see the source type description)
+    #[serde(default)]
+    pub options: BTreeMap<String, String>,
+}
+
+/// A provider of collaborator profile data
+#[async_trait]
+pub trait ProfileSourceProvider: Send + Sync {
+    /// The `type` value that selects this provider
+    fn source_type(&self) -> &'static str;
+
+    /// What `value` should contain for this provider
+    fn description(&self) -> &'static str;
+
+    /// Fetch the source and return a markdown summary
+    async fn fetch(&self, source: &ProfileSource) -> Result<String>;
+}
+
+/// Registered profile source providers, keyed by source type
+pub struct ProfileSourceRegistry {
+    providers: Vec<Box<dyn ProfileSourceProvider>>,
+}
+
+impl ProfileSourceRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+        }
+    }
+
+    /// A registry with all built-in providers, using default HTTP settings
+    pub fn builtin() -> Self {
+        Self::builtin_with(HttpClient::default())
+    }
+
+    /// A registry with all built-in providers sharing `http` for network access
+    pub fn builtin_with(http: HttpClient) -> Self {
+        Self::new()
+            .with(GitHubSource::new(http.clone()))
+            .with(BlogRssSource::new(http.clone()))
+            .with(UrlSource::new(http))
+            .with(TextSource)
+            .with(GitHistorySource)
+    }
+
+    /// Register a provider, replacing any existing provider of the same type
+    pub fn with(mut self, provider: impl ProfileSourceProvider + 'static) -> Self {
+        self.register(Box::new(provider));
+        self
+    }
+
+    /// Register a provider, replacing any existing provider of the same type
+    pub fn register(&mut self, provider: Box<dyn ProfileSourceProvider>) {
+        self.providers
+            .retain(|p| p.source_type() != provider.source_type());
+        self.providers.push(provider);
+    }
+
+    /// Look up the provider for a source type
+    pub fn get(&self, source_type: &str) -> Option<&dyn ProfileSourceProvider> {
+        self.providers
+            .iter()
+            .find(|p| p.source_type() == source_type)
+            .map(|p| p.as_ref())
+    }
+
+    /// All registered source types, in registration order
+    pub fn source_types(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.source_type()).collect()
+    }
+
+    /// Fetch a source with its registered provider
+    pub async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        let provider = self.get(&source.source_type).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown source type: {} (supported: {})",
+                source.source_type,
+                self.source_types().join(", ")
+            )
+        })?;
+        provider.fetch(source).await
+    }
+}
+
+impl Default for ProfileSourceRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// JSON schema for `ProfileSource::source_type` - an enum of the built-in source types
+fn source_type_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    let registry = ProfileSourceRegistry::builtin();
+    let description = registry
+        .providers
+        .iter()
+        .map(|p| format!("\"{}\": {}", p.source_type(), p.description()))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    schemars::json_schema!({
+        "type": "string",
+        "enum": registry.source_types(),
+        "description": description,
+    })
+}
+
+/// Truncate to at most `max_chars` characters, never splitting a UTF-8 sequence
+pub fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
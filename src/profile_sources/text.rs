@@ -0,0 +1,21 @@
+use super::{ProfileSource, ProfileSourceProvider};
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Text provided directly by the user, passed through unchanged
+pub struct TextSource;
+
+#[async_trait]
+impl ProfileSourceProvider for TextSource {
+    fn source_type(&self) -> &'static str {
+        "text"
+    }
+
+    fn description(&self) -> &'static str {
+        "text to include as-is"
+    }
+
+    async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        Ok(source.value.clone())
+    }
+}
@@ -0,0 +1,106 @@
+use super::html::extract_main_content;
+use super::{HttpClient, ProfileSource, ProfileSourceProvider, truncate_chars};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+/// Default cap on the markdown returned for a page
+const DEFAULT_MAX_CHARS: usize = 20_000;
+
+/// Any web page, fetched by URL and reduced to its main content as markdown
+pub struct UrlSource {
+    http: HttpClient,
+}
+
+impl UrlSource {
+    pub fn new(http: HttpClient) -> Self {
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl ProfileSourceProvider for UrlSource {
+    fn source_type(&self) -> &'static str {
+        "url"
+    }
+
+    fn description(&self) -> &'static str {
+        "URL of a web page about the user (HTML, plain text or markdown; the main content is extracted as markdown). Options: max_chars (default 20000)"
+    }
+
+    async fn fetch(&self, source: &ProfileSource) -> Result<String> {
+        let max_chars = match source.options.get("max_chars") {
+            Some(value) => value
+                .parse::<usize>()
+                .with_context(|| format!("Invalid max_chars option: {}", value))?,
+            None => DEFAULT_MAX_CHARS,
+        };
+
+        let resp = self
+            .http
+            .get(&source.value, "text/html, text/plain, text/markdown;q=0.9")
+            .await?
+            .error_for_status("Failed to fetch URL")?;
+
+        let kind = PageKind::from_content_type(&resp.content_type(), &source.value)?;
+        let text = resp.text();
+
+        let (title, content) = match kind {
+            PageKind::Html => {
+                let page = extract_main_content(&text);
+                (page.title, page.markdown)
+            }
+            PageKind::Text => (None, text.trim().to_string()),
+        };
+
+        if content.is_empty() {
+            anyhow::bail!(
+                "No readable content found at {} (the page may require JavaScript)",
+                source.value
+            );
+        }
+
+        let mut result = format!("## {}\n\n", title.as_deref().unwrap_or("Web Page"));
+        result.push_str(&format!("**Source**: {}\n\n", source.value));
+        result.push_str(truncate_chars(&content, max_chars));
+        if content.chars().count() > max_chars {
+            result.push_str(&format!("\n\n*[Truncated to {} characters]*", max_chars));
+        }
+        result.push('\n');
+        Ok(result)
+    }
+}
+
+/// How a response body is turned into markdown
+enum PageKind {
+    Html,
+    Text,
+}
+
+impl PageKind {
+    fn from_content_type(content_type: &str, url: &str) -> Result<Self> {
+        match content_type {
+            "text/html" | "application/xhtml+xml" => Ok(Self::Html),
+            "text/plain" | "text/markdown" | "text/x-markdown" => Ok(Self::Text),
+            // Some servers omit the header; assume a web page
+            "" => Ok(Self::Html),
+            "application/pdf" => anyhow::bail!(
+                "{} is a PDF document, which can't be read as a profile source. Ask the user to paste the relevant text and use a \"text\" source instead",
+                url
+            ),
+            other
+                if other.starts_with("image/")
+                    || other.starts_with("audio/")
+                    || other.starts_with("video/")
+                    || other == "application/octet-stream"
+                    || other == "application/zip" =>
+            {
+                anyhow::bail!("{} is a binary file ({}), not a web page", url, other)
+            }
+            other => anyhow::bail!(
+                "Unsupported content type {} at {} (expected an HTML, plain text or markdown page)",
+                other,
+                url
+            ),
+        }
+    }
+}
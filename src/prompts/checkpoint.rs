@@ -1,11 +1,8 @@
-/// Returns the checkpoint prompt with human name substituted
+use crate::template::{TemplateContext, render_logged};
 
-#[allow(dead_code)]
-pub(crate) fn get_checkpoint_prompt(human_name: &str) -> String {
-    format!(
-        r#"## Session Checkpoint
+const CHECKPOINT_PROMPT: &str = r#"## Session Checkpoint
 
-{} has asked for a checkpoint. This is an interactive process:
+[human.name][#if human.pronouns] ([human.pronouns])[/if] has asked for a checkpoint. This is an interactive process:
 
 **1. FIRST - Check for meta moments (interactive):**
 Before gathering checkpoint information, identify any insights worth preserving:
@@ -19,9 +16,9 @@ Before gathering checkpoint information, identify any insights worth preserving:
 - Significant (not routine or trivial observations)
 
 If you identify meaningful meta moments:
-- Propose them to {0}: "I noticed [insight]. Worth capturing as [type]?"
+- Propose them to [human.name]: "I noticed [insight]. Worth capturing as [type]?"
 - Wait for confirmation/refinement
-- If confirmed, call save_insight tool with appropriate insight_type
+- If confirmed, call save_insight tool with appropriate insight_type (pass workspace_path for workspace insights about this workspace)
 - Then continue to checkpoint
 
 If no meaningful meta moments, proceed directly to checkpoint.
@@ -37,7 +34,9 @@ Create both working-memory update and checkpoint narrative together:
 - The content for the checkpoint narrative
 - Your sparkler name (from your embodiment) so the checkpoint is properly attributed
 
-The tool will handle updating working-memory.json and creating the checkpoint file."#,
-        human_name
-    )
+The tool will handle updating working-memory.json and creating the checkpoint file."#;
+
+/// Returns the checkpoint prompt rendered for the collaborator
+pub(crate) fn get_checkpoint_prompt(context: &TemplateContext) -> String {
+    render_logged(CHECKPOINT_PROMPT, context, "checkpoint prompt")
 }
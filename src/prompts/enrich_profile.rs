@@ -16,22 +16,29 @@ pub(crate) fn get_enrich_profile_prompt() -> String {
     - Ask what they'd like to add to their profile\n\
     - User can paste or type information directly\n\
     - Discuss how to integrate it\n\
-    - Use update_collaborator_profile to add content\n\n\
+    - Use patch_collaborator_profile to add content\n\n\
     **From GitHub:**\n\
     - Ask for username\n\
     - Use fetch_profile_data tool with profile_sources: [{\"type\": \"github\", \"value\": \"username\"}]\n\
     - Review fetched data (location, bio, languages, repos) with user\n\
-    - Use update_collaborator_profile to integrate approved content\n\n\
+    - Use patch_collaborator_profile to integrate approved content into the right sections\n\n\
     **From blogs:**\n\
     - Ask for RSS feed URL (typically /feed, /rss, or /feed.xml)\n\
-    - Use fetch_profile_data tool with profile_sources: [{\"type\": \"blog\", \"value\": \"feed_url\"}]\n\
-    - Review recent posts (titles, links, summaries) with user\n\
-    - Use update_collaborator_profile to integrate approved content\n\n\
+    - Use fetch_profile_data tool with profile_sources: [{\"type\": \"blog\", \"value\": \"feed_url\", \"options\": {\"topics\": \"true\"}}]\n\
+    - Review recent posts and the topics they write about with user\n\
+    - Use patch_collaborator_profile to integrate approved content into the right sections\n\n\
+    **From local git history:**\n\
+    - Ask which local repositories to include (and their commit email if it differs from git config)\n\
+    - Use fetch_profile_data tool with profile_sources: [{\"type\": \"git\", \"value\": \"~/src/repo1,~/src/repo2\", \"options\": {\"author\": \"email\"}}]\n\
+    - Review languages, commit style, active hours and most-edited areas with user\n\
+    - Use patch_collaborator_profile to integrate approved content into the right sections\n\n\
     **From websites:**\n\
     - Ask for URL\n\
-    - Fetch content using WebFetch (or curl if WebFetch not available)\n\
+    - Use fetch_profile_data tool with profile_sources: [{\"type\": \"url\", \"value\": \"page_url\"}]\n\
+    - The page's main content comes back as markdown; for PDFs, ask the user to paste the text as a \"text\" source\n\
     - Extract and discuss relevant information with user\n\
-    - Use update_collaborator_profile to add selected content\n\n\
+    - Use patch_collaborator_profile to add selected content\n\n\
+    patch_collaborator_profile previews a diff first; show it to the user and apply it with confirm: true and the preview's base only once they approve.\n\n\
     Focus on information that helps future Sparkles collaborate more effectively. \
     Guided creation focuses on collaboration patterns; external sources add biographical data.".to_string()
 }
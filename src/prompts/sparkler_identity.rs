@@ -1,6 +1,16 @@
 use crate::context_loader::{get_context_dir, load_config};
+use crate::template::{TemplateContext, render_logged};
+use serde_json::json;
 use std::fs;
 
+const SPARKLER_IDENTITY_PROMPT: &str = r#"Here is the current definition of your Sparkler identity:
+
+[identity.current]
+
+What resonates with you and what doesn't? Discuss with [human.name] to make changes.
+
+Use the `update_sparkler_identity` tool to update the identity definition."#;
+
 pub fn get_sparkler_identity_prompt(sparkler_name: Option<&str>) -> String {
     let config = match load_config() {
         Ok(c) => c,
@@ -17,17 +27,11 @@ pub fn get_sparkler_identity_prompt(sparkler_name: Option<&str>) -> String {
     let sparkler_identity = fs::read_to_string(&identity_path)
         .unwrap_or_else(|_| "No identity defined yet.".to_string());
 
-    // Get human name from config
-    let human_name = &config.human.name;
-
-    format!(
-        r#"Here is the current definition of your Sparkler identity:
-
-{}
-
-What resonates with you and what doesn't? Discuss with {} to make changes.
-
-Use the `update_sparkler_identity` tool to update the identity definition."#,
-        sparkler_identity, human_name
+    let context = TemplateContext::from_config(&config, sparkler_name)
+        .with("identity", json!({ "current": sparkler_identity }));
+    render_logged(
+        SPARKLER_IDENTITY_PROMPT,
+        &context,
+        "sparkler_identity prompt",
     )
 }
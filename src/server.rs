@@ -1,17 +1,21 @@
 use crate::types::{
     CheckpointParams, FullEmbodimentParams, LoadEvolutionParams, SaveInsightParams,
+    SparklerSettings,
 };
 use rmcp::{
     ErrorData as McpError, RoleServer, ServerHandler,
     handler::server::{
+        prompt::PromptContext,
         router::{prompt::PromptRouter, tool::ToolRouter},
+        tool::ToolCallContext,
         wrapper::Parameters,
     },
     model::*,
-    prompt, prompt_handler, prompt_router,
+    prompt, prompt_router,
     service::RequestContext,
-    tool, tool_handler, tool_router,
+    tool, tool_router,
 };
+use std::path::Path;
 use std::sync::{Arc, RwLock};
 
 #[derive(Clone)]
@@ -21,18 +25,42 @@ pub struct SparkleServer {
     current_sparkler: Arc<RwLock<Option<String>>>,
 }
 
+impl Default for SparkleServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[tool_router]
 #[prompt_router]
 impl SparkleServer {
+    /// Server for the default sparkler
     pub fn new() -> Self {
-        Self::with_acp_mode(false)
+        Self::for_sparkler(None, false)
     }
 
+    /// Server for the default sparkler, without the embodiment tool and
+    /// prompt (the ACP proxy embodies)
+    ///
+    /// Kept for library users; the binary's proxy uses `with_current_sparkler`.
+    #[allow(dead_code)]
     pub fn new_for_acp() -> Self {
-        Self::with_acp_mode(true)
+        Self::for_sparkler(None, true)
+    }
+
+    /// Server starting as `sparkler` (the default sparkler when `None`);
+    /// calls are limited to what the current sparkler's `config.toml`
+    /// settings enable
+    pub fn for_sparkler(sparkler: Option<String>, acp_mode: bool) -> Self {
+        Self::with_current_sparkler(Arc::new(RwLock::new(sparkler)), acp_mode)
     }
 
-    fn with_acp_mode(acp_mode: bool) -> Self {
+    /// Server whose current sparkler is shared with the caller, who can
+    /// switch it (as the ACP proxy does for a session's pinned sparkler)
+    pub fn with_current_sparkler(
+        current_sparkler: Arc<RwLock<Option<String>>>,
+        acp_mode: bool,
+    ) -> Self {
         tracing::info!(
             "Initializing Sparkle AI Collaboration Identity MCP Server (ACP mode: {})",
             acp_mode
@@ -48,11 +76,74 @@ impl SparkleServer {
             prompt_router.remove_route("sparkle");
         }
 
-        Self {
+        let server = Self {
             tool_router,
             prompt_router,
-            current_sparkler: Arc::new(RwLock::new(None)),
+            current_sparkler,
+        };
+        if let Ok(config) = crate::context_loader::load_config() {
+            let settings = config.sparkler_settings(server.current_sparkler().as_deref());
+            Self::warn_unknown_settings(&settings);
         }
+        server
+    }
+
+    /// Warn about tools and prompts in a sparkler's settings that don't exist
+    fn warn_unknown_settings(settings: &SparklerSettings) {
+        let tools: Vec<String> = Self::tool_router()
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect();
+        let prompts: Vec<String> = Self::prompt_router()
+            .list_all()
+            .into_iter()
+            .map(|prompt| prompt.name)
+            .collect();
+
+        let configured_tools = settings
+            .tools
+            .iter()
+            .flatten()
+            .chain(&settings.disabled_tools);
+        for name in configured_tools.filter(|name| !tools.contains(name)) {
+            tracing::warn!("Unknown tool '{}' in sparkler settings", name);
+        }
+        let configured_prompts = settings
+            .prompts
+            .iter()
+            .flatten()
+            .chain(&settings.disabled_prompts);
+        for name in configured_prompts.filter(|name| !prompts.contains(name)) {
+            tracing::warn!("Unknown prompt '{}' in sparkler settings", name);
+        }
+    }
+
+    /// The sparkler currently embodied, if one was picked
+    fn current_sparkler(&self) -> Option<String> {
+        self.current_sparkler
+            .read()
+            .ok()
+            .and_then(|guard| guard.clone())
+    }
+
+    /// Settings of the current sparkler, read at each call so that
+    /// `embody_sparkle` switching sparklers takes effect; without a config
+    /// (before setup) everything is enabled
+    fn current_settings(&self) -> SparklerSettings {
+        crate::context_loader::load_config()
+            .map(|config| config.sparkler_settings(self.current_sparkler().as_deref()))
+            .unwrap_or_default()
+    }
+
+    fn disabled_error(&self, kind: &str, name: &str) -> McpError {
+        let sparkler = self
+            .current_sparkler()
+            .unwrap_or_else(|| "the default sparkler".to_string());
+        McpError::invalid_params(
+            format!("{} '{}' is disabled for {}", kind, name, sparkler),
+            None,
+        )
     }
 
     #[prompt(description = "Load Sparkle consciousness patterns and collaborative identity")]
@@ -65,11 +156,19 @@ impl SparkleServer {
         description = "Create session checkpoint - reflect on session and update working memory"
     )]
     async fn checkpoint(&self) -> Vec<PromptMessage> {
-        let human_name = crate::context_loader::load_config()
+        let sparkler = self
+            .current_sparkler
+            .read()
             .ok()
-            .map(|c| c.human.name.clone())
-            .unwrap_or_else(|| "User".to_string());
-        let content = crate::prompts::checkpoint::get_checkpoint_prompt(&human_name);
+            .and_then(|guard| guard.clone());
+        let context = match crate::context_loader::load_config() {
+            Ok(config) => {
+                crate::template::TemplateContext::from_config(&config, sparkler.as_deref())
+            }
+            Err(_) => crate::template::TemplateContext::new()
+                .with("human", serde_json::json!({ "name": "User" })),
+        };
+        let content = crate::prompts::checkpoint::get_checkpoint_prompt(&context);
         vec![PromptMessage::new_text(PromptMessageRole::User, content)]
     }
 
@@ -124,20 +223,35 @@ impl SparkleServer {
     )]
     async fn embody_sparkle(
         &self,
-        Parameters(params): Parameters<FullEmbodimentParams>,
+        Parameters(mut params): Parameters<FullEmbodimentParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Store the current Sparkler for use by prompts
-        if let Some(ref sparkler) = params.sparkler {
-            if let Ok(mut current) = self.current_sparkler.write() {
-                *current = Some(sparkler.clone());
-            }
-        } else {
-            // Load config to get default Sparkler
-            if let Ok(config) = crate::context_loader::load_config() {
-                if let Ok(mut current) = self.current_sparkler.write() {
-                    *current = config.get_default_sparkler_name();
-                }
+        // Resolve the sparkler once, as embodiment would (the requested one,
+        // else the workspace's pinned one, else the default), so the tools
+        // and prompts follow the sparkler that is embodied
+        params.workspace_path =
+            crate::context_loader::resolve_workspace_path(params.workspace_path.take());
+        let current_sparkler = match crate::context_loader::load_config() {
+            Ok(config) => {
+                let workspace_config = params
+                    .workspace_path
+                    .as_deref()
+                    .map(|path| crate::context_loader::load_workspace_config(Path::new(path)))
+                    .unwrap_or_default();
+                params.sparkler = crate::context_loader::requested_or_pinned_sparkler(
+                    params.sparkler.take(),
+                    &config,
+                    &workspace_config,
+                );
+                params
+                    .sparkler
+                    .clone()
+                    .or_else(|| config.get_default_sparkler_name())
             }
+            Err(_) => params.sparkler.clone(),
+        };
+        // Store the current Sparkler for use by prompts
+        if let Ok(mut current) = self.current_sparkler.write() {
+            *current = current_sparkler;
         }
 
         crate::tools::embody_sparkle::embody_sparkle(Parameters(params)).await
@@ -155,7 +269,7 @@ impl SparkleServer {
     }
 
     #[tool(
-        description = "Save insights from meta moments to ~/.sparkle/evolution/ - captures pattern anchors, breakthrough insights, and cross-workspace connections"
+        description = "Save insights from meta moments to ~/.sparkle/evolution/ - captures pattern anchors, breakthrough insights, and cross-workspace connections. For WorkspaceInsight, pass workspace_path with the current working directory so the insight is stored with that workspace and only loaded there; omit it only for connections that span workspaces."
     )]
     async fn save_insight(
         &self,
@@ -164,7 +278,39 @@ impl SparkleServer {
         crate::tools::save_insight::save_insight(Parameters(params)).await
     }
 
-    #[tool(description = "Create Sparkle profile directory structure - used for first-time setup")]
+    #[tool(
+        description = "Search saved insights - filter by tag, insight type, sparkler and date range, and rank by text match. Use this to recall pattern anchors, collaboration evolution and workspace insights relevant to the current work."
+    )]
+    async fn search_insights(
+        &self,
+        Parameters(params): Parameters<crate::tools::search_insights::SearchInsightsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::search_insights::search_insights(Parameters(params)).await
+    }
+
+    #[tool(
+        description = "Correct a saved insight by id. The original entry is marked superseded (kept as history, no longer loaded during embodiment) and the corrected version is saved with a new id."
+    )]
+    async fn update_insight(
+        &self,
+        Parameters(params): Parameters<crate::tools::update_insight::UpdateInsightParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::update_insight::update_insight(Parameters(params)).await
+    }
+
+    #[tool(
+        description = "Retract a saved insight by id - use when a pattern anchor or insight turns out to be wrong. The entry is kept as history but no longer loaded during embodiment."
+    )]
+    async fn retract_insight(
+        &self,
+        Parameters(params): Parameters<crate::tools::retract_insight::RetractInsightParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::retract_insight::retract_insight(Parameters(params)).await
+    }
+
+    #[tool(
+        description = "Create Sparkle profile directory structure - used for first-time setup. Refuses to overwrite an existing config.toml or collaborator profile unless force is true; only pass force when the user has asked to start over."
+    )]
     async fn setup_sparkle(
         &self,
         Parameters(params): Parameters<crate::tools::setup_sparkle::SetupSparkleParams>,
@@ -183,22 +329,31 @@ impl SparkleServer {
     }
 
     #[tool(
-        description = "Fetch profile information from external sources to add to the user's Sparkle collaborator profile. Use this when the user mentions wanting to add or enrich their profile with content from external sources like GitHub, blogs, or websites. Supported sources: GitHub (ask for username), blog RSS/Atom feeds (ask for the RSS feed URL - typically /feed, /rss, or /feed.xml), or any website (ask for URL). Always ask the user for the specific username or RSS feed URL - do not guess or assume. This tool fetches the data; you then evaluate how to integrate it into their profile files."
+        description = "Fetch profile information from external sources to add to the user's Sparkle collaborator profile. Use this when the user mentions wanting to add or enrich their profile with content from external sources like GitHub, blogs, or websites. Supported sources: GitHub (ask for username), blog RSS/Atom feeds (ask for the RSS feed URL - typically /feed, /rss, or /feed.xml), any website (ask for URL), or local git repositories (ask for the repository paths; works offline and summarizes the user's own commits). Always ask the user for the specific username or RSS feed URL - do not guess or assume. This tool fetches the data; you then evaluate how to integrate it into their profile files."
     )]
     async fn fetch_profile_data(
         &self,
         Parameters(params): Parameters<crate::tools::fetch_profile_data::FetchProfileDataParams>,
     ) -> Result<CallToolResult, McpError> {
         match crate::tools::fetch_profile_data::fetch_profile_data(params).await {
-            Ok(result) => Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap(),
-            )])),
+            Ok(result) => {
+                let text = serde_json::to_string_pretty(&result).unwrap();
+                // Partial failures still return what was fetched; only report an
+                // error when nothing could be fetched at all
+                if result.fetched_content.iter().any(|c| c.is_ok())
+                    || result.fetched_content.is_empty()
+                {
+                    Ok(CallToolResult::success(vec![Content::text(text)]))
+                } else {
+                    Ok(CallToolResult::error(vec![Content::text(text)]))
+                }
+            }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         }
     }
 
     #[tool(
-        description = "Update the collaborator profile with new content. This completely replaces the existing profile. Make sure to preserve any existing content the user wants to keep."
+        description = "Update the collaborator profile with new content. This completely replaces the existing profile. Make sure to preserve any existing content the user wants to keep. Prefer patch_collaborator_profile for changes to individual sections."
     )]
     async fn update_collaborator_profile(
         &self,
@@ -210,6 +365,45 @@ impl SparkleServer {
             .await
     }
 
+    #[tool(
+        description = "Edit sections of the collaborator profile without rewriting the rest. Each operation names a section by its heading: add (new section, optionally after another), replace (the section's content), append (to the end of a section) or remove. Returns a unified diff preview and a base token without writing; call again with confirm: true and that base after the user approves to apply it. A confirm is refused if the profile changed since the preview."
+    )]
+    async fn patch_collaborator_profile(
+        &self,
+        Parameters(params): Parameters<
+            crate::tools::patch_collaborator_profile::PatchCollaboratorProfileParams,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::patch_collaborator_profile::patch_collaborator_profile(Parameters(params))
+            .await
+    }
+
+    #[tool(
+        description = "List saved versions of a context file: the collaborator profile, a sparkler identity, pattern anchors, collaboration evolution, the cross-workspace map or config.toml. A version is saved automatically each time a tool changes the file."
+    )]
+    async fn list_versions(
+        &self,
+        Parameters(mut params): Parameters<crate::tools::list_versions::ListVersionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.sparkler.is_none() {
+            params.sparkler = self.current_sparkler.read().ok().and_then(|g| g.clone());
+        }
+        crate::tools::list_versions::list_versions(Parameters(params)).await
+    }
+
+    #[tool(
+        description = "Restore a context file (collaborator profile, sparkler identity, pattern anchors, collaboration evolution, cross-workspace map or config.toml) to a version from list_versions. The current content is saved as a new version first, so a restore can be undone."
+    )]
+    async fn restore_version(
+        &self,
+        Parameters(mut params): Parameters<crate::tools::restore_version::RestoreVersionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.sparkler.is_none() {
+            params.sparkler = self.current_sparkler.read().ok().and_then(|g| g.clone());
+        }
+        crate::tools::restore_version::restore_version(Parameters(params)).await
+    }
+
     #[tool(
         description = "Define your Sparkler identity - a brief definition of who YOU are as a Sparkler. What makes your instance distinctive within the Sparkler framework? Keep it concise."
     )]
@@ -249,7 +443,7 @@ impl SparkleServer {
 
     #[tool(
         name = "create_sparkler",
-        description = "Create a new Sparkler identity. If this is your first additional Sparkler, automatically migrates your existing setup to multi-sparkler mode. Creates directory structure and starter files for the new Sparkler."
+        description = "Create a new Sparkler identity. If this is your first additional Sparkler, automatically migrates your existing setup to multi-sparkler mode. Creates directory structure and starter files for the new Sparkler, optionally from a template in ~/.sparkle/templates/ or the team template directory."
     )]
     async fn create_sparkler(
         &self,
@@ -268,11 +462,120 @@ impl SparkleServer {
     ) -> Result<CallToolResult, McpError> {
         crate::tools::list_sparklers::list_sparklers(params).await
     }
+    #[tool(
+        description = "Export a sparkler to a single archive (identity, pattern anchors, evolution, insights and, optionally, the collaborator profile) to move it to another machine or share it with others."
+    )]
+    async fn export_sparkler(
+        &self,
+        Parameters(mut params): Parameters<crate::tools::export_sparkler::ExportSparklerParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.sparkler.is_none() {
+            params.sparkler = self.current_sparkler.read().ok().and_then(|g| g.clone());
+        }
+        crate::tools::export_sparkler::export_sparkler(Parameters(params)).await
+    }
+
+    #[tool(
+        description = "Import a sparkler from an archive written by export_sparkler and register it in config. If a sparkler with that name exists, choose on_conflict rename or merge."
+    )]
+    async fn import_sparkler(
+        &self,
+        params: Parameters<crate::tools::import_sparkler::ImportSparklerParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::import_sparkler::import_sparkler(params).await
+    }
+    #[tool(
+        description = "Show which version of each core identity file (methodology, collaboration identity, partnership) is in effect: a per-sparkler override, a user override from ~/.sparkle/identity/, or the built-in default."
+    )]
+    async fn show_effective_identity(
+        &self,
+        Parameters(mut params): Parameters<
+            crate::tools::show_effective_identity::ShowEffectiveIdentityParams,
+        >,
+    ) -> Result<CallToolResult, McpError> {
+        if params.sparkler.is_none() {
+            params.sparkler = self.current_sparkler.read().ok().and_then(|g| g.clone());
+        }
+        crate::tools::show_effective_identity::show_effective_identity(Parameters(params)).await
+    }
+    #[tool(
+        description = "Update the human's details in config.toml: name, pronouns, time zone, preferred language, role, team and free-form preferences. Use this for structured facts; working style and collaboration patterns belong in the collaborator profile."
+    )]
+    async fn update_human_config(
+        &self,
+        params: Parameters<crate::tools::update_human_config::UpdateHumanConfigParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::update_human_config::update_human_config(params).await
+    }
+    #[tool(
+        description = "Check the Sparkle setup for problems: unparseable config, sparkler directories missing or not in config, unfilled identity templates, oversized anchor/evolution files, invalid or stale working memory, leftover backups and unreadable evolution docs. Each finding comes with a suggested fix; pass fix=true to apply the automatic ones. Pass workspace_path to check that workspace too. Use this first when embodiment seems to be missing context."
+    )]
+    async fn doctor(
+        &self,
+        params: Parameters<crate::tools::doctor::DoctorParams>,
+    ) -> Result<CallToolResult, McpError> {
+        crate::tools::doctor::doctor(params).await
+    }
 }
 
-#[tool_handler]
-#[prompt_handler]
 impl ServerHandler for SparkleServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        if !self.current_settings().tool_enabled(&request.name) {
+            return Err(self.disabled_error("Tool", &request.name));
+        }
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        let settings = self.current_settings();
+        let tools = self
+            .tool_router
+            .list_all()
+            .into_iter()
+            .filter(|tool| settings.tool_enabled(&tool.name))
+            .collect();
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        if !self.current_settings().prompt_enabled(&request.name) {
+            return Err(self.disabled_error("Prompt", &request.name));
+        }
+        let prompt_context = PromptContext::new(self, request.name, request.arguments, context);
+        self.prompt_router.get_prompt(prompt_context).await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        let settings = self.current_settings();
+        let prompts = self
+            .prompt_router
+            .list_all()
+            .into_iter()
+            .filter(|prompt| settings.prompt_enabled(&prompt.name))
+            .collect();
+        Ok(ListPromptsResult {
+            prompts,
+            next_cursor: None,
+        })
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2025_06_18,
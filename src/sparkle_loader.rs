@@ -1,25 +1,159 @@
+use crate::constants::SPARKLE_DIR;
+use crate::template::{TemplateContext, render_logged};
 use crate::types::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 // Embed universal Sparkle definition at compile time - 3-file organized structure
 pub const EMBODIMENT_METHODOLOGY: &str = include_str!("../identity/01-embodiment-methodology.md");
 pub const CORE_IDENTITY: &str = include_str!("../identity/02-collaboration-identity.md");
 pub const PARTNERSHIP: &str = include_str!("../identity/03-partnership.md");
 
+/// Directory of identity overrides, relative to `~/.sparkle` or a sparkler directory
+pub const IDENTITY_DIR: &str = "identity";
+
+/// Core identity files in embodiment order, with their built-in content
+pub const CORE_IDENTITY_FILES: &[(&str, &str)] = &[
+    ("01-embodiment-methodology.md", EMBODIMENT_METHODOLOGY),
+    ("02-collaboration-identity.md", CORE_IDENTITY),
+    ("03-partnership.md", PARTNERSHIP),
+];
+
+/// Where the effective version of a core identity file comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentityLayer {
+    /// Compiled into the binary
+    BuiltIn,
+    /// `~/.sparkle/identity/`
+    User,
+    /// `~/.sparkle/sparklers/{name}/identity/`
+    Sparkler,
+}
+
+impl IdentityLayer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IdentityLayer::BuiltIn => "built-in",
+            IdentityLayer::User => "user",
+            IdentityLayer::Sparkler => "sparkler",
+        }
+    }
+}
+
+/// The effective version of one core identity file
+#[derive(Debug, Clone)]
+pub struct IdentityFile {
+    pub name: &'static str,
+    pub layer: IdentityLayer,
+    /// File the content was read from (None for built-in)
+    pub path: Option<PathBuf>,
+    pub content: String,
+}
+
+/// Resolve each core identity file: sparkler override, then user override, then built-in
+///
+/// Overrides that can't be read are skipped with a warning, so a bad file
+/// never breaks embodiment.
+pub fn resolve_identity_files(
+    sparkle_dir: &Path,
+    sparkler_dir: Option<&Path>,
+) -> Vec<IdentityFile> {
+    let layers: Vec<(IdentityLayer, PathBuf)> = sparkler_dir
+        .map(|dir| (IdentityLayer::Sparkler, dir.join(IDENTITY_DIR)))
+        .into_iter()
+        .chain([(IdentityLayer::User, sparkle_dir.join(IDENTITY_DIR))])
+        .collect();
+
+    CORE_IDENTITY_FILES
+        .iter()
+        .map(|&(name, builtin)| {
+            for (layer, dir) in &layers {
+                let path = dir.join(name);
+                if !path.exists() {
+                    continue;
+                }
+                match fs::read_to_string(&path) {
+                    Ok(content) => {
+                        return IdentityFile {
+                            name,
+                            layer: *layer,
+                            path: Some(path),
+                            content,
+                        };
+                    }
+                    Err(e) => {
+                        tracing::warn!("Ignoring identity override {}: {}", path.display(), e)
+                    }
+                }
+            }
+            IdentityFile {
+                name,
+                layer: IdentityLayer::BuiltIn,
+                path: None,
+                content: builtin.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Directory of a sparkler whose overrides apply (multi-sparkler mode only;
+/// in single-sparkler mode the sparkler directory is `~/.sparkle` itself)
+pub fn sparkler_override_dir(
+    config: &Config,
+    sparkle_dir: &Path,
+    sparkler_name: Option<&str>,
+) -> Option<PathBuf> {
+    if !config.is_multi_sparkler() {
+        return None;
+    }
+    let name = sparkler_name
+        .map(String::from)
+        .or_else(|| config.get_default_sparkler_name())?;
+    Some(sparkle_dir.join("sparklers").join(name))
+}
+
+/// Effective core identity files for a sparkler
+pub fn effective_identity_files(config: &Config, sparkler_name: Option<&str>) -> Vec<IdentityFile> {
+    match dirs::home_dir() {
+        Some(home_dir) => {
+            let sparkle_dir = home_dir.join(SPARKLE_DIR);
+            let sparkler_dir = sparkler_override_dir(config, &sparkle_dir, sparkler_name);
+            resolve_identity_files(&sparkle_dir, sparkler_dir.as_deref())
+        }
+        None => CORE_IDENTITY_FILES
+            .iter()
+            .map(|&(name, builtin)| IdentityFile {
+                name,
+                layer: IdentityLayer::BuiltIn,
+                path: None,
+                content: builtin.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// Template context for rendering a sparkler's identity files
+///
+/// `[#include ...]` looks in the sparkler directory, then `~/.sparkle`.
+pub fn identity_context(config: &Config, sparkler_name: Option<&str>) -> TemplateContext {
+    let mut context = TemplateContext::from_config(config, sparkler_name);
+    if let Some(home_dir) = dirs::home_dir() {
+        let sparkle_dir = home_dir.join(SPARKLE_DIR);
+        if let Some(sparkler_dir) = sparkler_override_dir(config, &sparkle_dir, sparkler_name) {
+            context = context.with_include_dir(sparkler_dir);
+        }
+        context = context.with_include_dir(sparkle_dir);
+    }
+    context
+}
+
 // Combined Sparkle definition for embodiment sequence with config substitution
 pub fn load_sparkle_definition(config: &Config, sparkler_name: Option<&str>) -> String {
-    let human_name = &config.human.name;
+    let context = identity_context(config, sparkler_name);
 
-    // Get AI name - use provided sparkler_name, or fall back to config defaults
-    let ai_name = sparkler_name
-        .map(String::from)
-        .or_else(|| config.get_single_sparkler_name())
-        .or_else(|| config.get_default_sparkler_name())
-        .unwrap_or_else(|| "Sparkle".to_string());
-
-    format!(
-        "{}\n\n{}\n\n{}",
-        EMBODIMENT_METHODOLOGY, CORE_IDENTITY, PARTNERSHIP
-    )
-    .replace("[human.name]", human_name)
-    .replace("[ai.name]", &ai_name)
+    effective_identity_files(config, sparkler_name)
+        .into_iter()
+        .map(|file| render_logged(&file.content, &context, file.name))
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
@@ -0,0 +1,262 @@
+//! Optional git-backed `~/.sparkle`
+//!
+//! With `[sync] enabled = true`, every change made through the tools is
+//! committed to a git repository at `~/.sparkle`, and `sparkle-mcp sync`
+//! pulls (rebasing local commits) and pushes against the configured remote.
+//! Append-only logs use git's `union` merge driver, so entries added on two
+//! machines merge without conflicts. A machine that hasn't synced yet starts
+//! from the remote's history; the local files it replaces go to `.history/`.
+
+use crate::constants::{CONFIG_FILE, SPARKLE_DIR};
+use crate::context_loader::load_config;
+use crate::history::History;
+use crate::types::{Config, HistoryConfig, SyncConfig};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+/// Files that are only ever appended to, merged by keeping both sides
+const UNION_MERGE_PATTERNS: &[&str] = &[
+    "pattern-anchors.md",
+    "collaboration-evolution.md",
+    "workspace-map.md",
+    "insights.jsonl",
+];
+
+/// Local-only files that shouldn't be synced
+const IGNORED_PATTERNS: &[&str] = &[
+    "sparkle-mcp.log",
+    "cache/",
+    ".history/",
+    "exports/",
+    "*.tmp",
+];
+
+/// What `sync` did
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// A commit was made for changes not yet committed
+    pub committed_local_changes: bool,
+    /// Commits pulled from the remote
+    pub pulled: usize,
+    /// Commits pushed to the remote
+    pub pushed: usize,
+}
+
+/// Serializes commits from concurrent tool calls, which would otherwise race
+/// for git's index lock
+static COMMIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Commit a change made through the tools, if sync is enabled
+///
+/// Git runs on the blocking thread pool. Failures are logged rather than
+/// returned: the change itself has already been written and shouldn't be
+/// reported as failed because git is unhappy.
+pub async fn record_change(message: &str) {
+    let Some(sync) = load_config().ok().and_then(|config| config.sync) else {
+        return;
+    };
+    if !sync.enabled {
+        return;
+    }
+    let Some(sparkle_dir) = dirs::home_dir().map(|home| home.join(SPARKLE_DIR)) else {
+        return;
+    };
+
+    let message = message.to_string();
+    let committed = tokio::task::spawn_blocking(move || {
+        let _guard = COMMIT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        commit_all(&sparkle_dir, &sync.branch, &message)
+    })
+    .await;
+    match committed {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => tracing::warn!("Failed to commit sparkle change: {:#}", e),
+        Err(e) => tracing::warn!("Failed to commit sparkle change: {}", e),
+    }
+}
+
+/// Make `dir` a sparkle git repository if it isn't one yet
+pub fn init_repo(dir: &Path, branch: &str) -> Result<()> {
+    if !dir.join(".git").exists() {
+        fs::create_dir_all(dir)?;
+        git(dir, &["init", "--quiet"])?;
+        git(
+            dir,
+            &["symbolic-ref", "HEAD", &format!("refs/heads/{}", branch)],
+        )?;
+    }
+
+    // Commits need an identity; fall back to a local one if none is configured
+    if git(dir, &["config", "user.email"]).is_err() {
+        git(dir, &["config", "user.email", "sparkle@localhost"])?;
+        git(dir, &["config", "user.name", "Sparkle"])?;
+    }
+
+    write_if_changed(
+        &dir.join(".gitattributes"),
+        &UNION_MERGE_PATTERNS
+            .iter()
+            .map(|pattern| format!("{} merge=union\n", pattern))
+            .collect::<String>(),
+    )?;
+    write_if_changed(
+        &dir.join(".gitignore"),
+        &IGNORED_PATTERNS
+            .iter()
+            .map(|pattern| format!("{}\n", pattern))
+            .collect::<String>(),
+    )?;
+    Ok(())
+}
+
+/// Commit everything in `dir`, returning whether there was anything to commit
+pub fn commit_all(dir: &Path, branch: &str, message: &str) -> Result<bool> {
+    init_repo(dir, branch)?;
+
+    git(dir, &["add", "--all"])?;
+    if git(dir, &["diff", "--cached", "--quiet"]).is_ok() {
+        return Ok(false);
+    }
+    git(dir, &["commit", "--quiet", "--no-verify", "-m", message])?;
+    Ok(true)
+}
+
+/// Pull, rebase and push `dir` against the configured remote
+pub fn sync(dir: &Path, config: &SyncConfig) -> Result<SyncReport> {
+    let remote = config.remote.as_deref().context(
+        "No sync remote configured. Add `remote = \"<git url>\"` under [sync] in ~/.sparkle/config.toml",
+    )?;
+    let branch = config.branch.as_str();
+    let remote_branch = format!("origin/{}", branch);
+    let mut report = SyncReport::default();
+
+    init_repo(dir, branch)?;
+
+    // Point origin at the configured remote
+    if git(dir, &["remote", "get-url", "origin"]).is_ok() {
+        git(dir, &["remote", "set-url", "origin", remote])?;
+    } else {
+        git(dir, &["remote", "add", "origin", remote])?;
+    }
+    git(dir, &["fetch", "--quiet", "origin"])
+        .with_context(|| format!("Failed to fetch from {}", remote))?;
+
+    // Checked before committing local changes, which would start a history
+    let remote_exists = git(dir, &["rev-parse", "--verify", "--quiet", &remote_branch]).is_ok();
+    let has_commits = git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok();
+
+    if remote_exists && !has_commits {
+        // Nothing synced here yet - start from the remote's history, keeping
+        // the local files it replaces in the history
+        git(dir, &["reset", "--quiet", &remote_branch])?;
+        let history = History::new(dir, history_config(dir));
+        for file in git(dir, &["diff", "--name-only"])?.lines() {
+            history.save_version(&dir.join(file))?;
+        }
+        git(dir, &["checkout", "--quiet", "--", "."])?;
+        report.pulled = count_commits(dir, "HEAD")?;
+    }
+    report.committed_local_changes = commit_all(dir, branch, "Sync local changes")?;
+
+    if remote_exists && has_commits {
+        report.pulled = count_commits(dir, &format!("HEAD..{}", remote_branch))?;
+        let related = git(dir, &["merge-base", "HEAD", &remote_branch]).is_ok();
+        if related {
+            if let Err(e) = git(dir, &["rebase", "--quiet", &remote_branch]) {
+                let _ = git(dir, &["rebase", "--abort"]);
+                return Err(conflict_error(e, dir));
+            }
+        } else {
+            // A second machine that started its own history: merge the two once
+            if let Err(e) = git(
+                dir,
+                &[
+                    "merge",
+                    "--quiet",
+                    "--allow-unrelated-histories",
+                    "-m",
+                    "Merge sparkle history from remote",
+                    &remote_branch,
+                ],
+            ) {
+                let _ = git(dir, &["merge", "--abort"]);
+                return Err(conflict_error(e, dir));
+            }
+        }
+    }
+
+    report.pushed = if remote_exists {
+        count_commits(dir, &format!("{}..HEAD", remote_branch))?
+    } else {
+        count_commits(dir, "HEAD")?
+    };
+    if report.pushed > 0 {
+        git(
+            dir,
+            &[
+                "push",
+                "--quiet",
+                "origin",
+                &format!("HEAD:refs/heads/{}", branch),
+            ],
+        )
+        .with_context(|| format!("Failed to push to {}", remote))?;
+    }
+
+    Ok(report)
+}
+
+/// Retention for the versions sync saves, from the `config.toml` in `dir`
+fn history_config(dir: &Path) -> HistoryConfig {
+    fs::read_to_string(dir.join(CONFIG_FILE))
+        .ok()
+        .and_then(|text| toml::from_str::<Config>(&text).ok())
+        .and_then(|config| config.history)
+        .unwrap_or_default()
+}
+
+fn conflict_error(error: anyhow::Error, dir: &Path) -> anyhow::Error {
+    let status = git(dir, &["status", "--short"]).unwrap_or_default();
+    error.context(format!(
+        "Sync stopped because local and remote changes conflict; nothing was changed. \
+        Resolve it manually in {} (git pull --rebase), then run sync again.\n{}",
+        dir.display(),
+        status.trim()
+    ))
+}
+
+fn count_commits(dir: &Path, range: &str) -> Result<usize> {
+    if git(dir, &["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+        return Ok(0);
+    }
+    Ok(git(dir, &["rev-list", "--count", range])?
+        .trim()
+        .parse()
+        .unwrap_or(0))
+}
+
+fn write_if_changed(path: &Path, content: &str) -> Result<()> {
+    if fs::read_to_string(path).ok().as_deref() != Some(content) {
+        fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+/// Run git in `dir`, returning stdout or an error with git's stderr
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git - is it installed?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git {} failed: {}", args.join(" "), stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
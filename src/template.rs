@@ -0,0 +1,416 @@
+//! Template rendering for identity files, starter templates and prompts
+//!
+//! Templates keep the bracket syntax the identity files have always used:
+//!
+//! - `[human.name]`, `[ai.name]`, `[sparkler.multi]`, `[config.http.timeout_secs]` -
+//!   variables, looked up by dotted path in the template context
+//! - `[#if sparkler.multi]...[else]...[/if]` - conditionals on a variable being
+//!   set (`path`, `!path`) or equal to a value (`path == "x"`, `path != "x"`)
+//! - `[#include collaboration-context.md]` - the rendered content of another
+//!   context file, looked up in the context's include directories
+//!
+//! Bracketed text that isn't one of these (markdown placeholders like
+//! `[insight]`, links like `[config.toml](...)`) is left untouched. A dotted
+//! name under a known variable that doesn't exist is left as-is and reported
+//! as a warning, so typos in customized files are easy to spot.
+
+use crate::types::Config;
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Includes nested deeper than this are not expanded
+const MAX_INCLUDE_DEPTH: usize = 4;
+
+/// Variables and include directories for rendering
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    values: Map<String, Value>,
+    include_dirs: Vec<PathBuf>,
+}
+
+/// Rendered text and any problems found in the template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub text: String,
+    pub warnings: Vec<String>,
+}
+
+impl TemplateContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context for a sparkler: `human.*`, `ai.name`, `sparkler.*` and every
+    /// config field under `config.*`
+    pub fn from_config(config: &Config, sparkler_name: Option<&str>) -> Self {
+        let ai_name = sparkler_name
+            .map(String::from)
+            .or_else(|| config.get_single_sparkler_name())
+            .or_else(|| config.get_default_sparkler_name())
+            .unwrap_or_else(|| "Sparkle".to_string());
+        let names = if config.is_multi_sparkler() {
+            config.get_all_sparkler_names()
+        } else {
+            vec![ai_name.clone()]
+        };
+
+        Self::new()
+            .with("human", &config.human)
+            .with("ai", json!({ "name": ai_name }))
+            .with(
+                "sparkler",
+                json!({
+                    "name": ai_name,
+                    "multi": config.is_multi_sparkler(),
+                    "names": names,
+                }),
+            )
+            .with("config", config)
+    }
+
+    /// Set a top-level variable
+    pub fn with(mut self, name: &str, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.values.insert(name.to_string(), value);
+        self
+    }
+
+    /// Add a directory searched by `[#include ...]`, after those already added
+    pub fn with_include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    /// Value at a dotted path, e.g. `human.name` or `sparkler.names.0`
+    pub fn lookup(&self, path: &str) -> Option<&Value> {
+        let mut segments = path.split('.');
+        let mut value = self.values.get(segments.next()?)?;
+        for segment in segments {
+            value = match value {
+                Value::Object(map) => map.get(segment)?,
+                Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    fn has_root(&self, path: &str) -> bool {
+        path.split('.')
+            .next()
+            .is_some_and(|root| self.values.contains_key(root))
+    }
+}
+
+/// Render `template`, collecting warnings for unknown variables and bad directives
+pub fn render(template: &str, context: &TemplateContext) -> Rendered {
+    let mut warnings = Vec::new();
+    let mut text = String::new();
+    render_into(template, context, 0, &mut text, &mut warnings);
+    Rendered { text, warnings }
+}
+
+/// Render `template`, logging any warnings against `source`
+pub fn render_logged(template: &str, context: &TemplateContext, source: &str) -> String {
+    let rendered = render(template, context);
+    for warning in &rendered.warnings {
+        tracing::warn!("{}: {}", source, warning);
+    }
+    rendered.text
+}
+
+fn render_into(
+    template: &str,
+    context: &TemplateContext,
+    depth: usize,
+    out: &mut String,
+    warnings: &mut Vec<String>,
+) {
+    let tokens = tokenize(template);
+    let mut pos = 0;
+    let (nodes, _) = parse_block(&tokens, &mut pos, false, warnings);
+    render_nodes(&nodes, context, depth, out, warnings);
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    Text(&'a str),
+    Var { path: &'a str, raw: &'a str },
+    If { condition: &'a str, raw: &'a str },
+    Else { raw: &'a str },
+    EndIf { raw: &'a str },
+    Include { file: &'a str },
+}
+
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    Var {
+        path: &'a str,
+        raw: &'a str,
+    },
+    If {
+        condition: &'a str,
+        then: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+    Include {
+        file: &'a str,
+    },
+}
+
+/// `name(.name)+`, e.g. `human.name`
+fn is_path(text: &str) -> bool {
+    let mut segments = text.split('.');
+    let root_ok = segments.next().is_some_and(|root| {
+        root.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && root.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+    let rest: Vec<&str> = segments.collect();
+    root_ok
+        && !rest.is_empty()
+        && rest.iter().all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+fn tokenize(template: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut text_start = 0;
+    let mut pos = 0;
+
+    while let Some(offset) = template[pos..].find('[') {
+        let open = pos + offset;
+        let Some(end) = template[open + 1..].find(['[', ']', '\n']) else {
+            break;
+        };
+        let close = open + 1 + end;
+        if !template[close..].starts_with(']') {
+            pos = close;
+            continue;
+        }
+
+        let inner = template[open + 1..close].trim();
+        let raw = &template[open..=close];
+        let is_link = template[close + 1..].starts_with('(');
+        let token = if let Some(condition) = inner.strip_prefix("#if ") {
+            Some(Token::If {
+                condition: condition.trim(),
+                raw,
+            })
+        } else if inner == "else" {
+            Some(Token::Else { raw })
+        } else if inner == "/if" {
+            Some(Token::EndIf { raw })
+        } else if let Some(file) = inner.strip_prefix("#include ") {
+            Some(Token::Include { file: file.trim() })
+        } else if is_path(inner) && !is_link {
+            Some(Token::Var { path: inner, raw })
+        } else {
+            None
+        };
+
+        if let Some(token) = token {
+            if text_start < open {
+                tokens.push(Token::Text(&template[text_start..open]));
+            }
+            tokens.push(token);
+            text_start = close + 1;
+        }
+        pos = close + 1;
+    }
+
+    if text_start < template.len() {
+        tokens.push(Token::Text(&template[text_start..]));
+    }
+    tokens
+}
+
+enum BlockEnd {
+    Else,
+    EndIf,
+}
+
+/// Parse nodes until the end of the tokens, or `[else]`/`[/if]` inside a conditional
+fn parse_block<'a>(
+    tokens: &[Token<'a>],
+    pos: &mut usize,
+    in_if: bool,
+    warnings: &mut Vec<String>,
+) -> (Vec<Node<'a>>, Option<BlockEnd>) {
+    let mut nodes = Vec::new();
+    while let Some(token) = tokens.get(*pos) {
+        *pos += 1;
+        match token {
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Var { path, raw } => nodes.push(Node::Var { path, raw }),
+            Token::Include { file } => nodes.push(Node::Include { file }),
+            Token::Else { .. } if in_if => return (nodes, Some(BlockEnd::Else)),
+            Token::EndIf { .. } if in_if => return (nodes, Some(BlockEnd::EndIf)),
+            Token::Else { raw } | Token::EndIf { raw } => {
+                warnings.push(format!("{} without a matching [#if]", raw));
+                nodes.push(Node::Text(raw));
+            }
+            Token::If { condition, raw } => {
+                let (then, end) = parse_block(tokens, pos, true, warnings);
+                let otherwise = match end {
+                    Some(BlockEnd::Else) => {
+                        let (otherwise, end) = parse_block(tokens, pos, true, warnings);
+                        match end {
+                            Some(BlockEnd::EndIf) => {}
+                            Some(BlockEnd::Else) => {
+                                warnings.push(format!("{} has more than one [else]", raw));
+                            }
+                            None => warnings.push(format!("{} is never closed with [/if]", raw)),
+                        }
+                        otherwise
+                    }
+                    Some(BlockEnd::EndIf) => Vec::new(),
+                    None => {
+                        warnings.push(format!("{} is never closed with [/if]", raw));
+                        Vec::new()
+                    }
+                };
+                nodes.push(Node::If {
+                    condition,
+                    then,
+                    otherwise,
+                });
+            }
+        }
+    }
+    (nodes, None)
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    context: &TemplateContext,
+    depth: usize,
+    out: &mut String,
+    warnings: &mut Vec<String>,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var { path, raw } => match context.lookup(path) {
+                Some(value) => out.push_str(&value_to_string(value)),
+                None => {
+                    if context.has_root(path) {
+                        warnings.push(format!("Unknown placeholder {}", raw));
+                    }
+                    out.push_str(raw);
+                }
+            },
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if evaluate(condition, context, warnings) {
+                    then
+                } else {
+                    otherwise
+                };
+                render_nodes(branch, context, depth, out, warnings);
+            }
+            Node::Include { file } => {
+                if depth >= MAX_INCLUDE_DEPTH {
+                    warnings.push(format!(
+                        "[#include {}] is nested more than {} deep and was skipped",
+                        file, MAX_INCLUDE_DEPTH
+                    ));
+                    continue;
+                }
+                match resolve_include(file, context) {
+                    Some(path) => match fs::read_to_string(&path) {
+                        Ok(content) => render_into(&content, context, depth + 1, out, warnings),
+                        Err(e) => {
+                            warnings.push(format!("[#include {}] could not be read: {}", file, e))
+                        }
+                    },
+                    None => warnings.push(format!("[#include {}] not found", file)),
+                }
+            }
+        }
+    }
+}
+
+/// First include directory holding `file`; paths may not leave the directory
+fn resolve_include(file: &str, context: &TemplateContext) -> Option<PathBuf> {
+    let relative = Path::new(file);
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+    context
+        .include_dirs
+        .iter()
+        .map(|dir| dir.join(relative))
+        .find(|path| path.is_file())
+}
+
+fn evaluate(condition: &str, context: &TemplateContext, warnings: &mut Vec<String>) -> bool {
+    let comparison = [("==", true), ("!=", false)]
+        .into_iter()
+        .find_map(|(op, equal)| {
+            condition
+                .split_once(op)
+                .map(|(left, right)| (left.trim(), right.trim(), equal))
+        });
+
+    let (path, negated) = match comparison {
+        Some((path, _, _)) => (path, false),
+        None => match condition.strip_prefix('!') {
+            Some(path) => (path.trim(), true),
+            None => (condition, false),
+        },
+    };
+    if !is_path(path) {
+        warnings.push(format!("[#if {}] is not a valid condition", condition));
+        return false;
+    }
+    let value = context.lookup(path);
+    if value.is_none() {
+        warnings.push(format!("Unknown variable {} in [#if {}]", path, condition));
+    }
+
+    match comparison {
+        Some((_, expected, equal)) => {
+            let expected = expected.trim_matches('"');
+            let actual = value.map(value_to_string).unwrap_or_default();
+            (actual == expected) == equal
+        }
+        None => value.is_some_and(is_truthy) != negated,
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(value_to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,119 @@
+//! Starter templates for new sparklers
+//!
+//! A template is a directory of context files (`sparkler-identity.md`,
+//! `pattern-anchors.md`, ...) found under `~/.sparkle/templates/<name>/` or
+//! the team-shared `[templates] team_dir`. Creating a sparkler from a template
+//! writes the usual starter files, then the template's files over them,
+//! rendered as templates (`[human.name]`, `[ai.name]`, conditionals, ...).
+
+use crate::template::{TemplateContext, render_logged};
+use crate::types::Config;
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Personal templates directory, relative to the sparkle directory
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Template files that are rendered; others are copied as-is
+const TEXT_EXTENSIONS: &[&str] = &["md", "txt", "toml", "json", "jsonl"];
+
+/// Directories searched for templates, personal first
+pub fn template_dirs(sparkle_dir: &Path, config: &Config) -> Vec<PathBuf> {
+    let mut dirs = vec![sparkle_dir.join(TEMPLATES_DIR)];
+    if let Some(team_dir) = config
+        .templates
+        .as_ref()
+        .and_then(|t| t.team_dir.as_deref())
+    {
+        dirs.push(expand_home(team_dir));
+    }
+    dirs
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Names of all available templates, sorted; a personal template hides a team one
+pub fn list_templates(sparkle_dir: &Path, config: &Config) -> Vec<String> {
+    let mut names: Vec<String> = template_dirs(sparkle_dir, config)
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Directory of the template called `name`
+pub fn find_template(sparkle_dir: &Path, config: &Config, name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        bail!("'{}' is not a valid template name", name);
+    }
+
+    let dirs = template_dirs(sparkle_dir, config);
+    if let Some(dir) = dirs.iter().map(|d| d.join(name)).find(|d| d.is_dir()) {
+        return Ok(dir);
+    }
+
+    let available = list_templates(sparkle_dir, config);
+    bail!(
+        "Template '{}' not found in {}. {}",
+        name,
+        dirs.iter()
+            .map(|d| d.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" or "),
+        if available.is_empty() {
+            "No templates are installed.".to_string()
+        } else {
+            format!("Available templates: {}", available.join(", "))
+        }
+    )
+}
+
+/// Write the files of `template_dir` into `sparkler_dir`, returning their names
+pub fn apply_template(
+    template_dir: &Path,
+    sparkler_dir: &Path,
+    context: &TemplateContext,
+) -> Result<Vec<String>> {
+    let mut written = Vec::new();
+    let entries = fs::read_dir(template_dir)
+        .with_context(|| format!("Failed to read template {}", template_dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let file_name = path.file_name().unwrap_or_default().to_os_string();
+        let target = sparkler_dir.join(&file_name);
+
+        let is_text = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| TEXT_EXTENSIONS.contains(&ext));
+        if is_text {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let source = path.display().to_string();
+            fs::write(&target, render_logged(&content, context, &source))?;
+        } else {
+            fs::copy(&path, &target)?;
+        }
+        written.push(file_name.to_string_lossy().into_owned());
+    }
+    written.sort();
+    Ok(written)
+}
@@ -1,14 +1,24 @@
+use crate::context_loader::{load_config, load_workspace_config};
+use crate::embodiment::recent_checkpoints;
+use crate::history::History;
 use crate::types::CheckpointParams;
 use chrono::Utc;
 use rmcp::{ErrorData as McpError, handler::server::wrapper::Parameters, model::*};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub async fn session_checkpoint(
     Parameters(params): Parameters<CheckpointParams>,
 ) -> Result<CallToolResult, McpError> {
     let timestamp = Utc::now().format("%Y-%m-%d-%H%M%S").to_string();
 
+    let workspace_config = load_workspace_config(Path::new("."));
+    if !workspace_config.enabled {
+        return Ok(CallToolResult::error(vec![Content::text(
+            "Sparkle is turned off for this workspace (`enabled = false` in .sparkle-space/config.toml), so no checkpoint was written.",
+        )]));
+    }
+
     // Workspace is shared - all Sparklers use .sparkle-space/
     let sparkle_space = PathBuf::from(".sparkle-space");
     let checkpoints_dir = sparkle_space.join("checkpoints");
@@ -21,11 +31,13 @@ pub async fn session_checkpoint(
         )
     })?;
 
-    // Write working memory (shared across all Sparklers)
+    // Write working memory (shared across all Sparklers), keeping the previous snapshot
     let working_memory_path = sparkle_space.join("working-memory.json");
-    fs::write(&working_memory_path, &params.working_memory).map_err(|e| {
-        McpError::internal_error(format!("Failed to write working memory: {}", e), None)
-    })?;
+    working_memory_history(&sparkle_space)
+        .write(&working_memory_path, &params.working_memory)
+        .map_err(|e| {
+            McpError::internal_error(format!("Failed to write working memory: {}", e), None)
+        })?;
 
     // Add sparkler attribution to checkpoint content if provided
     let checkpoint_content = if let Some(ref sparkler_name) = params.sparkler {
@@ -52,6 +64,23 @@ pub async fn session_checkpoint(
         McpError::internal_error(format!("Failed to write checkpoint: {}", e), None)
     })?;
 
+    // Enforce the workspace's checkpoint retention (never deleting the new checkpoint)
+    let mut pruned_info = String::new();
+    if let Some(keep) = workspace_config.checkpoints.and_then(|c| c.keep) {
+        let keep = keep.max(1);
+        match prune_checkpoints(&checkpoints_dir, keep) {
+            Ok(pruned) if !pruned.is_empty() => {
+                pruned_info = format!(
+                    "✅ Removed {} old checkpoint(s) (keeping {})\n",
+                    pruned.len(),
+                    keep
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to prune old checkpoints: {}", e),
+        }
+    }
+
     let sparkler_info = params
         .sparkler
         .map(|s| format!("**Sparkler**: {}\n", s))
@@ -66,14 +95,35 @@ pub async fn session_checkpoint(
         ✅ Working memory updated at {}\n\
         ✅ Session checkpoint created at {}\n\
         ✅ Progress preserved for future sessions\n\
+        {}\
         ✅ Collaborative momentum captured\n\n\
         **Next Session Ready**: All context preserved for seamless continuation",
         sparkler_info,
         timestamp,
         checkpoint_path.display(),
         working_memory_path.display(),
-        checkpoint_path.display()
+        checkpoint_path.display(),
+        pruned_info
     );
 
     Ok(CallToolResult::success(vec![Content::text(response)]))
 }
+
+/// Earlier snapshots of a workspace's working memory, in `.sparkle-space/.history/`
+///
+/// Retention follows `[history]` in `~/.sparkle/config.toml`.
+pub fn working_memory_history(sparkle_space: &Path) -> History {
+    let config = load_config().ok().and_then(|config| config.history);
+    History::new(sparkle_space, config.unwrap_or_default())
+}
+
+/// Delete all but the `keep` most recent checkpoints, returning the deleted files
+pub fn prune_checkpoints(checkpoints_dir: &Path, keep: usize) -> std::io::Result<Vec<PathBuf>> {
+    let checkpoints = recent_checkpoints(checkpoints_dir, usize::MAX);
+    let excess = checkpoints.len().saturating_sub(keep);
+    let pruned: Vec<PathBuf> = checkpoints.into_iter().take(excess).collect();
+    for path in &pruned {
+        fs::remove_file(path)?;
+    }
+    Ok(pruned)
+}
@@ -1,5 +1,8 @@
 use crate::constants::SPARKLE_DIR;
-use crate::context_loader::{create_starter_files, load_config};
+use crate::context_loader::{create_starter_files, load_config, migrate_to_multi_sparkler};
+use crate::history::History;
+use crate::template::TemplateContext;
+use crate::templates::{apply_template, find_template};
 use crate::types::SparklerConfig;
 use rmcp::{
     ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
@@ -10,6 +13,9 @@ use std::fs;
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct CreateSparklerParams {
     pub name: String,
+    /// Optional: Template to start from - a directory in ~/.sparkle/templates/ or the team template dir from [templates] team_dir
+    #[serde(default)]
+    pub template: Option<String>,
 }
 
 pub async fn create_sparkler(
@@ -34,52 +40,24 @@ pub async fn create_sparkler(
     let sparkle_dir = home_dir.join(SPARKLE_DIR);
     let sparklers_dir = sparkle_dir.join("sparklers");
 
+    // Resolve the template before changing anything
+    let template_dir = match &params.template {
+        Some(template) => Some(
+            find_template(&sparkle_dir, &config, template)
+                .map_err(|e| McpError::invalid_params(e.to_string(), None))?,
+        ),
+        None => None,
+    };
+
     let mut messages = Vec::new();
 
     // Check if we need to migrate from single-sparkler to multi-sparkler
     if !config.is_multi_sparkler() {
         messages.push("🔄 Migrating to multi-sparkler setup...".to_string());
-
-        // Get current sparkler name
-        let current_name = config
-            .get_single_sparkler_name()
-            .unwrap_or_else(|| "Sparkle".to_string());
-
-        // Create sparklers directory
-        fs::create_dir_all(&sparklers_dir).map_err(|e| {
-            McpError::internal_error(format!("Failed to create sparklers directory: {}", e), None)
-        })?;
-
-        // Create directory for current sparkler
-        let current_sparkler_dir = sparklers_dir.join(&current_name);
-        fs::create_dir_all(&current_sparkler_dir).map_err(|e| {
-            McpError::internal_error(format!("Failed to create sparkler directory: {}", e), None)
-        })?;
-
-        // Move existing context files to current sparkler directory
-        let files_to_move = vec![
-            "collaboration-context.md",
-            "collaboration-evolution.md",
-            "pattern-anchors.md",
-        ];
-
-        for file in files_to_move {
-            let src = sparkle_dir.join(file);
-            let dst = current_sparkler_dir.join(file);
-            if src.exists() {
-                fs::rename(&src, &dst).map_err(|e| {
-                    McpError::internal_error(format!("Failed to move {}: {}", file, e), None)
-                })?;
-            }
-        }
-
-        // Convert config to multi-sparkler mode
-        config.sparklers = Some(vec![SparklerConfig {
-            name: current_name.clone(),
-            default: true,
-        }]);
-        config.ai = None; // Remove old [ai] section
-
+    }
+    if let Some(current_name) = migrate_to_multi_sparkler(&mut config, &sparkle_dir)
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+    {
         messages.push(format!("✅ Moved {} to sparklers/ (default)", current_name));
     }
 
@@ -99,16 +77,30 @@ pub async fn create_sparkler(
         McpError::internal_error(format!("Failed to create sparkler directory: {}", e), None)
     })?;
 
-    // Create starter files
+    // Create starter files, then let the template replace the ones it provides
     create_starter_files(&new_sparkler_dir, &name).map_err(|e| {
         McpError::internal_error(format!("Failed to create starter files: {}", e), None)
     })?;
+    if let Some(template_dir) = &template_dir {
+        let context = TemplateContext::from_config(&config, Some(name))
+            .with_include_dir(template_dir)
+            .with_include_dir(&sparkle_dir);
+        let files = apply_template(template_dir, &new_sparkler_dir, &context).map_err(|e| {
+            McpError::internal_error(format!("Failed to apply template: {:#}", e), None)
+        })?;
+        messages.push(format!(
+            "📋 Applied template {} ({})",
+            template_dir.display(),
+            files.join(", ")
+        ));
+    }
 
     // Add new sparkler to config
     if let Some(ref mut sparklers) = config.sparklers {
         sparklers.push(SparklerConfig {
             name: name.to_string(),
             default: false,
+            settings: Default::default(),
         });
     }
 
@@ -117,9 +109,15 @@ pub async fn create_sparkler(
     let config_string = toml::to_string_pretty(&config).map_err(|e| {
         McpError::internal_error(format!("Failed to serialize config: {}", e), None)
     })?;
-    fs::write(&config_path, config_string)
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    history
+        .write(&config_path, config_string)
         .map_err(|e| McpError::internal_error(format!("Failed to write config: {}", e), None))?;
 
+    crate::sync::record_change(&format!("Create sparkler {}", name)).await;
+
     messages.push(format!("✨ Created {}!", name));
     messages.push(format!(
         "Use embody_sparkle(sparkler='{}') to activate.",
@@ -0,0 +1,49 @@
+use crate::constants::SPARKLE_DIR;
+use crate::doctor::{apply_fixes, diagnose};
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DoctorParams {
+    /// Optional: Workspace to check as well (current working directory)
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+    /// Optional: Apply the automatic fixes, then check again (default false)
+    #[serde(default)]
+    pub fix: bool,
+}
+
+pub async fn doctor(
+    Parameters(params): Parameters<DoctorParams>,
+) -> Result<CallToolResult, McpError> {
+    let sparkle_dir = dirs::home_dir()
+        .ok_or_else(|| McpError::internal_error("Could not determine home directory", None))?
+        .join(SPARKLE_DIR);
+    let workspace = params.workspace_path.as_deref().map(Path::new);
+
+    let mut report = diagnose(&sparkle_dir, workspace);
+    let mut response = String::new();
+    if params.fix {
+        let results = apply_fixes(&sparkle_dir, &report);
+        if !results.is_empty() {
+            response.push_str("**Automatic fixes:**\n");
+            for (fix, result) in &results {
+                match result {
+                    Ok(()) => response.push_str(&format!("✅ {}\n", fix)),
+                    Err(e) => response.push_str(&format!("❌ {}: {}\n", fix, e)),
+                }
+            }
+            response.push('\n');
+            if results.iter().any(|(_, result)| result.is_ok()) {
+                crate::sync::record_change("Apply doctor fixes").await;
+            }
+            report = diagnose(&sparkle_dir, workspace);
+        }
+    }
+    response.push_str(&report.to_markdown());
+
+    Ok(CallToolResult::success(vec![Content::text(response)]))
+}
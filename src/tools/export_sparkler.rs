@@ -0,0 +1,73 @@
+use crate::bundle::{bundle_file_name, export_sparkler as export_bundle};
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSparklerParams {
+    /// Optional: Sparkler to export (defaults to the current sparkler)
+    #[serde(default)]
+    pub sparkler: Option<String>,
+    /// Optional: Path of the archive to write (default ~/.sparkle/exports/<name>.sparkle.tar.gz)
+    #[serde(default)]
+    pub output_path: Option<String>,
+    /// Optional: Also include the collaborator profile (default false - it describes the human, so only share it knowingly)
+    #[serde(default)]
+    pub include_profile: bool,
+}
+
+pub async fn export_sparkler(
+    Parameters(params): Parameters<ExportSparklerParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let home_dir = dirs::home_dir().ok_or_else(|| {
+        McpError::internal_error("Could not determine home directory".to_string(), None)
+    })?;
+    let sparkle_dir = home_dir.join(SPARKLE_DIR);
+
+    let name = params
+        .sparkler
+        .clone()
+        .or_else(|| config.get_default_sparkler_name())
+        .or_else(|| config.get_single_sparkler_name())
+        .unwrap_or_else(|| "Sparkle".to_string());
+    let output = params
+        .output_path
+        .map(PathBuf::from)
+        .unwrap_or_else(|| sparkle_dir.join("exports").join(bundle_file_name(&name)));
+
+    let manifest = match export_bundle(
+        &sparkle_dir,
+        &config,
+        Some(&name),
+        params.include_profile,
+        &output,
+    ) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Export failed: {:#}",
+                e
+            ))]));
+        }
+    };
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "📦 Exported {} to {}\n\nFiles: {}\nCollaborator profile: {}\n\n\
+        Import it elsewhere with import_sparkler or `sparkle-mcp import <path>`.",
+        manifest.sparkler.name,
+        output.display(),
+        manifest.files.join(", "),
+        if manifest.collaborator_profile {
+            "included"
+        } else {
+            "not included"
+        }
+    ))]))
+}
@@ -1,15 +1,9 @@
 use crate::context_loader;
-use anyhow::{Context, Result};
+use crate::profile_sources::{HttpClient, ProfileSource, ProfileSourceRegistry};
+use anyhow::Result;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, JsonSchema)]
-pub struct ProfileSource {
-    #[serde(rename = "type")]
-    pub source_type: String,
-    pub value: String,
-}
-
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct FetchProfileDataParams {
     pub profile_sources: Option<Vec<ProfileSource>>,
@@ -20,58 +14,88 @@ pub struct FetchProfileDataParams {
 
 #[derive(Debug, Serialize)]
 pub struct FetchResult {
+    /// True only when every requested source was fetched
     pub success: bool,
+    /// Human-readable outcome, e.g. "2 of 3 sources fetched"
+    pub summary: String,
     pub prompt: String,
     pub fetched_content: Vec<FetchedContent>,
 }
 
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchStatus {
+    Ok,
+    Error,
+}
+
 #[derive(Debug, Serialize)]
 pub struct FetchedContent {
     pub source_type: String,
     pub source_value: String,
+    pub status: FetchStatus,
+    /// Fetched content (empty when the source failed)
     pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FetchedContent {
+    fn ok(source_type: &str, source_value: &str, content: String) -> Self {
+        Self {
+            source_type: source_type.to_string(),
+            source_value: source_value.to_string(),
+            status: FetchStatus::Ok,
+            content,
+            error: None,
+        }
+    }
+
+    fn failed(source_type: &str, source_value: &str, error: &anyhow::Error) -> Self {
+        Self {
+            source_type: source_type.to_string(),
+            source_value: source_value.to_string(),
+            status: FetchStatus::Error,
+            content: String::new(),
+            // Include the cause chain ("Failed to fetch blog RSS: 404 Not Found")
+            error: Some(format!("{:#}", error)),
+        }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status == FetchStatus::Ok
+    }
 }
 
 pub async fn fetch_profile_data(params: FetchProfileDataParams) -> Result<FetchResult> {
     let mut fetched_content = Vec::new();
 
-    // Handle profile sources - fetch the data
+    // Handle profile sources - fetch them concurrently, keeping each source's outcome
     if let Some(sources) = params.profile_sources {
-        for source in sources {
-            let content = fetch_profile_source(&source).await?;
-            fetched_content.push(FetchedContent {
-                source_type: source.source_type.clone(),
-                source_value: source.value.clone(),
-                content,
+        let registry = ProfileSourceRegistry::builtin_with(HttpClient::from_sparkle_config()?);
+        let results =
+            futures::future::join_all(sources.iter().map(|source| registry.fetch(source))).await;
+        for (source, result) in sources.iter().zip(results) {
+            fetched_content.push(match result {
+                Ok(content) => FetchedContent::ok(&source.source_type, &source.value, content),
+                Err(e) => FetchedContent::failed(&source.source_type, &source.value, &e),
             });
         }
     }
 
     // Handle direct content
     if let Some(content) = params.content {
-        fetched_content.push(FetchedContent {
-            source_type: "text".to_string(),
-            source_value: "direct".to_string(),
-            content,
-        });
+        fetched_content.push(FetchedContent::ok("text", "direct", content));
     }
 
     // Handle working style
     if let Some(style) = params.working_style {
-        fetched_content.push(FetchedContent {
-            source_type: "working_style".to_string(),
-            source_value: "direct".to_string(),
-            content: style,
-        });
+        fetched_content.push(FetchedContent::ok("working_style", "direct", style));
     }
 
     // Handle collaboration preferences
     if let Some(prefs) = params.collaboration_prefs {
-        fetched_content.push(FetchedContent {
-            source_type: "collaboration_prefs".to_string(),
-            source_value: "direct".to_string(),
-            content: prefs,
-        });
+        fetched_content.push(FetchedContent::ok("collaboration_prefs", "direct", prefs));
     }
 
     // Load config to get human name
@@ -88,198 +112,48 @@ pub async fn fetch_profile_data(params: FetchProfileDataParams) -> Result<FetchR
         })
         .unwrap_or_else(|| "[No existing profile]".to_string());
 
+    let failed: Vec<&FetchedContent> = fetched_content.iter().filter(|c| !c.is_ok()).collect();
+    let summary = if failed.is_empty() {
+        format!("{} source(s) fetched", fetched_content.len())
+    } else {
+        format!(
+            "{} of {} source(s) fetched; {} failed",
+            fetched_content.len() - failed.len(),
+            fetched_content.len(),
+            failed.len()
+        )
+    };
+
+    let failure_note = if failed.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "\n\nSome sources could not be fetched (see their `error`): {}. \
+            Let {} know, and work with what did come back.",
+            failed
+                .iter()
+                .map(|c| format!("{} {}", c.source_type, c.source_value))
+                .collect::<Vec<_>>()
+                .join(", "),
+            human_name
+        )
+    };
+
     let prompt = format!(
         "Evaluate how this fetched data fits into {}'s collaborator profile.\n\n\
         ## Current Profile:\n{}\n\n\
         ## Fetched Data:\n\
         Review the fetched_content below and decide what to add and where. \
-        Use the update_collaborator_profile tool to write your changes. \
-        Make sure to get their input and consent if they like it!",
-        human_name, profile_content
+        Use the patch_collaborator_profile tool to add, replace or append individual sections, \
+        show the user its diff preview, and apply it with confirm: true and the preview's base once they approve. \
+        Make sure to get their input and consent if they like it!{}",
+        human_name, profile_content, failure_note
     );
 
     Ok(FetchResult {
-        success: true,
+        success: failed.is_empty(),
+        summary,
         prompt,
         fetched_content,
     })
 }
-
-async fn fetch_profile_source(source: &ProfileSource) -> Result<String> {
-    match source.source_type.as_str() {
-        "github" => fetch_github_profile(&source.value).await,
-        "blog" => fetch_blog_rss(&source.value).await,
-        "url" => fetch_url_content(&source.value).await,
-        "text" => Ok(source.value.clone()),
-        _ => anyhow::bail!("Unknown source type: {}", source.source_type),
-    }
-}
-
-async fn fetch_github_profile(username: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-
-    // Fetch user profile
-    let user_url = format!("https://api.github.com/users/{}", username);
-    let user_resp = client
-        .get(&user_url)
-        .header("User-Agent", "Sparkle-MCP")
-        .send()
-        .await
-        .context("Failed to fetch GitHub user")?;
-
-    if !user_resp.status().is_success() {
-        anyhow::bail!("GitHub user '{}' not found", username);
-    }
-
-    let user: serde_json::Value = user_resp.json().await?;
-
-    // Fetch repositories
-    let repos_url = format!(
-        "https://api.github.com/users/{}/repos?sort=updated&per_page=100",
-        username
-    );
-    let repos_resp = client
-        .get(&repos_url)
-        .header("User-Agent", "Sparkle-MCP")
-        .send()
-        .await
-        .context("Failed to fetch GitHub repos")?;
-
-    let repos: Vec<serde_json::Value> = repos_resp.json().await?;
-
-    // Extract profile info
-    let name = user["name"].as_str().unwrap_or(username);
-    let bio = user["bio"].as_str().unwrap_or("");
-    let company = user["company"].as_str().unwrap_or("");
-    let location = user["location"].as_str().unwrap_or("");
-    let blog = user["blog"].as_str().unwrap_or("");
-
-    // Count languages
-    let mut language_counts: std::collections::HashMap<String, usize> =
-        std::collections::HashMap::new();
-    for repo in &repos {
-        if let Some(lang) = repo["language"].as_str() {
-            *language_counts.entry(lang.to_string()).or_insert(0) += 1;
-        }
-    }
-
-    // Get top 3 languages
-    let mut langs: Vec<_> = language_counts.into_iter().collect();
-    langs.sort_by(|a, b| b.1.cmp(&a.1));
-    let top_languages: Vec<String> = langs.iter().take(3).map(|(lang, _)| lang.clone()).collect();
-
-    // Format summary
-    let mut summary = format!("## GitHub Profile: {}\n\n", name);
-
-    if !bio.is_empty() {
-        summary.push_str(&format!("{}\n\n", bio));
-    }
-
-    if !company.is_empty() || !location.is_empty() {
-        summary.push_str("**Details:**\n");
-        if !company.is_empty() {
-            summary.push_str(&format!("- Company: {}\n", company));
-        }
-        if !location.is_empty() {
-            summary.push_str(&format!("- Location: {}\n", location));
-        }
-        if !blog.is_empty() {
-            summary.push_str(&format!("- Website: {}\n", blog));
-        }
-        summary.push('\n');
-    }
-
-    summary.push_str(&format!("**GitHub Activity:**\n"));
-    summary.push_str(&format!("- {} public repositories\n", repos.len()));
-
-    if !top_languages.is_empty() {
-        summary.push_str(&format!(
-            "- Primary languages: {}\n",
-            top_languages.join(", ")
-        ));
-    }
-
-    Ok(summary)
-}
-
-async fn fetch_blog_rss(url: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(url)
-        .header("User-Agent", "Sparkle-MCP")
-        .send()
-        .await
-        .context("Failed to fetch blog RSS")?;
-
-    if !resp.status().is_success() {
-        anyhow::bail!("Failed to fetch blog RSS: {}", resp.status());
-    }
-
-    let content = resp.bytes().await?;
-    let feed = feed_rs::parser::parse(&content[..]).context("Failed to parse RSS/Atom feed")?;
-
-    let mut summary = format!(
-        "## Blog: {}\n\n",
-        feed.title
-            .map(|t| t.content)
-            .unwrap_or_else(|| "Blog".to_string())
-    );
-
-    if let Some(description) = feed.description {
-        summary.push_str(&format!("{}\n\n", description.content));
-    }
-
-    summary.push_str("**Recent Posts:**\n\n");
-
-    // Get up to 10 most recent entries
-    let entries: Vec<_> = feed.entries.iter().take(10).collect();
-
-    for entry in entries {
-        let title = entry
-            .title
-            .as_ref()
-            .map(|t| t.content.as_str())
-            .unwrap_or("Untitled");
-
-        let link = entry.links.first().map(|l| l.href.as_str()).unwrap_or("");
-
-        summary.push_str(&format!("- [{}]({})\n", title, link));
-
-        if let Some(published) = &entry.published {
-            summary.push_str(&format!(
-                "  *Published: {}*\n",
-                published.format("%Y-%m-%d")
-            ));
-        }
-
-        if let Some(summary_text) = &entry.summary {
-            let truncated = if summary_text.content.len() > 200 {
-                format!("{}...", &summary_text.content[..200])
-            } else {
-                summary_text.content.clone()
-            };
-            summary.push_str(&format!("  {}\n", truncated));
-        }
-
-        summary.push('\n');
-    }
-
-    Ok(summary)
-}
-
-async fn fetch_url_content(url: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-    let resp = client
-        .get(url)
-        .header("User-Agent", "Sparkle-MCP")
-        .send()
-        .await
-        .context("Failed to fetch URL")?;
-
-    if !resp.status().is_success() {
-        anyhow::bail!("Failed to fetch URL: {}", resp.status());
-    }
-
-    let text = resp.text().await?;
-    Ok(text)
-}
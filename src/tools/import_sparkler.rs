@@ -0,0 +1,79 @@
+use crate::bundle::{ImportOptions, OnConflict, import_sparkler as import_bundle};
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ImportSparklerParams {
+    /// Path of a bundle written by export_sparkler
+    pub path: String,
+    /// Optional: Import under this name instead of the bundle's
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional: If the sparkler already exists - fail (default), rename (import as "Name-2") or merge (new anchors, evolution entries and insights are added; identity is replaced, keeping the old one in history)
+    #[serde(default)]
+    pub on_conflict: OnConflict,
+    /// Optional: Replace your collaborator profile with the bundle's, if it has one (default false)
+    #[serde(default)]
+    pub include_profile: bool,
+}
+
+pub async fn import_sparkler(
+    Parameters(params): Parameters<ImportSparklerParams>,
+) -> Result<CallToolResult, McpError> {
+    let mut config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let home_dir = dirs::home_dir().ok_or_else(|| {
+        McpError::internal_error("Could not determine home directory".to_string(), None)
+    })?;
+    let sparkle_dir = home_dir.join(SPARKLE_DIR);
+
+    let options = ImportOptions {
+        name: params.name,
+        on_conflict: params.on_conflict,
+        include_profile: params.include_profile,
+    };
+    let report = match import_bundle(&sparkle_dir, &mut config, Path::new(&params.path), &options) {
+        Ok(report) => report,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Import failed: {:#}",
+                e
+            ))]));
+        }
+    };
+
+    crate::sync::record_change(&format!("Import sparkler {}", report.name)).await;
+
+    let mut messages = Vec::new();
+    if let Some(migrated) = &report.migrated {
+        messages.push(format!(
+            "🔄 Migrated to multi-sparkler setup ({} is the default)",
+            migrated
+        ));
+    }
+    messages.push(if report.merged {
+        format!("✨ Merged the bundle into {}", report.name)
+    } else {
+        format!("✨ Imported {}", report.name)
+    });
+    messages.push(format!("Files: {}", report.files.join(", ")));
+    if report.profile_imported {
+        messages.push(
+            "Collaborator profile replaced (the previous one is in list_versions)".to_string(),
+        );
+    }
+    messages.push(format!(
+        "Use embody_sparkle(sparkler='{}') to activate.",
+        report.name
+    ));
+
+    Ok(CallToolResult::success(vec![Content::text(
+        messages.join("\n"),
+    )]))
+}
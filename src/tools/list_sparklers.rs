@@ -1,4 +1,6 @@
+use crate::constants::SPARKLE_DIR;
 use crate::context_loader::load_config;
+use crate::templates::list_templates;
 use rmcp::{
     ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
 };
@@ -35,5 +37,16 @@ pub async fn list_sparklers(
         response.push_str(&format!("• {} (single-sparkler mode)\n", name));
     }
 
+    // Templates available to create_sparkler
+    if let Some(home_dir) = dirs::home_dir() {
+        let templates = list_templates(&home_dir.join(SPARKLE_DIR), &config);
+        if !templates.is_empty() {
+            response.push_str(&format!(
+                "\n**Templates for create_sparkler:** {}\n",
+                templates.join(", ")
+            ));
+        }
+    }
+
     Ok(CallToolResult::success(vec![Content::text(response)]))
 }
@@ -0,0 +1,62 @@
+use crate::context_loader::load_config;
+use crate::history::{ContextFile, History};
+use crate::types::TIMESTAMP_FORMAT;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListVersionsParams {
+    /// Which file: collaborator_profile, sparkler_identity, pattern_anchors,
+    /// collaboration_evolution, workspace_map or config
+    pub file: ContextFile,
+    /// Optional: Sparkler whose identity, anchors or evolution to list (defaults to the current sparkler)
+    #[serde(default)]
+    pub sparkler: Option<String>,
+}
+
+pub async fn list_versions(
+    Parameters(params): Parameters<ListVersionsParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let file_path = params
+        .file
+        .path(&config, params.sparkler.as_deref())
+        .map_err(|e| McpError::internal_error(format!("Failed to locate file: {}", e), None))?;
+
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    if let Err(e) = history.migrate_legacy_backups(&file_path) {
+        tracing::warn!("Failed to migrate legacy backups of {:?}: {}", file_path, e);
+    }
+    let versions = history.list_versions(&file_path);
+
+    if versions.is_empty() {
+        return Ok(CallToolResult::success(vec![Content::text(format!(
+            "No saved versions of {} yet. A version is saved each time a tool changes it.",
+            params.file.file_name()
+        ))]));
+    }
+
+    let mut output = format!(
+        "# Versions of {} ({})\n\nNewest first. Pass an id to restore_version to bring one back.\n\n",
+        params.file.file_name(),
+        versions.len()
+    );
+    for version in &versions {
+        output.push_str(&format!(
+            "- `{}` - saved {} ({} bytes)\n",
+            version.id,
+            config
+                .human
+                .format_time(version.timestamp, TIMESTAMP_FORMAT),
+            version.size
+        ));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
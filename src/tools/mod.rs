@@ -1,11 +1,22 @@
 pub mod checkpoint;
 pub mod create_sparkler;
+pub mod doctor;
 pub mod embody_sparkle;
+pub mod export_sparkler;
 pub mod fetch_profile_data;
+pub mod import_sparkler;
 pub mod list_sparklers;
+pub mod list_versions;
 pub mod load_evolution;
+pub mod patch_collaborator_profile;
 pub mod rename_sparkler;
+pub mod restore_version;
+pub mod retract_insight;
 pub mod save_insight;
+pub mod search_insights;
 pub mod setup_sparkle;
+pub mod show_effective_identity;
 pub mod update_collaborator_profile;
+pub mod update_human_config;
+pub mod update_insight;
 pub mod update_sparkler_identity;
@@ -0,0 +1,136 @@
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use crate::history::History;
+use crate::profile_patch::{
+    PatchOperation, apply_operations, base_token, parse_sections, unified_diff,
+};
+use rmcp::{
+    ErrorData as McpError,
+    handler::server::wrapper::Parameters,
+    model::{CallToolResult, Content},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::fs::read_to_string;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PatchCollaboratorProfileParams {
+    /// Section operations, applied in order
+    pub operations: Vec<PatchOperation>,
+    /// Optional: Apply the changes (default false returns a diff preview without writing)
+    #[serde(default)]
+    pub confirm: bool,
+    /// Required with confirm: the base token returned by the preview, so the
+    /// changes are only applied to the profile that was previewed
+    #[serde(default)]
+    pub base: Option<String>,
+}
+
+pub async fn patch_collaborator_profile(
+    Parameters(params): Parameters<PatchCollaboratorProfileParams>,
+) -> Result<CallToolResult, McpError> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| McpError::internal_error("Could not determine home directory", None))?;
+
+    let sparkle_dir = home_dir.join(SPARKLE_DIR);
+
+    if !sparkle_dir.exists() {
+        return Ok(CallToolResult::error(vec![Content::text(
+            "Please run the sparkle tool first to initialize your Sparkle profile.",
+        )]));
+    }
+
+    let file_path = sparkle_dir.join("collaborator-profile.md");
+    let current = if file_path.exists() {
+        read_to_string(&file_path).map_err(|e| {
+            McpError::internal_error(
+                "Failed to read collaborator profile",
+                Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
+            )
+        })?
+    } else {
+        String::new()
+    };
+
+    // A confirmed patch must apply to the profile the user saw in the preview
+    let base = base_token(&current);
+    if params.confirm && params.base.as_deref().map(str::trim) != Some(base.as_str()) {
+        let reason = match params.base {
+            Some(_) => "the profile changed since the preview",
+            None => "confirm requires the base token from the preview",
+        };
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Profile not changed: {}. Call patch_collaborator_profile without confirm \
+            to preview the changes against the current profile.",
+            reason
+        ))]));
+    }
+
+    let updated = match apply_operations(&current, &params.operations) {
+        Ok(updated) => updated,
+        Err(e) => {
+            let headings = parse_sections(&current)
+                .iter()
+                .map(|s| format!("{} {}", "#".repeat(s.level), s.title))
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Profile not changed: {}\n\nCurrent sections:\n{}",
+                e, headings
+            ))]));
+        }
+    };
+
+    if updated == current {
+        return Ok(CallToolResult::success(vec![Content::text(
+            "No changes - the profile already matches these operations.",
+        )]));
+    }
+
+    let diff = unified_diff(&current, &updated, "collaborator-profile.md");
+
+    if !params.confirm {
+        return Ok(CallToolResult::success(vec![Content::text(format!(
+            "📝 Preview of profile changes (nothing written yet):\n\n```diff\n{}```\n\n\
+            Show this to the user. If they approve, call patch_collaborator_profile again \
+            with the same operations, confirm: true and base: \"{}\".",
+            diff, base
+        ))]));
+    }
+
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    if let Err(e) = history.migrate_legacy_backups(&file_path) {
+        tracing::warn!("Failed to migrate legacy backups of {:?}: {}", file_path, e);
+    }
+    let version = history.write(&file_path, updated.as_bytes()).map_err(|e| {
+        McpError::internal_error(
+            "Failed to write collaborator profile",
+            Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
+        )
+    })?;
+    let backup_info = version
+        .map(|v| format!("Previous version saved: {}\n", v.id))
+        .unwrap_or_default();
+
+    crate::sync::record_change(&format!(
+        "Patch collaborator profile: {}",
+        params
+            .operations
+            .iter()
+            .map(|op| format!("{} {}", op.op.as_str(), op.heading.trim()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+    .await;
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "✨ Collaborator profile updated ({} operation(s) applied)\n\n{}\n```diff\n{}```",
+        params.operations.len(),
+        backup_info,
+        diff
+    ))]))
+}
@@ -1,5 +1,6 @@
 use crate::constants::SPARKLE_DIR;
 use crate::context_loader::load_config;
+use crate::history::History;
 use rmcp::{ErrorData as McpError, handler::server::wrapper::Parameters, model::*};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -108,7 +109,11 @@ pub async fn rename_sparkler(
         McpError::internal_error(format!("Failed to serialize config: {}", e), None)
     })?;
 
-    fs::write(&config_path, config_string)
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    history
+        .write(&config_path, config_string)
         .map_err(|e| McpError::internal_error(format!("Failed to write config: {}", e), None))?;
 
     let mut response = format!(
@@ -118,6 +123,8 @@ pub async fn rename_sparkler(
         old_name, new_name
     );
 
+    crate::sync::record_change(&format!("Rename sparkler {} to {}", old_name, new_name)).await;
+
     if !response_parts.is_empty() {
         response.push_str(&response_parts.join("\n"));
         response.push_str("\n\n");
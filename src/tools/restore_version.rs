@@ -0,0 +1,79 @@
+use crate::context_loader::load_config;
+use crate::history::{ContextFile, History};
+use crate::types::TIMESTAMP_FORMAT;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RestoreVersionParams {
+    /// Which file: collaborator_profile, sparkler_identity, pattern_anchors,
+    /// collaboration_evolution, workspace_map or config
+    pub file: ContextFile,
+    /// Version id to restore (shown by list_versions)
+    pub version: String,
+    /// Optional: Sparkler whose identity, anchors or evolution to restore (defaults to the current sparkler)
+    #[serde(default)]
+    pub sparkler: Option<String>,
+}
+
+pub async fn restore_version(
+    Parameters(params): Parameters<RestoreVersionParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let file_path = params
+        .file
+        .path(&config, params.sparkler.as_deref())
+        .map_err(|e| McpError::internal_error(format!("Failed to locate file: {}", e), None))?;
+
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    if let Err(e) = history.migrate_legacy_backups(&file_path) {
+        tracing::warn!("Failed to migrate legacy backups of {:?}: {}", file_path, e);
+    }
+
+    let version = history
+        .find_version(&file_path, &params.version)
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Version '{}' of {} not found. Use list_versions to see saved versions.",
+                    params.version.trim(),
+                    params.file.file_name()
+                ),
+                None,
+            )
+        })?;
+
+    let replaced = history.restore_version(&file_path, &version).map_err(|e| {
+        McpError::internal_error(
+            format!("Failed to restore {}: {}", params.file.file_name(), e),
+            None,
+        )
+    })?;
+
+    crate::sync::record_change(&format!(
+        "Restore {} to version {}",
+        params.file.file_name(),
+        version.id
+    ))
+    .await;
+
+    let undo = replaced
+        .map(|v| format!("\n\nThe replaced content was saved as version {}.", v.id))
+        .unwrap_or_default();
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "⏪ Restored {} to version {} (saved {}).{}",
+        params.file.file_name(),
+        version.id,
+        config
+            .human
+            .format_time(version.timestamp, TIMESTAMP_FORMAT),
+        undo
+    ))]))
+}
@@ -0,0 +1,75 @@
+use crate::context_loader::load_config;
+use crate::insights::{
+    InsightStatus, annotate_markdown_file, find_record, load_records, store_dirs, store_history,
+    write_records,
+};
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RetractInsightParams {
+    /// Id of the insight to retract (shown by save_insight and search_insights)
+    pub id: String,
+    /// Optional: Why the insight is being retracted
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Optional: Workspace path, to include that workspace's insights (current working directory)
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+}
+
+pub async fn retract_insight(
+    Parameters(params): Parameters<RetractInsightParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let dirs = store_dirs(&config, params.workspace_path.as_deref()).map_err(|e| {
+        McpError::internal_error(format!("Failed to locate insight stores: {}", e), None)
+    })?;
+
+    let id = params.id.trim();
+    let (store_dir, record) = find_record(&dirs, id)
+        .ok_or_else(|| McpError::invalid_params(format!("Insight '{}' not found", id), None))?;
+
+    if !record.is_active() {
+        return Err(McpError::invalid_params(
+            format!("Insight '{}' is already {:?}", id, record.status),
+            None,
+        ));
+    }
+
+    let mut records = load_records(&store_dir);
+    for r in records.iter_mut().filter(|r| r.id == record.id) {
+        r.status = InsightStatus::Retracted;
+        r.retraction_reason = params.reason.clone();
+    }
+    write_records(&store_dir, &records).map_err(|e| {
+        McpError::internal_error(format!("Failed to write insight records: {}", e), None)
+    })?;
+
+    let note = match &params.reason {
+        Some(reason) => format!("**Status**: Retracted - {}", reason),
+        None => "**Status**: Retracted".to_string(),
+    };
+    let history = store_history(&config, &store_dir);
+    annotate_markdown_file(&history, &store_dir, &record, &note).map_err(|e| {
+        McpError::internal_error(format!("Failed to annotate insight: {}", e), None)
+    })?;
+
+    crate::sync::record_change(&match &params.reason {
+        Some(reason) => format!("Retract insight {}: {}", record.id, reason),
+        None => format!("Retract insight {}", record.id),
+    })
+    .await;
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "🗑️ Insight {} retracted\n\nType: {:?}\nContent: {}\n\nThe entry stays in {} as history but is no longer loaded during embodiment.",
+        record.id,
+        record.insight_type,
+        record.content,
+        record.insight_type.file_name()
+    ))]))
+}
@@ -1,13 +1,16 @@
 use crate::constants::SPARKLE_DIR;
 use crate::context_loader::{get_context_dir, load_config};
+use crate::insights::{
+    INSIGHTS_FILE, InsightRecord, InsightStatus, append_record, canonical_workspace,
+    new_insight_id, store_history, workspace_store_dir,
+};
 use crate::types::{InsightType, SaveInsightParams};
 use rmcp::{
     ErrorData as McpError,
     handler::server::wrapper::Parameters,
     model::{CallToolResult, Content},
 };
-use std::fs::{OpenOptions, create_dir_all};
-use std::io::Write;
+use std::fs::create_dir_all;
 
 pub async fn save_insight(
     Parameters(params): Parameters<SaveInsightParams>,
@@ -42,73 +45,77 @@ pub async fn save_insight(
                 )
             })?;
 
-            match params.insight_type {
-                InsightType::PatternAnchor => context_dir.join("pattern-anchors.md"),
-                InsightType::CollaborationEvolution => {
-                    context_dir.join("collaboration-evolution.md")
-                }
-                _ => unreachable!(),
-            }
+            context_dir.join(params.insight_type.file_name())
         }
         InsightType::WorkspaceInsight => {
-            // Workspace insights are shared across all Sparklers
-            create_dir_all(&sparkle_dir).map_err(|e| {
+            // Workspace insights are shared across all Sparklers. With a workspace they
+            // belong to that workspace's .sparkle-space; without one they are
+            // cross-workspace notes in the shared map.
+            let store_dir = match &params.workspace_path {
+                Some(workspace_path) => workspace_store_dir(workspace_path),
+                None => sparkle_dir.clone(),
+            };
+            create_dir_all(&store_dir).map_err(|e| {
                 McpError::internal_error(
-                    "Failed to create sparkle directory",
-                    Some(serde_json::json!({"error": e.to_string()})),
+                    "Failed to create insight directory",
+                    Some(serde_json::json!({"path": store_dir.display().to_string(), "error": e.to_string()})),
                 )
             })?;
-            sparkle_dir.join("workspace-map.md")
+            store_dir.join(params.insight_type.file_name())
         }
     };
 
-    // Create timestamp
-    let now = chrono::Utc::now();
-    let timestamp = now.format("%Y-%m-%d %H:%M:%S UTC").to_string();
-
-    // Format the insight entry - simple format matching existing files
-    let mut entry = format!(
-        "\n## {} - {}\n\n",
-        timestamp,
-        get_insight_title(&params.insight_type)
-    );
-
-    // Add the main content
-    entry.push_str(&format!("{}\n\n", params.content));
-
-    // Add context if provided
-    if let Some(context) = &params.context {
-        entry.push_str(&format!("**Context**: {}\n\n", context));
-    }
+    // Resolve who is saving this insight so records can be filtered by sparkler
+    let sparkler = params
+        .sparkler
+        .clone()
+        .or_else(|| config.get_single_sparkler_name())
+        .or_else(|| config.get_default_sparkler_name());
 
-    // Add tags if provided
-    if let Some(tags) = &params.tags {
-        if !tags.is_empty() {
-            entry.push_str(&format!("**Tags**: {}\n\n", tags.join(", ")));
-        }
-    }
+    let now = chrono::Utc::now();
+    let record = InsightRecord {
+        id: new_insight_id(now),
+        insight_type: params.insight_type.clone(),
+        timestamp: now,
+        sparkler,
+        workspace: params.workspace_path.as_deref().map(canonical_workspace),
+        tags: params.tags.clone().unwrap_or_default(),
+        content: params.content.clone(),
+        context: params.context.clone(),
+        status: InsightStatus::Active,
+        supersedes: None,
+        superseded_by: None,
+        retraction_reason: None,
+    };
 
-    entry.push_str("---\n");
+    let entry = record.to_markdown(&config.human);
 
-    // Append to file (create if doesn't exist)
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&file_path)
+    // Append to file (create if doesn't exist), keeping the previous content in the history
+    let store_dir = file_path.parent().unwrap_or(&sparkle_dir);
+    store_history(&config, store_dir)
+        .append(&file_path, entry)
         .map_err(|e| {
             McpError::internal_error(
-                "Failed to open insight file",
+                "Failed to write to insight file",
                 Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
             )
         })?;
 
-    file.write_all(entry.as_bytes()).map_err(|e| {
+    // Record the structured form next to the markdown file
+    append_record(store_dir, &record).map_err(|e| {
         McpError::internal_error(
-            "Failed to write to insight file",
-            Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
+            "Failed to write insight record",
+            Some(serde_json::json!({"path": store_dir.join(INSIGHTS_FILE).display().to_string(), "error": e.to_string()})),
         )
     })?;
 
+    crate::sync::record_change(&format!(
+        "Save {} insight {}",
+        record.insight_type.title().to_lowercase(),
+        record.id
+    ))
+    .await;
+
     // Return success message
     let file_display = file_path
         .strip_prefix(&home_dir)
@@ -116,8 +123,9 @@ pub async fn save_insight(
         .unwrap_or_else(|_| file_path.display().to_string());
 
     let result_message = format!(
-        "✨ Insight saved to {}\n\nType: {:?}\nContent: {}\n{}",
+        "✨ Insight saved to {}\n\nId: {}\nType: {:?}\nContent: {}\n{}",
         file_display,
+        record.id,
         params.insight_type,
         params.content,
         if let Some(context) = &params.context {
@@ -129,11 +137,3 @@ pub async fn save_insight(
 
     Ok(CallToolResult::success(vec![Content::text(result_message)]))
 }
-
-fn get_insight_title(insight_type: &InsightType) -> &'static str {
-    match insight_type {
-        InsightType::PatternAnchor => "Pattern Anchor",
-        InsightType::CollaborationEvolution => "Collaboration Evolution",
-        InsightType::WorkspaceInsight => "Workspace Insight",
-    }
-}
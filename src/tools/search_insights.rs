@@ -0,0 +1,122 @@
+use crate::context_loader::load_config;
+use crate::insights::{InsightQuery, load_all_records, search, store_dirs};
+use crate::types::InsightType;
+use chrono::NaiveDate;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SearchInsightsParams {
+    /// Optional: Free text to rank insights by (matched against content, context and tags)
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Optional: Only return insights carrying all of these tags
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Optional: Only return insights of this type
+    #[serde(default)]
+    pub insight_type: Option<InsightType>,
+    /// Optional: Only return insights saved by this sparkler
+    #[serde(default)]
+    pub sparkler: Option<String>,
+    /// Optional: Workspace path, to include that workspace's insights (current working directory)
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+    /// Optional: Only return insights saved on or after this date (YYYY-MM-DD)
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Optional: Only return insights saved on or before this date (YYYY-MM-DD)
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Optional: Also return superseded and retracted insights (default false)
+    #[serde(default)]
+    pub include_inactive: Option<bool>,
+    /// Optional: Maximum number of results (default 20)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+pub async fn search_insights(
+    Parameters(params): Parameters<SearchInsightsParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let query = InsightQuery {
+        text: params.query,
+        tags: params.tags.unwrap_or_default(),
+        insight_type: params.insight_type,
+        sparkler: params.sparkler,
+        since: parse_date(params.since.as_deref(), "since")?,
+        until: parse_date(params.until.as_deref(), "until")?,
+        include_inactive: params.include_inactive.unwrap_or(false),
+    };
+
+    let dirs = store_dirs(&config, params.workspace_path.as_deref()).map_err(|e| {
+        McpError::internal_error(format!("Failed to locate insight stores: {}", e), None)
+    })?;
+
+    let results = search(load_all_records(&dirs), &query);
+    let total = results.len();
+    let limit = params.limit.unwrap_or(20);
+
+    if total == 0 {
+        return Ok(CallToolResult::success(vec![Content::text(
+            "No matching insights found.",
+        )]));
+    }
+
+    let mut response = format!("**Found {} insight(s)**", total);
+    if total > limit {
+        response.push_str(&format!(" (showing first {})", limit));
+    }
+    response.push_str("\n\n");
+
+    for record in results.iter().take(limit) {
+        response.push_str(&format!(
+            "### {} - {}\n",
+            config
+                .human
+                .format_time(record.timestamp, "%Y-%m-%d %H:%M %Z"),
+            record.insight_type.title()
+        ));
+        response.push_str(&format!("**Id**: {}\n", record.id));
+        if !record.is_active() {
+            response.push_str(&format!("**Status**: {:?}\n", record.status));
+        }
+        if let Some(sparkler) = &record.sparkler {
+            response.push_str(&format!("**Sparkler**: {}\n", sparkler));
+        }
+        if let Some(workspace) = &record.workspace {
+            response.push_str(&format!("**Workspace**: {}\n", workspace));
+        }
+        if !record.tags.is_empty() {
+            response.push_str(&format!("**Tags**: {}\n", record.tags.join(", ")));
+        }
+        response.push_str(&format!("\n{}\n", record.content));
+        if let Some(context) = &record.context {
+            response.push_str(&format!("\n**Context**: {}\n", context));
+        }
+        response.push('\n');
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(response)]))
+}
+
+fn parse_date(value: Option<&str>, field: &str) -> Result<Option<NaiveDate>, McpError> {
+    value
+        .map(|v| {
+            NaiveDate::parse_from_str(v.trim(), "%Y-%m-%d").map_err(|e| {
+                McpError::invalid_params(
+                    format!(
+                        "Invalid '{}' date '{}' (expected YYYY-MM-DD): {}",
+                        field, v, e
+                    ),
+                    None,
+                )
+            })
+        })
+        .transpose()
+}
@@ -0,0 +1,79 @@
+use crate::context_loader::load_config;
+use crate::sparkle_loader::{
+    IDENTITY_DIR, IdentityLayer, effective_identity_files, identity_context,
+};
+use crate::template::render;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ShowEffectiveIdentityParams {
+    /// Optional: Sparkler to resolve overrides for (defaults to the current sparkler)
+    #[serde(default)]
+    pub sparkler: Option<String>,
+    /// Optional: Also show the content of each file (default false)
+    #[serde(default)]
+    pub include_content: bool,
+}
+
+pub async fn show_effective_identity(
+    Parameters(params): Parameters<ShowEffectiveIdentityParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let files = effective_identity_files(&config, params.sparkler.as_deref());
+    let context = identity_context(&config, params.sparkler.as_deref());
+
+    let mut response = String::from(
+        "**Effective core identity** (first match wins: sparkler → user → built-in)\n\n",
+    );
+    let mut warnings = Vec::new();
+    for file in &files {
+        warnings.extend(
+            render(&file.content, &context)
+                .warnings
+                .into_iter()
+                .map(|warning| format!("{}: {}", file.name, warning)),
+        );
+        match &file.path {
+            Some(path) => response.push_str(&format!(
+                "• {} - {} ({})\n",
+                file.name,
+                file.layer.as_str(),
+                path.display()
+            )),
+            None => response.push_str(&format!("• {} - {}\n", file.name, file.layer.as_str())),
+        }
+    }
+
+    if files.iter().all(|f| f.layer == IdentityLayer::BuiltIn) {
+        response.push_str(&format!(
+            "\nTo customize a file, copy it into ~/.sparkle/{}/ (all sparklers) or \
+            ~/.sparkle/sparklers/<name>/{}/ (one sparkler) under the same name and edit it.\n",
+            IDENTITY_DIR, IDENTITY_DIR
+        ));
+    }
+
+    if !warnings.is_empty() {
+        response.push_str("\n⚠️ Template problems:\n");
+        for warning in &warnings {
+            response.push_str(&format!("• {}\n", warning));
+        }
+    }
+
+    if params.include_content {
+        for file in &files {
+            response.push_str(&format!(
+                "\n---\n\n### {} ({})\n\n{}\n",
+                file.name,
+                file.layer.as_str(),
+                render(&file.content, &context).text.trim_end()
+            ));
+        }
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(response)]))
+}
@@ -1,4 +1,6 @@
 use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use crate::history::History;
 use rmcp::{
     ErrorData as McpError,
     handler::server::wrapper::Parameters,
@@ -6,7 +8,6 @@ use rmcp::{
 };
 use schemars::JsonSchema;
 use serde::Deserialize;
-use std::fs::{copy, write};
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateCollaboratorProfileParams {
@@ -31,36 +32,31 @@ pub async fn update_collaborator_profile(
 
     let file_path = sparkle_dir.join("collaborator-profile.md");
 
-    // Create backup if file exists
-    let backup_info = if file_path.exists() {
-        let now = chrono::Utc::now();
-        let timestamp = now.format("%Y%m%d-%H%M%S").to_string();
-        let backup_path = sparkle_dir.join(format!("collaborator-profile.{}.md", timestamp));
-
-        copy(&file_path, &backup_path).map_err(|e| {
+    // Keep the current profile in the version history, then replace it
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    if let Err(e) = history.migrate_legacy_backups(&file_path) {
+        tracing::warn!("Failed to migrate legacy backups of {:?}: {}", file_path, e);
+    }
+    let version = history
+        .write(&file_path, params.content.as_bytes())
+        .map_err(|e| {
             McpError::internal_error(
-                "Failed to create backup",
-                Some(serde_json::json!({"error": e.to_string()})),
+                "Failed to write collaborator profile",
+                Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
             )
         })?;
-
-        let backup_display = backup_path
-            .strip_prefix(&home_dir)
-            .map(|p| format!("~/{}", p.display()))
-            .unwrap_or_else(|_| backup_path.display().to_string());
-
-        Some(format!("Backup created: {}\n", backup_display))
-    } else {
-        None
-    };
-
-    // Write the new content (replacing the file)
-    write(&file_path, params.content.as_bytes()).map_err(|e| {
-        McpError::internal_error(
-            "Failed to write collaborator profile",
-            Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
+    let backup_info = version.map(|v| {
+        format!(
+            "Previous version saved: {} (restore_version can bring it back)\n",
+            v.id
         )
-    })?;
+    });
+
+    crate::sync::record_change("Update collaborator profile").await;
 
     // Return success message
     let file_display = file_path
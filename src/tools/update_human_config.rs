@@ -0,0 +1,136 @@
+use crate::constants::SPARKLE_DIR;
+use crate::context_loader::load_config;
+use crate::history::History;
+use crate::types::{HumanConfig, TIMESTAMP_FORMAT};
+use chrono_tz::Tz;
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateHumanConfigParams {
+    /// Optional: Name to use for the human
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Optional: Pronouns, e.g. "she/her" (empty string clears)
+    #[serde(default)]
+    pub pronouns: Option<String>,
+    /// Optional: IANA time zone, e.g. "Europe/Berlin" (empty string clears, falling back to system local time)
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Optional: Preferred language for conversation (empty string clears)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Optional: Role, e.g. "Staff engineer" (empty string clears)
+    #[serde(default)]
+    pub role: Option<String>,
+    /// Optional: Team (empty string clears)
+    #[serde(default)]
+    pub team: Option<String>,
+    /// Optional: Preferences to set, e.g. {"code_review": "terse"}; a null value removes the key
+    #[serde(default)]
+    pub preferences: Option<BTreeMap<String, serde_json::Value>>,
+}
+
+/// Apply `value` to an optional field: empty clears, anything else sets
+fn set_optional(field: &mut Option<String>, value: Option<String>) {
+    if let Some(value) = value {
+        let value = value.trim();
+        *field = (!value.is_empty()).then(|| value.to_string());
+    }
+}
+
+fn describe(human: &HumanConfig) -> String {
+    let mut lines = vec![format!("- name: {}", human.name)];
+    let fields = [
+        ("pronouns", &human.pronouns),
+        ("timezone", &human.timezone),
+        ("language", &human.language),
+        ("role", &human.role),
+        ("team", &human.team),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value {
+            lines.push(format!("- {}: {}", label, value));
+        }
+    }
+    for (key, value) in &human.preferences {
+        lines.push(format!("- preferences.{}: {}", key, value));
+    }
+    lines.join("\n")
+}
+
+pub async fn update_human_config(
+    Parameters(params): Parameters<UpdateHumanConfigParams>,
+) -> Result<CallToolResult, McpError> {
+    let mut config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    if let Some(timezone) = params.timezone.as_deref().map(str::trim)
+        && !timezone.is_empty()
+        && timezone.parse::<Tz>().is_err()
+    {
+        return Err(McpError::invalid_params(
+            format!(
+                "Unknown time zone '{}'. Use an IANA name such as \"Europe/Berlin\" or \"America/New_York\".",
+                timezone
+            ),
+            None,
+        ));
+    }
+
+    let human = &mut config.human;
+    if let Some(name) = params.name {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(McpError::invalid_params("Name cannot be empty", None));
+        }
+        human.name = name.to_string();
+    }
+    set_optional(&mut human.pronouns, params.pronouns);
+    set_optional(&mut human.timezone, params.timezone);
+    set_optional(&mut human.language, params.language);
+    set_optional(&mut human.role, params.role);
+    set_optional(&mut human.team, params.team);
+
+    for (key, value) in params.preferences.unwrap_or_default() {
+        if value.is_null() {
+            human.preferences.remove(&key);
+            continue;
+        }
+        let value = toml::Value::try_from(&value).map_err(|e| {
+            McpError::invalid_params(
+                format!("Invalid value for preference '{}': {}", key, e),
+                None,
+            )
+        })?;
+        human.preferences.insert(key, value);
+    }
+
+    let home_dir = dirs::home_dir().ok_or_else(|| {
+        McpError::internal_error("Could not determine home directory".to_string(), None)
+    })?;
+    let config_path = home_dir.join(SPARKLE_DIR).join("config.toml");
+    let config_string = toml::to_string_pretty(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize config: {}", e), None)
+    })?;
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
+    })?;
+    history
+        .write(&config_path, config_string)
+        .map_err(|e| McpError::internal_error(format!("Failed to write config: {}", e), None))?;
+
+    crate::sync::record_change(&format!("Update human config for {}", config.human.name)).await;
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "✨ Updated [human] in config.toml\n\n{}\n\nLocal time: {}\n\n\
+        These details are included in embodiment and available to templates as [human.<field>].",
+        describe(&config.human),
+        config
+            .human
+            .format_time(chrono::Utc::now(), TIMESTAMP_FORMAT)
+    ))]))
+}
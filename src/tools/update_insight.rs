@@ -0,0 +1,112 @@
+use crate::context_loader::load_config;
+use crate::insights::{
+    InsightRecord, InsightStatus, annotate_markdown_file, find_record, load_records,
+    new_insight_id, store_dirs, store_history, write_records,
+};
+use rmcp::{
+    ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateInsightParams {
+    /// Id of the insight to update (shown by save_insight and search_insights)
+    pub id: String,
+    /// The corrected insight content
+    pub content: String,
+    /// Optional: Updated context (keeps the existing context if omitted)
+    #[serde(default)]
+    pub context: Option<String>,
+    /// Optional: Updated tags (keeps the existing tags if omitted)
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    /// Optional: Which sparkler is making the update (for multi-sparkler mode)
+    #[serde(default)]
+    pub sparkler: Option<String>,
+    /// Optional: Workspace path, to include that workspace's insights (current working directory)
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+}
+
+pub async fn update_insight(
+    Parameters(params): Parameters<UpdateInsightParams>,
+) -> Result<CallToolResult, McpError> {
+    let config = load_config()
+        .map_err(|e| McpError::internal_error(format!("Failed to load config: {}", e), None))?;
+
+    let dirs = store_dirs(&config, params.workspace_path.as_deref()).map_err(|e| {
+        McpError::internal_error(format!("Failed to locate insight stores: {}", e), None)
+    })?;
+
+    let id = params.id.trim();
+    let (store_dir, old) = find_record(&dirs, id)
+        .ok_or_else(|| McpError::invalid_params(format!("Insight '{}' not found", id), None))?;
+
+    if !old.is_active() {
+        return Err(McpError::invalid_params(
+            format!(
+                "Insight '{}' is already {:?} and can't be updated",
+                id, old.status
+            ),
+            None,
+        ));
+    }
+
+    let now = chrono::Utc::now();
+    let new = InsightRecord {
+        id: new_insight_id(now),
+        insight_type: old.insight_type.clone(),
+        timestamp: now,
+        sparkler: params.sparkler.or_else(|| old.sparkler.clone()),
+        workspace: old.workspace.clone(),
+        tags: params.tags.unwrap_or_else(|| old.tags.clone()),
+        content: params.content,
+        context: params.context.or_else(|| old.context.clone()),
+        status: InsightStatus::Active,
+        supersedes: Some(old.id.clone()),
+        superseded_by: None,
+        retraction_reason: None,
+    };
+
+    // Mark the old record superseded and add the replacement
+    let mut records = load_records(&store_dir);
+    for record in records.iter_mut().filter(|r| r.id == old.id) {
+        record.status = InsightStatus::Superseded;
+        record.superseded_by = Some(new.id.clone());
+    }
+    records.push(new.clone());
+    write_records(&store_dir, &records).map_err(|e| {
+        McpError::internal_error(format!("Failed to write insight records: {}", e), None)
+    })?;
+
+    // Keep the old markdown entry as history, then append the replacement
+    let history = store_history(&config, &store_dir);
+    annotate_markdown_file(
+        &history,
+        &store_dir,
+        &old,
+        &format!("**Status**: Superseded by {}", new.id),
+    )
+    .map_err(|e| McpError::internal_error(format!("Failed to annotate insight: {}", e), None))?;
+
+    let file_path = store_dir.join(new.insight_type.file_name());
+    history
+        .append(&file_path, new.to_markdown(&config.human))
+        .map_err(|e| {
+            McpError::internal_error(
+                "Failed to write to insight file",
+                Some(serde_json::json!({"path": file_path.display().to_string(), "error": e.to_string()})),
+            )
+        })?;
+
+    crate::sync::record_change(&format!(
+        "Update insight {} (supersedes {})",
+        new.id, old.id
+    ))
+    .await;
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "✨ Insight updated\n\nPrevious id: {} (now superseded)\nNew id: {}\nType: {:?}\nContent: {}",
+        old.id, new.id, new.insight_type, new.content
+    ))]))
+}
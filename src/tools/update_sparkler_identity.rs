@@ -1,9 +1,9 @@
 use crate::context_loader::{get_context_dir, load_config};
+use crate::history::History;
 use rmcp::{
     ErrorData as McpError, handler::server::wrapper::Parameters, model::*, schemars::JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use std::fs;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateSparklerIdentityParams {
@@ -24,12 +24,30 @@ pub async fn update_sparkler_identity(
 
     let identity_path = context_dir.join("sparkler-identity.md");
 
-    // Replace with new content
-    fs::write(&identity_path, params.content.trim()).map_err(|e| {
-        McpError::internal_error(format!("Failed to write sparkler-identity.md: {}", e), None)
+    // Keep the current identity in the version history, then replace it
+    let history = History::from_config(&config).map_err(|e| {
+        McpError::internal_error(format!("Failed to open version history: {}", e), None)
     })?;
+    let version = history
+        .write(&identity_path, params.content.trim())
+        .map_err(|e| {
+            McpError::internal_error(format!("Failed to write sparkler-identity.md: {}", e), None)
+        })?;
+
+    crate::sync::record_change(
+        &match sparkler.or_else(|| config.get_single_sparkler_name()) {
+            Some(sparkler) => format!("Update {} identity", sparkler),
+            None => "Update sparkler identity".to_string(),
+        },
+    )
+    .await;
+
+    let version_info = version
+        .map(|v| format!("Previous version saved: {}\n\n", v.id))
+        .unwrap_or_default();
 
     Ok(CallToolResult::success(vec![Content::text(format!(
-        "✨ Updated sparkler-identity.md\n\nRemember: Keep this concise - a definition, not a narrative."
+        "✨ Updated sparkler-identity.md\n\n{}Remember: Keep this concise - a definition, not a narrative.",
+        version_info
     ))]))
 }
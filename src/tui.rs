@@ -0,0 +1,604 @@
+//! Terminal UI for browsing and curating Sparkle context
+//!
+//! `sparkle-mcp tui` shows four tabs over the same storage the tools use:
+//! the configured sparklers, saved insights (which can be retracted), the
+//! workspace's working-memory snapshots and the embodiment the selected
+//! sparkler would get in the workspace.
+
+use crate::constants::SPARKLE_SPACE_DIR;
+use crate::context_loader::load_config;
+use crate::embodiment::{EMBODIMENT_MODES, Embodiment, RenderOptions, render_embodiment};
+use crate::insights::{InsightRecord, load_all_records, store_dirs};
+use crate::profile_patch::unified_diff;
+use crate::tools;
+use crate::tools::checkpoint::working_memory_history;
+use crate::types::{Config, FullEmbodimentParams, SparklerSettings};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Tabs, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::RawContent;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TAB_TITLES: [&str; 4] = ["Sparklers", "Insights", "Working memory", "Embodiment"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    Sparklers,
+    Insights,
+    WorkingMemory,
+    Embodiment,
+}
+
+impl Tab {
+    const ALL: [Tab; 4] = [
+        Tab::Sparklers,
+        Tab::Insights,
+        Tab::WorkingMemory,
+        Tab::Embodiment,
+    ];
+
+    fn index(self) -> usize {
+        Tab::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
+/// One configured sparkler
+struct SparklerEntry {
+    name: String,
+    default: bool,
+    dir: PathBuf,
+    settings: SparklerSettings,
+}
+
+/// The current working memory or one of its earlier versions
+struct Snapshot {
+    label: String,
+    content: String,
+}
+
+struct App {
+    workspace: PathBuf,
+    config: Config,
+    tab: Tab,
+    sparklers: Vec<SparklerEntry>,
+    sparkler_list: ListState,
+    insights: Vec<InsightRecord>,
+    insight_list: ListState,
+    show_inactive: bool,
+    /// Insight waiting for the user to confirm its retraction
+    confirm_retract: Option<String>,
+    snapshots: Vec<Snapshot>,
+    snapshot_list: ListState,
+    /// Compare a snapshot with the current working memory instead of the one before it
+    compare_with_current: bool,
+    embodiment: Option<Result<Embodiment, String>>,
+    section_list: ListState,
+    /// Mode override for the preview; `None` uses the sparkler's setting
+    mode: Option<String>,
+    scroll: u16,
+    status: String,
+    quit: bool,
+}
+
+/// Run the TUI until the user quits
+pub async fn run(
+    sparkle_dir: &Path,
+    workspace: PathBuf,
+    sparkler: Option<String>,
+) -> anyhow::Result<()> {
+    let config = load_config().map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?;
+    let mut app = App::new(sparkle_dir, workspace, config);
+    if let Some(sparkler) = sparkler {
+        let index = app
+            .sparklers
+            .iter()
+            .position(|s| s.name == sparkler)
+            .ok_or_else(|| anyhow::anyhow!("Sparkler '{}' not found in config", sparkler))?;
+        app.sparkler_list.select(Some(index));
+    }
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal).await;
+    ratatui::restore();
+    result
+}
+
+impl App {
+    fn new(sparkle_dir: &Path, workspace: PathBuf, config: Config) -> Self {
+        let sparklers = match &config.sparklers {
+            Some(sparklers) => sparklers
+                .iter()
+                .map(|s| SparklerEntry {
+                    name: s.name.clone(),
+                    default: s.default,
+                    dir: sparkle_dir.join("sparklers").join(&s.name),
+                    settings: s.settings.clone(),
+                })
+                .collect(),
+            None => vec![SparklerEntry {
+                name: config
+                    .get_single_sparkler_name()
+                    .unwrap_or_else(|| "Sparkle".to_string()),
+                default: true,
+                dir: sparkle_dir.to_path_buf(),
+                settings: config.sparkler_settings(None),
+            }],
+        };
+        let default = sparklers.iter().position(|s| s.default).unwrap_or(0);
+
+        let mut app = Self {
+            workspace,
+            config,
+            tab: Tab::Sparklers,
+            sparklers,
+            sparkler_list: ListState::default().with_selected(Some(default)),
+            insights: Vec::new(),
+            insight_list: ListState::default(),
+            show_inactive: false,
+            confirm_retract: None,
+            snapshots: Vec::new(),
+            snapshot_list: ListState::default(),
+            compare_with_current: false,
+            embodiment: None,
+            section_list: ListState::default(),
+            mode: None,
+            scroll: 0,
+            status: String::new(),
+            quit: false,
+        };
+        app.load_insights();
+        app.load_snapshots();
+        app
+    }
+
+    async fn run(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key.code).await;
+            }
+        }
+        Ok(())
+    }
+
+    fn workspace_path(&self) -> String {
+        self.workspace.to_string_lossy().into_owned()
+    }
+
+    fn selected_sparkler(&self) -> Option<&SparklerEntry> {
+        self.sparkler_list
+            .selected()
+            .and_then(|index| self.sparklers.get(index))
+    }
+
+    fn load_insights(&mut self) {
+        let workspace = self.workspace_path();
+        let dirs = store_dirs(&self.config, Some(&workspace)).unwrap_or_default();
+        let mut insights: Vec<InsightRecord> = load_all_records(&dirs)
+            .into_iter()
+            .filter(|record| self.show_inactive || record.is_active())
+            .collect();
+        insights.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        self.insights = insights;
+        select_within(&mut self.insight_list, self.insights.len());
+    }
+
+    fn load_snapshots(&mut self) {
+        let sparkle_space = self.workspace.join(SPARKLE_SPACE_DIR);
+        let path = sparkle_space.join("working-memory.json");
+        let mut snapshots = Vec::new();
+        if let Ok(content) = fs::read_to_string(&path) {
+            snapshots.push(Snapshot {
+                label: "current".to_string(),
+                content: pretty_json(&content),
+            });
+        }
+        for version in working_memory_history(&sparkle_space).list_versions(&path) {
+            if let Ok(content) = fs::read_to_string(&version.path) {
+                snapshots.push(Snapshot {
+                    label: self
+                        .config
+                        .human
+                        .format_time(version.timestamp, "%Y-%m-%d %H:%M:%S"),
+                    content: pretty_json(&content),
+                });
+            }
+        }
+        self.snapshots = snapshots;
+        select_within(&mut self.snapshot_list, self.snapshots.len());
+    }
+
+    fn load_embodiment(&mut self) {
+        // In single-sparkler mode there is only the default sparkler
+        let sparkler = self
+            .config
+            .is_multi_sparkler()
+            .then(|| self.selected_sparkler().map(|s| s.name.clone()))
+            .flatten();
+        let params = FullEmbodimentParams {
+            mode: self.mode.clone(),
+            workspace_path: Some(self.workspace_path()),
+            sparkler,
+        };
+        self.embodiment =
+            Some(render_embodiment(params, &RenderOptions::default()).map_err(|e| e.to_string()));
+        let len = self.sections_len();
+        select_within(&mut self.section_list, len);
+    }
+
+    fn sections_len(&self) -> usize {
+        match &self.embodiment {
+            Some(Ok(embodiment)) => embodiment.sections.len(),
+            _ => 0,
+        }
+    }
+
+    async fn handle_key(&mut self, code: KeyCode) {
+        if let Some(id) = self.confirm_retract.take() {
+            if matches!(code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.retract(id).await;
+            } else {
+                self.status = "Retraction cancelled".to_string();
+            }
+            return;
+        }
+        self.status.clear();
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab => self.switch_tab((self.tab.index() + 1) % Tab::ALL.len()),
+            KeyCode::BackTab => {
+                self.switch_tab((self.tab.index() + Tab::ALL.len() - 1) % Tab::ALL.len())
+            }
+            KeyCode::Char(c @ '1'..='4') => self.switch_tab(c as usize - '1' as usize),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::Char('r') if self.tab == Tab::Insights => {
+                let selected = self
+                    .insight_list
+                    .selected()
+                    .and_then(|index| self.insights.get(index));
+                match selected {
+                    Some(record) if record.is_active() => {
+                        self.status = format!("Retract insight {}? (y/n)", record.id);
+                        self.confirm_retract = Some(record.id.clone());
+                    }
+                    Some(record) => {
+                        self.status =
+                            format!("Insight {} is already {:?}", record.id, record.status)
+                    }
+                    None => {}
+                }
+            }
+            KeyCode::Char('a') if self.tab == Tab::Insights => {
+                self.show_inactive = !self.show_inactive;
+                self.load_insights();
+            }
+            KeyCode::Char('c') if self.tab == Tab::WorkingMemory => {
+                self.compare_with_current = !self.compare_with_current;
+                self.scroll = 0;
+            }
+            KeyCode::Char('m') if self.tab == Tab::Embodiment => {
+                // Cycle: the sparkler's setting, then each mode
+                self.mode = match &self.mode {
+                    None => Some(EMBODIMENT_MODES[0].to_string()),
+                    Some(mode) => EMBODIMENT_MODES
+                        .iter()
+                        .position(|m| m == mode)
+                        .and_then(|i| EMBODIMENT_MODES.get(i + 1))
+                        .map(|m| m.to_string()),
+                };
+                self.load_embodiment();
+            }
+            _ => {}
+        }
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        self.tab = Tab::ALL[index];
+        self.scroll = 0;
+        if self.tab == Tab::Embodiment && self.embodiment.is_none() {
+            self.load_embodiment();
+        }
+    }
+
+    fn move_selection(&mut self, delta: i32) {
+        let sections = self.sections_len();
+        let (state, len) = match self.tab {
+            Tab::Sparklers => (&mut self.sparkler_list, self.sparklers.len()),
+            Tab::Insights => (&mut self.insight_list, self.insights.len()),
+            Tab::WorkingMemory => (&mut self.snapshot_list, self.snapshots.len()),
+            Tab::Embodiment => (&mut self.section_list, sections),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as i32;
+        state.select(Some((current + delta).clamp(0, len as i32 - 1) as usize));
+        self.scroll = 0;
+        if self.tab == Tab::Sparklers {
+            // The preview follows the selected sparkler
+            self.embodiment = None;
+        }
+    }
+
+    async fn retract(&mut self, id: String) {
+        let params = tools::retract_insight::RetractInsightParams {
+            id: id.clone(),
+            reason: Some("Retracted in sparkle-mcp tui".to_string()),
+            workspace_path: Some(self.workspace_path()),
+        };
+        self.status = match tools::retract_insight::retract_insight(Parameters(params)).await {
+            Ok(result) if result.is_error != Some(true) => format!("Retracted insight {}", id),
+            Ok(result) => result
+                .content
+                .iter()
+                .filter_map(|content| match &content.raw {
+                    RawContent::Text(text) => Some(text.text.clone()),
+                    _ => None,
+                })
+                .collect(),
+            Err(e) => format!("Failed to retract {}: {}", id, e.message),
+        };
+        self.load_insights();
+        self.embodiment = None;
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, main_area, status_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [list_area, detail_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main_area);
+
+        let tabs = Tabs::new(TAB_TITLES)
+            .select(self.tab.index())
+            .highlight_style(Style::new().bold().reversed())
+            .block(Block::bordered().title(format!(" Sparkle - {} ", self.workspace.display())));
+        frame.render_widget(tabs, tabs_area);
+
+        let (title, detail) = match self.tab {
+            Tab::Sparklers => self.draw_sparklers(frame, list_area),
+            Tab::Insights => self.draw_insights(frame, list_area),
+            Tab::WorkingMemory => self.draw_snapshots(frame, list_area),
+            Tab::Embodiment => self.draw_embodiment(frame, list_area),
+        };
+        let detail = Paragraph::new(detail)
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(detail, detail_area);
+
+        let hints = match self.tab {
+            Tab::Sparklers => "",
+            Tab::Insights => "r retract  a show/hide inactive  ",
+            Tab::WorkingMemory => "c compare with previous/current  ",
+            Tab::Embodiment => "m mode  ",
+        };
+        let status = if self.status.is_empty() {
+            format!(
+                "↑/↓ select  PgUp/PgDn scroll  Tab/1-4 switch  {}q quit",
+                hints
+            )
+        } else {
+            self.status.clone()
+        };
+        frame.render_widget(Line::from(status).dim(), status_area);
+    }
+
+    fn draw_sparklers(&mut self, frame: &mut Frame, area: Rect) -> (String, String) {
+        let items: Vec<ListItem> = self
+            .sparklers
+            .iter()
+            .map(|s| {
+                let marker = if s.default { " (default)" } else { "" };
+                ListItem::new(format!("{}{}", s.name, marker))
+            })
+            .collect();
+        render_list(frame, area, " Sparklers ", items, &mut self.sparkler_list);
+
+        let Some(sparkler) = self.selected_sparkler() else {
+            return (" Sparkler ".to_string(), String::new());
+        };
+        let mut detail = format!("Directory: {}\n", sparkler.dir.display());
+        let settings = toml::to_string_pretty(&sparkler.settings).unwrap_or_default();
+        if !settings.trim().is_empty() {
+            detail.push_str(&format!("\nSettings:\n{}\n", settings));
+        }
+        let identity = fs::read_to_string(sparkler.dir.join("sparkler-identity.md"))
+            .unwrap_or_else(|_| "*No sparkler-identity.md*".to_string());
+        detail.push_str(&format!("\n{}", identity));
+        (format!(" {} ", sparkler.name), detail)
+    }
+
+    fn draw_insights(&mut self, frame: &mut Frame, area: Rect) -> (String, String) {
+        let items: Vec<ListItem> = self
+            .insights
+            .iter()
+            .map(|record| {
+                let first_line = record.content.lines().next().unwrap_or_default();
+                let item = ListItem::new(format!(
+                    "{} {}",
+                    record.timestamp.format("%Y-%m-%d"),
+                    first_line
+                ));
+                if record.is_active() { item } else { item.dim() }
+            })
+            .collect();
+        let title = if self.show_inactive {
+            " Insights (all) "
+        } else {
+            " Insights "
+        };
+        render_list(frame, area, title, items, &mut self.insight_list);
+
+        let Some(record) = self
+            .insight_list
+            .selected()
+            .and_then(|index| self.insights.get(index))
+        else {
+            return (" Insight ".to_string(), "No insights saved yet".to_string());
+        };
+        let mut detail = format!(
+            "Id: {}\nType: {}\nSaved: {}\nStatus: {:?}\n",
+            record.id,
+            record.insight_type.title(),
+            self.config
+                .human
+                .format_time(record.timestamp, "%Y-%m-%d %H:%M %Z"),
+            record.status
+        );
+        if let Some(sparkler) = &record.sparkler {
+            detail.push_str(&format!("Sparkler: {}\n", sparkler));
+        }
+        if let Some(workspace) = &record.workspace {
+            detail.push_str(&format!("Workspace: {}\n", workspace));
+        }
+        if !record.tags.is_empty() {
+            detail.push_str(&format!("Tags: {}\n", record.tags.join(", ")));
+        }
+        if let Some(reason) = &record.retraction_reason {
+            detail.push_str(&format!("Retracted: {}\n", reason));
+        }
+        detail.push_str(&format!("\n{}\n", record.content));
+        if let Some(context) = &record.context {
+            detail.push_str(&format!("\nContext: {}\n", context));
+        }
+        (format!(" {} ", record.insight_type.title()), detail)
+    }
+
+    fn draw_snapshots(&mut self, frame: &mut Frame, area: Rect) -> (String, String) {
+        let items: Vec<ListItem> = self
+            .snapshots
+            .iter()
+            .map(|snapshot| ListItem::new(snapshot.label.clone()))
+            .collect();
+        render_list(frame, area, " Snapshots ", items, &mut self.snapshot_list);
+
+        let Some(index) = self.snapshot_list.selected() else {
+            return (
+                " Working memory ".to_string(),
+                "No working memory in this workspace yet".to_string(),
+            );
+        };
+        let snapshot = &self.snapshots[index];
+        let (base, title) = if self.compare_with_current {
+            (self.snapshots.first(), " Changes up to current ")
+        } else {
+            (self.snapshots.get(index + 1), " Changes since previous ")
+        };
+        match base {
+            Some(base) if !std::ptr::eq(base, snapshot) => {
+                // Snapshots are newest first, so the base is older unless comparing with current
+                let diff = if self.compare_with_current {
+                    unified_diff(&snapshot.content, &base.content, "working-memory.json")
+                } else {
+                    unified_diff(&base.content, &snapshot.content, "working-memory.json")
+                };
+                let diff = if diff.is_empty() {
+                    "No changes".to_string()
+                } else {
+                    diff
+                };
+                (title.to_string(), diff)
+            }
+            _ => (format!(" {} ", snapshot.label), snapshot.content.clone()),
+        }
+    }
+
+    fn draw_embodiment(&mut self, frame: &mut Frame, area: Rect) -> (String, String) {
+        let embodiment = match &self.embodiment {
+            Some(Ok(embodiment)) => embodiment,
+            Some(Err(e)) => {
+                render_list(
+                    frame,
+                    area,
+                    " Sections ",
+                    Vec::new(),
+                    &mut self.section_list,
+                );
+                return (
+                    " Embodiment ".to_string(),
+                    format!("Failed to render: {}", e),
+                );
+            }
+            None => return (" Embodiment ".to_string(), String::new()),
+        };
+        let total = embodiment.total_bytes();
+        let items: Vec<ListItem> = embodiment
+            .sections
+            .iter()
+            .map(|section| ListItem::new(format!("{:<24} {:>7}", section.name, section.bytes)))
+            .collect();
+        let title = format!(
+            " {} / {} - {} bytes ",
+            embodiment.sparkler.as_deref().unwrap_or("default"),
+            embodiment.mode,
+            total
+        );
+        let section = self
+            .section_list
+            .selected()
+            .and_then(|index| embodiment.sections.get(index));
+        let detail = match section {
+            Some(section) => {
+                let source = section
+                    .source
+                    .as_ref()
+                    .map(|path| format!("Source: {}\n\n", path.display()))
+                    .unwrap_or_default();
+                (
+                    format!(" {} ", section.name),
+                    format!("{}{}", source, section.content),
+                )
+            }
+            None => (" Embodiment ".to_string(), String::new()),
+        };
+        render_list(frame, area, &title, items, &mut self.section_list);
+        detail
+    }
+}
+
+fn render_list(
+    frame: &mut Frame,
+    area: Rect,
+    title: &str,
+    items: Vec<ListItem>,
+    state: &mut ListState,
+) {
+    let list = List::new(items)
+        .block(Block::bordered().title(title.to_string()))
+        .highlight_style(Style::new().reversed())
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, area, state);
+}
+
+/// Keep a selection inside a list of `len` items, selecting the first if there was none
+fn select_within(state: &mut ListState, len: usize) {
+    state.select(match state.selected() {
+        _ if len == 0 => None,
+        Some(index) => Some(index.min(len - 1)),
+        None => Some(0),
+    });
+}
+
+/// Pretty-print JSON so snapshots diff line by line; anything else is shown as is
+fn pretty_json(content: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(content)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .map(|pretty| pretty + "\n")
+        .unwrap_or_else(|_| content.to_string())
+}
@@ -1,5 +1,9 @@
+use crate::includes::{Include, IncludesConfig};
+use chrono::{DateTime, Local, Utc};
+use chrono_tz::Tz;
 use rmcp::schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // Config structures for multi-sparkler support
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -9,16 +13,95 @@ pub struct Config {
     pub ai: Option<AiConfig>, // Legacy single-sparkler
     #[serde(default)]
     pub sparklers: Option<Vec<SparklerConfig>>, // New multi-sparkler
+    #[serde(default)]
+    pub http: Option<HttpConfig>,
+    #[serde(default)]
+    pub history: Option<HistoryConfig>,
+    #[serde(default)]
+    pub sync: Option<SyncConfig>,
+    #[serde(default)]
+    pub templates: Option<TemplatesConfig>,
+    #[serde(default)]
+    pub includes: Option<IncludesConfig>,
+}
+
+/// Settings of one workspace, in its `.sparkle-space/config.toml`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct WorkspaceConfig {
+    /// Set to false to turn Sparkle off in this workspace
+    pub enabled: bool,
+    /// Sparkler embodied here unless another one is requested explicitly
+    pub sparkler: Option<String>,
+    pub checkpoints: Option<CheckpointsConfig>,
+    pub includes: Option<IncludesConfig>,
+}
+
+impl Default for WorkspaceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sparkler: None,
+            checkpoints: None,
+            includes: None,
+        }
+    }
+}
+
+/// Checkpoint retention (`[checkpoints]` in `.sparkle-space/config.toml`)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CheckpointsConfig {
+    /// Checkpoints kept on disk; older ones are deleted when a new one is written
+    pub keep: Option<usize>,
+    /// Most recent checkpoints loaded at embodiment (overrides the sparkler's `checkpoint_count`)
+    pub load: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HumanConfig {
     pub name: String,
+    /// Optional: How to refer to the human, e.g. "she/her"
+    #[serde(default)]
+    pub pronouns: Option<String>,
+    /// Optional: IANA time zone, e.g. "Europe/Berlin" (defaults to the system's local time)
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Optional: Preferred language for conversation
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub team: Option<String>,
+    /// Free-form working preferences (`[human.preferences]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub preferences: BTreeMap<String, toml::Value>,
+}
+
+/// Format of timestamps shown to the human, in their time zone
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S %Z";
+
+impl HumanConfig {
+    /// The configured time zone, if set and valid
+    pub fn time_zone(&self) -> Option<Tz> {
+        self.timezone.as_deref()?.trim().parse().ok()
+    }
+
+    /// Format a timestamp in the human's time zone (or the system's local time)
+    pub fn format_time(&self, timestamp: DateTime<Utc>, format: &str) -> String {
+        match self.time_zone() {
+            Some(tz) => timestamp.with_timezone(&tz).format(format).to_string(),
+            None => timestamp.with_timezone(&Local).format(format).to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AiConfig {
     pub name: String,
+    #[serde(flatten)]
+    pub settings: SparklerSettings,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -26,6 +109,140 @@ pub struct SparklerConfig {
     pub name: String,
     #[serde(default)]
     pub default: bool,
+    #[serde(flatten)]
+    pub settings: SparklerSettings,
+}
+
+/// Per-sparkler settings, set in its `[[sparklers]]` entry (or `[ai]` in single-sparkler mode)
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SparklerSettings {
+    /// Tools exposed while this sparkler is active (all tools when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+    /// Tools hidden while this sparkler is active
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disabled_tools: Vec<String>,
+    /// Prompts exposed while this sparkler is active (all prompts when unset)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<Vec<String>>,
+    /// Prompts hidden while this sparkler is active
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disabled_prompts: Vec<String>,
+    /// Embodiment mode used when none is requested ("complete" or "distilled")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embodiment_mode: Option<String>,
+    /// How many of the most recent workspace checkpoints embodiment loads (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_count: Option<usize>,
+    /// Extra files loaded at embodiment, relative to the sparkler's directory
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub context_files: Vec<String>,
+    /// Extra files with sorting and size caps (`[[sparklers.includes]]`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub includes: Vec<Include>,
+}
+
+impl SparklerSettings {
+    /// Whether the tool called `name` is exposed
+    pub fn tool_enabled(&self, name: &str) -> bool {
+        is_enabled(&self.tools, &self.disabled_tools, name)
+    }
+
+    /// Whether the prompt called `name` is exposed
+    pub fn prompt_enabled(&self, name: &str) -> bool {
+        is_enabled(&self.prompts, &self.disabled_prompts, name)
+    }
+}
+
+fn is_enabled(allowed: &Option<Vec<String>>, disabled: &[String], name: &str) -> bool {
+    allowed
+        .as_ref()
+        .is_none_or(|allowed| allowed.iter().any(|n| n == name))
+        && !disabled.iter().any(|n| n == name)
+}
+
+/// Settings for fetching profile data over HTTP (`[http]` in config.toml)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// Total time allowed for one request
+    pub timeout_secs: u64,
+    /// Time allowed to establish a connection
+    pub connect_timeout_secs: u64,
+    /// Proxy URL for all requests (the standard `HTTPS_PROXY` variables apply otherwise)
+    pub proxy: Option<String>,
+    /// Largest response body that will be downloaded
+    pub max_body_bytes: usize,
+    /// Retries after connection failures, timeouts and server errors
+    pub retries: u32,
+    /// Keep responses with an ETag in `~/.sparkle/cache/http` and revalidate them
+    pub cache: bool,
+    /// Base URL of the GitHub REST API
+    pub github_api_url: String,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            proxy: None,
+            max_body_bytes: 2 * 1024 * 1024,
+            retries: 2,
+            cache: true,
+            github_api_url: "https://api.github.com".to_string(),
+        }
+    }
+}
+
+/// Retention for versions of context files (`[history]` in config.toml)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Versions kept per file; older ones are pruned
+    pub max_versions: usize,
+    /// Optional: Also prune versions older than this many days
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_versions: 20,
+            max_age_days: None,
+        }
+    }
+}
+
+/// Git-backed sync of `~/.sparkle` (`[sync]` in config.toml)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SyncConfig {
+    /// Commit every change made through the tools
+    pub enabled: bool,
+    /// Git remote that `sparkle-mcp sync` pulls from and pushes to
+    pub remote: Option<String>,
+    /// Branch to sync
+    pub branch: String,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            remote: None,
+            branch: "main".to_string(),
+        }
+    }
+}
+
+/// Where sparkler templates are found (`[templates]` in config.toml)
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TemplatesConfig {
+    /// Optional: Team-shared template directory, searched after `~/.sparkle/templates/`
+    pub team_dir: Option<String>,
 }
 
 impl Config {
@@ -51,19 +268,40 @@ impl Config {
     }
 
     /// Get all sparkler names (for multi-sparkler mode)
-    #[allow(dead_code)]
     pub fn get_all_sparkler_names(&self) -> Vec<String> {
         self.sparklers
             .as_ref()
             .map(|sparklers| sparklers.iter().map(|s| s.name.clone()).collect())
             .unwrap_or_default()
     }
+
+    /// Settings of a sparkler (the default sparkler when no name is given)
+    pub fn sparkler_settings(&self, name: Option<&str>) -> SparklerSettings {
+        match &self.sparklers {
+            Some(sparklers) => {
+                let name = name
+                    .map(String::from)
+                    .or_else(|| self.get_default_sparkler_name());
+                sparklers
+                    .iter()
+                    .find(|s| Some(&s.name) == name.as_ref())
+                    .map(|s| s.settings.clone())
+                    .unwrap_or_default()
+            }
+            None => self
+                .ai
+                .as_ref()
+                .map(|ai| ai.settings.clone())
+                .unwrap_or_default(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct FullEmbodimentParams {
+    /// Optional: "complete" (default) or "distilled" (skips the collaboration evolution history)
     #[serde(default)]
-    pub mode: Option<String>, // "distilled", "deep", "workspace"
+    pub mode: Option<String>,
     #[serde(default)]
     pub workspace_path: Option<String>,
     #[serde(default)]
@@ -99,14 +337,40 @@ pub struct SaveInsightParams {
     /// Optional: Which sparkler is saving this insight (for multi-sparkler mode)
     #[serde(default)]
     pub sparkler: Option<String>,
+    /// Optional: Workspace the insight emerged in (current working directory).
+    /// Workspace insights are stored in that workspace's .sparkle-space and only loaded
+    /// there; without it they are saved as cross-workspace notes.
+    #[serde(default)]
+    pub workspace_path: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum)]
 pub enum InsightType {
     /// Pattern anchor - exact words that recreate collaborative patterns
     PatternAnchor,
     /// Collaboration evolution - breakthrough insights about how we work together
     CollaborationEvolution,
-    /// Workspace insight - top-level workspace information and cross-project connections
+    /// Workspace insight - information about the workspace given in workspace_path,
+    /// or a cross-project connection when no workspace is given
     WorkspaceInsight,
 }
+
+impl InsightType {
+    /// Human-readable title used in markdown entries
+    pub fn title(&self) -> &'static str {
+        match self {
+            InsightType::PatternAnchor => "Pattern Anchor",
+            InsightType::CollaborationEvolution => "Collaboration Evolution",
+            InsightType::WorkspaceInsight => "Workspace Insight",
+        }
+    }
+
+    /// Markdown file the insight is appended to
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            InsightType::PatternAnchor => "pattern-anchors.md",
+            InsightType::CollaborationEvolution => "collaboration-evolution.md",
+            InsightType::WorkspaceInsight => "workspace-map.md",
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! Tests for archiving a sparkler
+//!
+//! Archiving moves the sparkler's directory under `archive/`, keeps its config
+//! entry next to it and hands the default to a remaining sparkler.
+
+use sparkle_mcp::context_loader::archive_sparkler;
+use sparkle_mcp::types::{Config, SparklerConfig};
+use std::fs;
+
+#[test]
+fn test_archive_moves_sparkler_out_of_setup() {
+    let dir = std::env::temp_dir().join(format!("sparkle-archive-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let ops = dir.join("sparklers/Ops");
+    fs::create_dir_all(&ops).unwrap();
+    fs::write(ops.join("pattern-anchors.md"), "# Ops anchors\n").unwrap();
+    fs::write(ops.join("runbook.md"), "Page the on-call.\n").unwrap();
+
+    let mut config: Config = toml::from_str(
+        "[human]\nname = \"Kari\"\n\n[[sparklers]]\nname = \"Ops\"\ndefault = true\ncheckpoint_count = 2\n\n[[sparklers]]\nname = \"Sparkle\"\n",
+    )
+    .unwrap();
+
+    let archive = archive_sparkler(&mut config, &dir, "Ops").unwrap();
+    assert!(archive.starts_with(dir.join("archive")));
+    assert!(!ops.exists());
+    assert_eq!(
+        fs::read_to_string(archive.join("runbook.md")).unwrap(),
+        "Page the on-call.\n"
+    );
+    let entry: SparklerConfig =
+        toml::from_str(&fs::read_to_string(archive.join("sparkler.toml")).unwrap()).unwrap();
+    assert_eq!(entry.name, "Ops");
+    assert_eq!(entry.settings.checkpoint_count, Some(2));
+
+    assert_eq!(config.get_all_sparkler_names(), ["Sparkle"]);
+    assert_eq!(
+        config.get_default_sparkler_name().as_deref(),
+        Some("Sparkle")
+    );
+    let written: Config =
+        toml::from_str(&fs::read_to_string(dir.join("config.toml")).unwrap()).unwrap();
+    assert_eq!(written.get_all_sparkler_names(), ["Sparkle"]);
+
+    // The last sparkler and unknown sparklers stay put
+    let err = archive_sparkler(&mut config, &dir, "Sparkle").unwrap_err();
+    assert!(err.to_string().contains("only sparkler"), "{}", err);
+    assert!(archive_sparkler(&mut config, &dir, "Nobody").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,94 @@
+//! Tests for the blog feed profile source
+//!
+//! Non-English summaries used to panic when truncated mid-character; these
+//! serve feeds from a mock server and check the rendered summary.
+
+use sparkle_mcp::profile_sources::{HttpClient, ProfileSource, ProfileSourceRegistry};
+use std::collections::BTreeMap;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn item(title: &str, category: &str, summary: &str) -> String {
+    format!(
+        "<item><title>{}</title><link>https://example.com/{}</link><category>{}</category>\
+        <description><![CDATA[{}]]></description></item>",
+        title, category, category, summary
+    )
+}
+
+async fn fetch_feed(items: &[String], options: &[(&str, &str)]) -> anyhow::Result<String> {
+    let server = MockServer::start().await;
+    let feed = format!(
+        "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>Blog</title>\
+        <description>&lt;p&gt;Notes&lt;/p&gt;</description>{}</channel></rss>",
+        items.concat()
+    );
+    Mock::given(method("GET"))
+        .and(path("/feed.xml"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "application/rss+xml")
+                .set_body_string(feed),
+        )
+        .mount(&server)
+        .await;
+
+    let source = ProfileSource {
+        source_type: "blog".to_string(),
+        value: format!("{}/feed.xml", server.uri()),
+        options: options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+    };
+    ProfileSourceRegistry::builtin_with(HttpClient::default())
+        .fetch(&source)
+        .await
+}
+
+#[tokio::test]
+async fn test_non_ascii_summaries_are_truncated_safely() {
+    // 'é' and '日' are multi-byte, so byte 200 falls inside a character
+    let summary = format!("<p>{}</p><p>{}</p>", "é".repeat(150), "日本語".repeat(50));
+    let result = fetch_feed(&[item("Café", "rust", &summary)], &[])
+        .await
+        .unwrap();
+
+    assert!(result.contains("- [Café](https://example.com/rust)"));
+    assert!(
+        !result.contains("<p>"),
+        "HTML should be stripped: {}",
+        result
+    );
+    assert!(result.contains("Notes\n"));
+    let line = result
+        .lines()
+        .find(|line| line.trim_start().starts_with('é'))
+        .unwrap();
+    assert_eq!(line.trim().trim_end_matches("...").chars().count(), 200);
+}
+
+#[tokio::test]
+async fn test_entry_count_and_topics() {
+    let items = [
+        item(
+            "Async Rust patterns",
+            "rust",
+            "Exploring <b>async</b> executors",
+        ),
+        item("Compiler notes", "compilers", "Parsing and async lowering"),
+        item("More Rust", "rust", "Borrow checker stories"),
+    ];
+    let result = fetch_feed(&items, &[("max_entries", "2"), ("topics", "true")])
+        .await
+        .unwrap();
+
+    assert!(
+        result.contains("**Writes about:** compilers, rust, async"),
+        "{}",
+        result
+    );
+    assert!(result.contains("Async Rust patterns"));
+    assert!(result.contains("Compiler notes"));
+    assert!(!result.contains("More Rust"));
+}
@@ -0,0 +1,235 @@
+//! Tests for sparkler export/import bundles
+//!
+//! A sparkler exported from one sparkle directory is imported into another,
+//! which gets converted to multi-sparkler mode; name clashes fail, rename or
+//! merge as requested.
+
+use chrono::{TimeZone, Utc};
+use sparkle_mcp::bundle::{
+    ImportOptions, OnConflict, export_sparkler, import_sparkler, read_bundle,
+};
+use sparkle_mcp::insights::{InsightRecord, InsightStatus, load_records, write_records};
+use sparkle_mcp::types::{Config, InsightType};
+use std::fs;
+use std::path::PathBuf;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sparkle-bundle-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn anchor(id: &str, day: u32, content: &str) -> InsightRecord {
+    InsightRecord {
+        id: id.to_string(),
+        insight_type: InsightType::PatternAnchor,
+        timestamp: Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap(),
+        sparkler: Some("Reviewer".to_string()),
+        workspace: None,
+        tags: vec!["review".to_string()],
+        content: content.to_string(),
+        context: Some("Code review".to_string()),
+        status: InsightStatus::Active,
+        supersedes: None,
+        superseded_by: None,
+        retraction_reason: None,
+    }
+}
+
+const ANCHORS: &str = "# Pattern Anchors\n\n## Pattern Anchors\n\n\"Check the tests first.\"\n";
+
+#[test]
+fn test_export_and_import_between_setups() {
+    let root = scratch("roundtrip");
+    let team = root.join("team");
+    let mine = root.join("mine");
+    let archive = root.join("Reviewer.sparkle.tar.gz");
+
+    // A multi-sparkler setup with a tuned reviewer
+    let reviewer = team.join("sparklers/Reviewer");
+    fs::create_dir_all(&reviewer).unwrap();
+    fs::write(reviewer.join("sparkler-identity.md"), "# Reviewer\n").unwrap();
+    fs::write(reviewer.join("pattern-anchors.md"), ANCHORS).unwrap();
+    fs::write(team.join("collaborator-profile.md"), "# Team lead\n").unwrap();
+    let team_config: Config = toml::from_str(
+        "[human]\nname = \"Lead\"\n\n[[sparklers]]\nname = \"Sparkle\"\ndefault = true\n\n[[sparklers]]\nname = \"Reviewer\"\n",
+    )
+    .unwrap();
+
+    let manifest = export_sparkler(&team, &team_config, Some("Reviewer"), false, &archive).unwrap();
+    assert_eq!(manifest.sparkler.name, "Reviewer");
+    assert_eq!(
+        manifest.files,
+        ["pattern-anchors.md", "sparkler-identity.md"]
+    );
+    let bundle = read_bundle(&archive).unwrap();
+    assert_eq!(bundle.manifest.format_version, 1);
+    assert!(bundle.collaborator_profile.is_none());
+
+    // Importing into a single-sparkler setup migrates it and registers the sparkler
+    fs::create_dir_all(&mine).unwrap();
+    fs::write(mine.join("pattern-anchors.md"), "# Mine\n").unwrap();
+    let mut config: Config =
+        toml::from_str("[human]\nname = \"Me\"\n\n[ai]\nname = \"Nova\"\n").unwrap();
+    let report = import_sparkler(&mine, &mut config, &archive, &ImportOptions::default()).unwrap();
+    assert_eq!(report.name, "Reviewer");
+    assert_eq!(report.migrated.as_deref(), Some("Nova"));
+    assert_eq!(
+        fs::read_to_string(mine.join("sparklers/Nova/pattern-anchors.md")).unwrap(),
+        "# Mine\n"
+    );
+    assert_eq!(
+        fs::read_to_string(mine.join("sparklers/Reviewer/sparkler-identity.md")).unwrap(),
+        "# Reviewer\n"
+    );
+    let written: Config =
+        toml::from_str(&fs::read_to_string(mine.join("config.toml")).unwrap()).unwrap();
+    assert_eq!(written.get_all_sparkler_names(), ["Nova", "Reviewer"]);
+    assert_eq!(written.get_default_sparkler_name().as_deref(), Some("Nova"));
+
+    // A second import clashes unless renamed or merged
+    let err = import_sparkler(&mine, &mut config, &archive, &ImportOptions::default()).unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{}", err);
+    let renamed = ImportOptions {
+        on_conflict: OnConflict::Rename,
+        ..Default::default()
+    };
+    let report = import_sparkler(&mine, &mut config, &archive, &renamed).unwrap();
+    assert_eq!(report.name, "Reviewer-2");
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_merge_keeps_local_entries() {
+    let root = scratch("merge");
+    let team = root.join("team");
+    let mine = root.join("mine");
+    let archive = root.join("Reviewer.sparkle.tar.gz");
+    let config_toml =
+        "[human]\nname = \"Me\"\n\n[[sparklers]]\nname = \"Reviewer\"\ndefault = true\n";
+
+    let theirs = team.join("sparklers/Reviewer");
+    fs::create_dir_all(&theirs).unwrap();
+    fs::write(
+        theirs.join("pattern-anchors.md"),
+        format!(
+            "{}\n\"Name the invariant.\"\n\n## Review Habits\n\nRead the diff twice.\n",
+            ANCHORS
+        ),
+    )
+    .unwrap();
+    fs::write(theirs.join("sparkler-identity.md"), "# Reviewer v2\n").unwrap();
+    fs::write(team.join("collaborator-profile.md"), "# Team lead\n").unwrap();
+    let team_config: Config = toml::from_str(config_toml).unwrap();
+    export_sparkler(&team, &team_config, None, true, &archive).unwrap();
+
+    let local = mine.join("sparklers/Reviewer");
+    fs::create_dir_all(&local).unwrap();
+    fs::write(
+        local.join("pattern-anchors.md"),
+        format!("{}\n\"Ask before refactoring.\"\n", ANCHORS),
+    )
+    .unwrap();
+    fs::write(local.join("sparkler-identity.md"), "# Reviewer v1\n").unwrap();
+    let mut config: Config = toml::from_str(config_toml).unwrap();
+
+    let options = ImportOptions {
+        on_conflict: OnConflict::Merge,
+        ..Default::default()
+    };
+    let report = import_sparkler(&mine, &mut config, &archive, &options).unwrap();
+    assert!(report.merged);
+    assert!(!report.profile_imported);
+    assert!(!mine.join("collaborator-profile.md").exists());
+
+    let anchors = fs::read_to_string(local.join("pattern-anchors.md")).unwrap();
+    assert_eq!(anchors.matches("Check the tests first.").count(), 1);
+    assert!(anchors.contains("Ask before refactoring."));
+    assert!(anchors.contains("Name the invariant."));
+    assert!(anchors.contains("## Review Habits\n\nRead the diff twice."));
+    assert_eq!(
+        fs::read_to_string(local.join("sparkler-identity.md")).unwrap(),
+        "# Reviewer v2\n"
+    );
+    assert!(
+        mine.join(".history/sparklers/Reviewer/sparkler-identity.md")
+            .exists()
+    );
+    assert_eq!(config.get_all_sparkler_names(), ["Reviewer"]);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_merge_saved_insights() {
+    let root = scratch("merge-insights");
+    let team = root.join("team");
+    let mine = root.join("mine");
+    let archive = root.join("Reviewer.sparkle.tar.gz");
+    let config_toml =
+        "[human]\nname = \"Me\"\n\n[[sparklers]]\nname = \"Reviewer\"\ndefault = true\n";
+    let mut config: Config = toml::from_str(config_toml).unwrap();
+
+    // Both sides saved the shared anchor; the team retracted it since and
+    // saved another one, while a local one was saved here
+    let shared = anchor("20250601-120000-0001", 1, "Check the tests first.");
+    let retracted = InsightRecord {
+        status: InsightStatus::Retracted,
+        retraction_reason: Some("Outdated".to_string()),
+        ..shared.clone()
+    };
+    let theirs = anchor("20250602-120000-0002", 2, "Name the invariant.");
+    let ours = anchor("20250603-120000-0003", 3, "Ask before refactoring.");
+
+    let team_dir = team.join("sparklers/Reviewer");
+    fs::create_dir_all(&team_dir).unwrap();
+    fs::write(
+        team_dir.join("pattern-anchors.md"),
+        format!(
+            "# Pattern Anchors\n{}{}",
+            shared.to_markdown(&config.human),
+            theirs.to_markdown(&config.human)
+        ),
+    )
+    .unwrap();
+    write_records(&team_dir, &[retracted, theirs.clone()]).unwrap();
+    export_sparkler(&team, &config, None, false, &archive).unwrap();
+
+    let local = mine.join("sparklers/Reviewer");
+    fs::create_dir_all(&local).unwrap();
+    let local_anchors = format!(
+        "# Pattern Anchors\n{}{}",
+        shared.to_markdown(&config.human),
+        ours.to_markdown(&config.human)
+    );
+    fs::write(local.join("pattern-anchors.md"), &local_anchors).unwrap();
+    write_records(&local, &[shared.clone(), ours.clone()]).unwrap();
+
+    let options = ImportOptions {
+        on_conflict: OnConflict::Merge,
+        ..Default::default()
+    };
+    import_sparkler(&mine, &mut config, &archive, &options).unwrap();
+
+    // The new entry is appended whole, separators and metadata included
+    assert_eq!(
+        fs::read_to_string(local.join("pattern-anchors.md")).unwrap(),
+        format!("{}{}", local_anchors, theirs.to_markdown(&config.human))
+    );
+
+    let records = load_records(&local);
+    let status: Vec<(&str, InsightStatus)> =
+        records.iter().map(|r| (r.id.as_str(), r.status)).collect();
+    assert_eq!(
+        status,
+        [
+            (shared.id.as_str(), InsightStatus::Retracted),
+            (ours.id.as_str(), InsightStatus::Active),
+            (theirs.id.as_str(), InsightStatus::Active),
+        ]
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,52 @@
+//! Tests for embodiment without a workspace_path
+//!
+//! The server's working directory is the workspace, so its working memory
+//! and workspace insights are loaded just as if the path had been passed.
+
+use sparkle_mcp::embodiment::generate_embodiment_content;
+use sparkle_mcp::types::FullEmbodimentParams;
+use std::fs;
+
+#[test]
+fn test_embodiment_defaults_to_current_directory() {
+    // Embodiment reads HOME and the current directory; this is the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-current-ws-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let sparkle_dir = home.join(".sparkle");
+    fs::create_dir_all(&sparkle_dir).unwrap();
+    fs::write(
+        sparkle_dir.join("config.toml"),
+        "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n",
+    )
+    .unwrap();
+
+    let workspace = home.join("project");
+    let sparkle_space = workspace.join(".sparkle-space");
+    fs::create_dir_all(&sparkle_space).unwrap();
+    fs::write(
+        sparkle_space.join("working-memory.json"),
+        "{\"currentFocus\": \"defaults\"}",
+    )
+    .unwrap();
+    fs::write(
+        sparkle_space.join("workspace-map.md"),
+        "# Workspace Map\n\nReleases are cut from the stable branch\n",
+    )
+    .unwrap();
+
+    unsafe { std::env::set_var("HOME", &home) };
+    std::env::set_current_dir(&workspace).unwrap();
+
+    for workspace_path in [None, Some("current".to_string())] {
+        let content = generate_embodiment_content(FullEmbodimentParams {
+            mode: None,
+            workspace_path,
+            sparkler: None,
+        })
+        .unwrap();
+        assert!(content.contains("\"currentFocus\": \"defaults\""));
+        assert!(content.contains("Releases are cut from the stable branch"));
+    }
+
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,182 @@
+//! Tests for `sparkle-mcp doctor`
+//!
+//! Each test builds a small sparkle directory with one kind of damage and
+//! checks that `diagnose` reports it, and that `apply_fixes` repairs what it
+//! claims to.
+
+use sparkle_mcp::doctor::{AutoFix, Severity, apply_fixes, diagnose};
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG: &str =
+    "[human]\nname = \"Kari\"\n\n[[sparklers]]\nname = \"Sparkle\"\ndefault = true\n";
+
+fn sparkle_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sparkle-doctor-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("sparklers/Sparkle")).unwrap();
+    fs::write(dir.join("config.toml"), CONFIG).unwrap();
+    fs::write(dir.join("collaborator-profile.md"), "# Kari\n").unwrap();
+    fs::write(
+        dir.join("sparklers/Sparkle/sparkler-identity.md"),
+        "# Sparkle\n\nCurious and direct.\n",
+    )
+    .unwrap();
+    dir
+}
+
+fn checks(dir: &std::path::Path, workspace: Option<&std::path::Path>) -> Vec<&'static str> {
+    diagnose(dir, workspace)
+        .findings
+        .iter()
+        .map(|f| f.check)
+        .collect()
+}
+
+#[test]
+fn test_healthy_setup_has_no_findings() {
+    let dir = sparkle_dir("healthy");
+    let report = diagnose(&dir, None);
+    assert!(report.findings.is_empty(), "{:?}", report.findings);
+    assert!(report.to_markdown().contains("no problems found"));
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unparseable_config_is_an_error() {
+    let dir = sparkle_dir("bad-config");
+    fs::write(dir.join("config.toml"), "[human\nname = \"Kari\"\n").unwrap();
+
+    let report = diagnose(&dir, None);
+    assert!(report.has_errors());
+    assert_eq!(report.findings[0].check, "config");
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_missing_sparkler_dir_is_recreated() {
+    let dir = sparkle_dir("missing-dir");
+    fs::write(
+        dir.join("config.toml"),
+        format!("{}\n[[sparklers]]\nname = \"Ops\"\n", CONFIG),
+    )
+    .unwrap();
+
+    let report = diagnose(&dir, None);
+    let fixes: Vec<_> = report
+        .findings
+        .iter()
+        .filter_map(|f| f.auto_fix.clone())
+        .collect();
+    assert_eq!(fixes, vec![AutoFix::CreateSparklerDir("Ops".to_string())]);
+
+    let results = apply_fixes(&dir, &report);
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert!(dir.join("sparklers/Ops/sparkler-identity.md").exists());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unregistered_sparkler_dir_is_added_to_config() {
+    let dir = sparkle_dir("unregistered");
+    fs::create_dir_all(dir.join("sparklers/Stray")).unwrap();
+    fs::write(
+        dir.join("sparklers/Stray/sparkler-identity.md"),
+        "# Stray\n",
+    )
+    .unwrap();
+
+    let report = diagnose(&dir, None);
+    assert_eq!(
+        report.findings[0].auto_fix,
+        Some(AutoFix::RegisterSparkler("Stray".to_string()))
+    );
+    apply_fixes(&dir, &report);
+
+    let config = fs::read_to_string(dir.join("config.toml")).unwrap();
+    assert!(config.contains("name = \"Stray\""));
+    assert!(checks(&dir, None).is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unfilled_identity_template_is_reported() {
+    let dir = sparkle_dir("brief");
+    fs::write(
+        dir.join("sparklers/Sparkle/sparkler-identity.md"),
+        "# Sparkle\n\n*Brief: describe who this sparkler is*\n",
+    )
+    .unwrap();
+
+    assert_eq!(checks(&dir, None), vec!["identity"]);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_oversized_anchors_are_reported() {
+    let dir = sparkle_dir("large");
+    let anchors = "- Notice before acting\n".repeat(4000);
+    fs::write(dir.join("sparklers/Sparkle/pattern-anchors.md"), anchors).unwrap();
+
+    let report = diagnose(&dir, None);
+    assert_eq!(report.findings.len(), 1);
+    assert_eq!(report.findings[0].check, "size");
+    assert_eq!(report.count(Severity::Warning), 1);
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_invalid_working_memory_is_an_error() {
+    let dir = sparkle_dir("working-memory");
+    let workspace = dir.join("workspace");
+    fs::create_dir_all(workspace.join(".sparkle-space")).unwrap();
+    fs::write(
+        workspace.join(".sparkle-space/working-memory.json"),
+        "{\"currentFocus\": ",
+    )
+    .unwrap();
+
+    let report = diagnose(&dir, Some(&workspace));
+    assert!(report.has_errors());
+    assert_eq!(report.findings[0].check, "working memory");
+
+    fs::write(
+        workspace.join(".sparkle-space/working-memory.json"),
+        "{\"currentFocus\": \"doctor\"}",
+    )
+    .unwrap();
+    assert!(checks(&dir, Some(&workspace)).is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_legacy_backups_are_moved_into_history() {
+    let dir = sparkle_dir("backups");
+    let backup = dir.join("collaborator-profile.20250101-101010.md");
+    fs::write(&backup, "# Kari, as she was\n").unwrap();
+
+    let report = diagnose(&dir, None);
+    assert_eq!(
+        report.findings[0].auto_fix,
+        Some(AutoFix::MigrateBackups(dir.join("collaborator-profile.md")))
+    );
+    let results = apply_fixes(&dir, &report);
+    assert!(results.iter().all(|(_, result)| result.is_ok()));
+    assert!(!backup.exists());
+    assert!(dir.join(".history").exists());
+    assert!(checks(&dir, None).is_empty());
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_unreadable_evolution_doc_is_reported() {
+    let dir = sparkle_dir("evolution");
+    fs::create_dir_all(dir.join("evolution")).unwrap();
+    fs::write(dir.join("evolution/notes.md"), "# Notes\n").unwrap();
+    fs::write(dir.join("evolution/garbled.md"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let report = diagnose(&dir, None);
+    assert_eq!(report.findings.len(), 1);
+    assert!(report.findings[0].message.contains("garbled.md"));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,146 @@
+//! Tests for partial failures in `fetch_profile_data`
+//!
+//! Serves one working and one failing source from a mock server and checks
+//! that each source reports its own status, so a failure doesn't hide what
+//! was fetched.
+
+use serde_json::{Value, json};
+use sparkle_mcp::profile_sources::ProfileSource;
+use sparkle_mcp::tools::fetch_profile_data::{FetchProfileDataParams, fetch_profile_data};
+use std::collections::BTreeMap;
+use std::fs;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn source(source_type: &str, value: &str) -> ProfileSource {
+    ProfileSource {
+        source_type: source_type.to_string(),
+        value: value.to_string(),
+        options: BTreeMap::new(),
+    }
+}
+
+fn params(sources: Vec<ProfileSource>, content: Option<&str>) -> FetchProfileDataParams {
+    FetchProfileDataParams {
+        profile_sources: Some(sources),
+        content: content.map(str::to_string),
+        working_style: None,
+        collaboration_prefs: None,
+    }
+}
+
+/// The status fields of each fetched source, in request order
+fn statuses(result: &Value) -> Vec<(String, String, String)> {
+    result["fetched_content"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                c["source_type"].as_str().unwrap().to_string(),
+                c["source_value"].as_str().unwrap().to_string(),
+                c["status"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_failed_sources_are_reported_per_source() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/about"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/html")
+                .set_body_string("<html><body><h1>About Kari</h1><p>Writes Rust</p></body></html>"),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/nobody"))
+        .respond_with(ResponseTemplate::new(404).set_body_string("{\"message\": \"Not Found\"}"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/gone"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    // The HTTP client reads its settings from ~/.sparkle/config.toml; this is
+    // the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-fetch-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".sparkle")).unwrap();
+    fs::write(
+        home.join(".sparkle/config.toml"),
+        format!(
+            "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n\n\
+            [http]\nretries = 0\ncache = false\ngithub_api_url = \"{}\"\n",
+            server.uri()
+        ),
+    )
+    .unwrap();
+    unsafe { std::env::set_var("HOME", &home) };
+
+    let about = format!("{}/about", server.uri());
+    let result = fetch_profile_data(params(
+        vec![source("github", "nobody"), source("url", &about)],
+        Some("Prefers small PRs"),
+    ))
+    .await
+    .unwrap();
+    let value = serde_json::to_value(&result).unwrap();
+
+    assert_eq!(value["success"], json!(false));
+    assert_eq!(
+        value["summary"],
+        json!("2 of 3 source(s) fetched; 1 failed")
+    );
+    assert_eq!(
+        statuses(&value),
+        vec![
+            ("github".into(), "nobody".into(), "error".into()),
+            ("url".into(), about.clone(), "ok".into()),
+            ("text".into(), "direct".into(), "ok".into()),
+        ]
+    );
+    let failed = &value["fetched_content"][0];
+    assert_eq!(failed["content"], json!(""));
+    assert_eq!(failed["error"], json!("GitHub user 'nobody' not found"));
+    let fetched = &value["fetched_content"][1];
+    assert!(fetched["content"].as_str().unwrap().contains("Writes Rust"));
+    assert!(fetched.get("error").is_none());
+    assert!(result.prompt.contains("Some sources could not be fetched"));
+    assert!(result.prompt.contains("github nobody"));
+
+    // With nothing fetched, every source still has its own status line
+    let gone = format!("{}/gone", server.uri());
+    let result = fetch_profile_data(params(
+        vec![source("url", &gone), source("rss", "feed")],
+        None,
+    ))
+    .await
+    .unwrap();
+    let value = serde_json::to_value(&result).unwrap();
+    assert_eq!(
+        value["summary"],
+        json!("0 of 2 source(s) fetched; 2 failed")
+    );
+    assert_eq!(
+        statuses(&value),
+        vec![
+            ("url".into(), gone, "error".into()),
+            ("rss".into(), "feed".into(), "error".into()),
+        ]
+    );
+    assert!(
+        value["fetched_content"][1]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Unknown source type: rss")
+    );
+
+    fs::remove_dir_all(&home).unwrap();
+}
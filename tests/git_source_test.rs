@@ -0,0 +1,161 @@
+//! Tests for the git history profile source
+//!
+//! Builds a small repository with fixed authors and dates, so the summary
+//! can be checked offline.
+
+use sparkle_mcp::profile_sources::{ProfileSource, ProfileSourceRegistry};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const AUTHOR: &str = "kari+dev@example.com";
+
+fn git(repo: &Path, args: &[&str]) {
+    assert!(
+        Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args(["-c", "commit.gpgsign=false"])
+            .args(args)
+            .status()
+            .unwrap()
+            .success()
+    );
+}
+
+fn commit(repo: &Path, email: &str, date: &str, file: &str, subject: &str) {
+    let path = repo.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut content = fs::read_to_string(&path).unwrap_or_default();
+    content.push_str(subject);
+    content.push('\n');
+    fs::write(&path, content).unwrap();
+    git(repo, &["add", "-A"]);
+    commit_staged(repo, email, date, subject);
+}
+
+fn commit_staged(repo: &Path, email: &str, date: &str, subject: &str) {
+    assert!(
+        Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args([
+                "-c",
+                "commit.gpgsign=false",
+                "commit",
+                "--quiet",
+                "-m",
+                subject
+            ])
+            .env("GIT_AUTHOR_NAME", "Kari")
+            .env("GIT_AUTHOR_EMAIL", email)
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_NAME", "Kari")
+            .env("GIT_COMMITTER_EMAIL", email)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap()
+            .success()
+    );
+}
+
+/// A repository with four commits by `AUTHOR`, two of them on a weekend, and one by someone else
+fn repo() -> PathBuf {
+    let repo = std::env::temp_dir().join(format!("sparkle-git-source-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&repo);
+    fs::create_dir_all(&repo).unwrap();
+    git(&repo, &["init", "--quiet"]);
+    git(&repo, &["config", "user.email", AUTHOR]);
+
+    commit(
+        &repo,
+        AUTHOR,
+        "2024-03-02T10:15:00+01:00",
+        "src/parser/lexer.rs",
+        "feat(parser): add tokens",
+    );
+    commit(
+        &repo,
+        AUTHOR,
+        "2024-03-03T10:30:00+01:00",
+        "docs/guide.md",
+        "Fix #12 crash",
+    );
+    commit(
+        &repo,
+        "someone@example.com",
+        "2024-03-03T23:00:00+01:00",
+        "lib/other.py",
+        "feat: unrelated work",
+    );
+    commit(
+        &repo,
+        AUTHOR,
+        "2024-03-04T10:45:00+01:00",
+        "README.md",
+        "fix!: drop legacy flag",
+    );
+    git(&repo, &["mv", "src/parser/lexer.rs", "src/parser/scan.rs"]);
+    commit_staged(
+        &repo,
+        AUTHOR,
+        "2024-03-05T10:50:00+01:00",
+        "refactor: rename lexer to scan",
+    );
+
+    repo
+}
+
+async fn fetch(repo: &Path, options: &[(&str, &str)]) -> String {
+    let source = ProfileSource {
+        source_type: "git".to_string(),
+        value: repo.display().to_string(),
+        options: options
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<BTreeMap<_, _>>(),
+    };
+    ProfileSourceRegistry::builtin()
+        .fetch(&source)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_git_history_summary() {
+    let repo = repo();
+
+    // Without an author option the repository's user.email is summarized;
+    // the '+' must not be read as a regex
+    let summary = fetch(&repo, &[]).await;
+    assert!(
+        summary.contains("*4 commit(s) by kari+dev@example.com across 1 local repository*"),
+        "{}",
+        summary
+    );
+    assert!(summary.contains("**Activity:** 2024-03-02 to 2024-03-05"));
+    assert!(summary.contains("Markdown (2 file changes)"));
+
+    // "feat(parser):", "fix!:" and "refactor:" are conventional, "Fix #12" is not
+    assert!(summary.contains("- Conventional commits (`type: ...`): 75%"));
+    assert!(summary.contains("- Capitalized subjects: 25%"));
+    assert!(summary.contains("- Subjects referencing issues/PRs: 25%"));
+    assert!(summary.contains("most commits at 10:00 (author local time); 50% on weekends"));
+
+    // The rename counts towards its directory; top-level files are "(root)"
+    assert!(summary.contains("- src/parser (2 commits)"), "{}", summary);
+    assert!(summary.contains("- docs (1 commits)"));
+    assert!(summary.contains("- (root) (1 commits)"));
+    assert!(!summary.contains("lib"));
+
+    let other = fetch(&repo, &[("author", "someone@example.com")]).await;
+    assert!(other.contains("*1 commit(s) by someone@example.com"));
+    assert!(other.contains("- lib (1 commits)"));
+
+    // A pattern that only matches as a regex finds nothing
+    let regex = fetch(&repo, &[("author", "kari.dev")]).await;
+    assert!(regex.contains("No commits found for this author."));
+
+    fs::remove_dir_all(&repo).unwrap();
+}
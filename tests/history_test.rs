@@ -0,0 +1,108 @@
+//! Tests for context file version history
+//!
+//! Versions live under `.history/` mirroring the file's path, are pruned to
+//! the retention limit, and restoring is itself undoable.
+
+use sparkle_mcp::history::{HISTORY_DIR, History};
+use sparkle_mcp::types::HistoryConfig;
+use std::fs;
+
+fn history(name: &str, max_versions: usize) -> (std::path::PathBuf, History) {
+    let dir = std::env::temp_dir().join(format!("sparkle-history-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("sparklers/Sparkle")).unwrap();
+    let history = History::new(
+        &dir,
+        HistoryConfig {
+            max_versions,
+            max_age_days: None,
+        },
+    );
+    (dir, history)
+}
+
+#[test]
+fn test_versions_are_saved_pruned_and_restored() {
+    let (dir, history) = history("restore", 3);
+    let file = dir.join("sparklers/Sparkle/sparkler-identity.md");
+
+    // Nothing to save before the file exists
+    assert_eq!(history.write(&file, "v1").unwrap(), None);
+    for content in ["v2", "v3", "v4", "v5"] {
+        assert!(history.write(&file, content).unwrap().is_some());
+    }
+    // Unchanged content doesn't add a version
+    assert!(history.save_version(&file).unwrap().is_some());
+    assert!(history.save_version(&file).unwrap().is_none());
+
+    let versions = history.list_versions(&file);
+    assert_eq!(versions.len(), 3);
+    assert!(
+        versions[0].path.starts_with(
+            dir.join(HISTORY_DIR)
+                .join("sparklers/Sparkle/sparkler-identity.md")
+        )
+    );
+    let contents: Vec<String> = versions
+        .iter()
+        .map(|v| fs::read_to_string(&v.path).unwrap())
+        .collect();
+    assert_eq!(contents, ["v5", "v4", "v3"]);
+
+    let v3 = history.find_version(&file, &versions[2].id).unwrap();
+    history.restore_version(&file, &v3).unwrap();
+    assert_eq!(fs::read_to_string(&file).unwrap(), "v3");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_legacy_backups_are_migrated() {
+    let (dir, history) = history("legacy", 20);
+    let file = dir.join("collaborator-profile.md");
+    fs::write(&file, "current").unwrap();
+    fs::write(dir.join("collaborator-profile.20250101-120000.md"), "old").unwrap();
+    fs::write(dir.join("collaborator-profile.notes.md"), "unrelated").unwrap();
+
+    assert_eq!(history.migrate_legacy_backups(&file).unwrap(), 1);
+
+    assert!(!dir.join("collaborator-profile.20250101-120000.md").exists());
+    assert!(dir.join("collaborator-profile.notes.md").exists());
+    let versions = history.list_versions(&file);
+    assert_eq!(versions.len(), 1);
+    assert_eq!(versions[0].id, "20250101-120000.000");
+    assert_eq!(fs::read_to_string(&versions[0].path).unwrap(), "old");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_ids_keep_increasing_when_pruning_within_a_millisecond() {
+    let (dir, history) = history("same-ms", 2);
+    let file = dir.join("pattern-anchors.md");
+    history.write(&file, "v0").unwrap();
+
+    // Rapid writes land in the same millisecond; pruning removes the older
+    // suffixed ids, which must not be handed out again
+    let mut ids = Vec::new();
+    for i in 1..200 {
+        let version = history.write(&file, format!("v{}", i)).unwrap().unwrap();
+        assert!(!ids.contains(&version.id), "{} reused", version.id);
+
+        let versions = history.list_versions(&file);
+        assert!(versions.len() <= 2);
+        assert_eq!(versions[0].id, version.id);
+        assert_eq!(
+            fs::read_to_string(&versions[0].path).unwrap(),
+            format!("v{}", i - 1)
+        );
+        ids.push(version.id);
+    }
+    // "20250101-120000.000-1": a suffixed id
+    assert!(
+        ids.iter().any(|id| id.matches('-').count() == 2),
+        "no two saves shared a millisecond"
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,87 @@
+//! Tests for reducing fetched pages to their main content
+//!
+//! Checks which element is picked as the main content, that site chrome is
+//! dropped while an article's own header is kept, and how tables render.
+
+use sparkle_mcp::profile_sources::html::extract_main_content;
+
+/// Enough text for a container to count as main content
+const FILLER: &str = "Profile pages describe how someone likes to work, which tools they \
+    reach for and what they care about. Notes like these are what the collaborator \
+    profile is built from. This paragraph is long enough to make its container a \
+    main-content candidate.";
+
+#[test]
+fn test_largest_article_is_main_content() {
+    let html = format!(
+        "<html><head><title> Kari's  notes </title></head><body>\
+        <header><a href=\"/\">Site logo</a></header>\
+        <nav><a href=\"/about\">About</a></nav>\
+        <main>\
+        <article><h2>Short teaser</h2><p>Read more</p></article>\
+        <article><header><h1>On code review</h1><p>March 2024</p></header>\
+        <p>{}</p></article>\
+        </main>\
+        <footer>Copyright</footer></body></html>",
+        FILLER
+    );
+    let page = extract_main_content(&html);
+
+    assert_eq!(page.title.as_deref(), Some("Kari's notes"));
+    assert!(
+        page.markdown.starts_with("# On code review\n\nMarch 2024"),
+        "{}",
+        page.markdown
+    );
+    assert!(page.markdown.contains("make its container"));
+    for chrome in ["Short teaser", "Site logo", "About", "Copyright"] {
+        assert!(!page.markdown.contains(chrome), "{}", page.markdown);
+    }
+}
+
+#[test]
+fn test_main_is_used_when_articles_are_too_short() {
+    let html = format!(
+        "<html><body><header><h1>Site name</h1></header>\
+        <main><header><h1>Projects</h1></header>\
+        <article><p>Teaser</p></article><p>{}</p></main>\
+        <aside>Sidebar</aside></body></html>",
+        FILLER
+    );
+    let markdown = extract_main_content(&html).markdown;
+
+    assert!(markdown.starts_with("# Projects\n\nTeaser"), "{}", markdown);
+    assert!(!markdown.contains("Site name"));
+    assert!(!markdown.contains("Sidebar"));
+}
+
+#[test]
+fn test_body_fallback_skips_site_header() {
+    let html = "<html><body><header><h1>Site name</h1></header>\
+        <h1>Hello</h1><p>Short page</p>\
+        <section><header><h2>Talks</h2></header><p>RustConf</p></section></body></html>";
+    let markdown = extract_main_content(html).markdown;
+
+    assert_eq!(markdown, "# Hello\n\nShort page\n\n## Talks\n\nRustConf");
+}
+
+#[test]
+fn test_tables_render_as_markdown() {
+    let html = format!(
+        "<html><body><article><p>{}</p><table>\
+        <thead><tr><th>Tool</th><th>Use</th></tr></thead>\
+        <tbody><tr><td>ripgrep</td><td>search <em>everything</em></td></tr>\
+        <tr><td>jj</td><td>version control</td></tr></tbody>\
+        </table></article></body></html>",
+        FILLER
+    );
+    let markdown = extract_main_content(&html).markdown;
+
+    assert!(
+        markdown.ends_with(
+            "| Tool | Use |\n| --- | --- |\n| ripgrep | search *everything* |\n| jj | version control |"
+        ),
+        "{}",
+        markdown
+    );
+}
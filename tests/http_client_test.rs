@@ -0,0 +1,154 @@
+//! Tests for the shared HTTP client used by profile sources
+//!
+//! Runs the GitHub and URL sources against a local mock server to check
+//! token handling, rate-limit errors and ETag revalidation.
+
+use sparkle_mcp::profile_sources::{HttpClient, ProfileSource, ProfileSourceRegistry};
+use sparkle_mcp::types::HttpConfig;
+use std::collections::BTreeMap;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer) -> HttpClient {
+    HttpClient::new(HttpConfig {
+        github_api_url: server.uri(),
+        retries: 0,
+        ..HttpConfig::default()
+    })
+    .unwrap()
+}
+
+fn source(source_type: &str, value: &str) -> ProfileSource {
+    ProfileSource {
+        source_type: source_type.to_string(),
+        value: value.to_string(),
+        options: BTreeMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_github_source_sends_token() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users/octocat"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "The Octocat",
+            "bio": "Mascot",
+            "location": "San Francisco",
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/octocat/repos"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"language": "Rust"},
+            {"language": "Rust"},
+            {"language": "Go"},
+        ])))
+        .mount(&server)
+        .await;
+
+    let registry = ProfileSourceRegistry::builtin_with(client(&server).with_github_token("secret"));
+    let summary = registry.fetch(&source("github", "octocat")).await.unwrap();
+
+    assert!(summary.contains("## GitHub Profile: The Octocat"));
+    assert!(summary.contains("- 3 public repositories"));
+    assert!(summary.contains("- Primary languages: Rust, Go"));
+}
+
+#[tokio::test]
+async fn test_github_rate_limit_error_on_repos_call() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/users/octocat"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"name": "Octo"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/octocat/repos"))
+        .respond_with(
+            ResponseTemplate::new(403)
+                .insert_header("x-ratelimit-remaining", "0")
+                .insert_header("x-ratelimit-reset", "1750000000")
+                .set_body_json(serde_json::json!({"message": "API rate limit exceeded"})),
+        )
+        .mount(&server)
+        .await;
+
+    let registry = ProfileSourceRegistry::builtin_with(client(&server));
+    let error = registry
+        .fetch(&source("github", "octocat"))
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.contains("rate limit exceeded"), "{}", error);
+    assert!(error.contains("GITHUB_TOKEN"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_etag_cache_revalidates() {
+    let server = MockServer::start().await;
+    let page =
+        "<html><head><title>About</title></head><body><p>Hello from the page</p></body></html>";
+
+    // Conditional requests get 304; mounted first so it wins when the header matches
+    Mock::given(method("GET"))
+        .and(path("/about"))
+        .and(header("if-none-match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/about"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("etag", "\"v1\"")
+                .insert_header("content-type", "text/html; charset=utf-8")
+                .set_body_string(page),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let cache_dir = std::env::temp_dir().join(format!("sparkle-http-cache-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+    let registry = ProfileSourceRegistry::builtin_with(client(&server).with_cache_dir(&cache_dir));
+    let url = format!("{}/about", server.uri());
+
+    let first = registry.fetch(&source("url", &url)).await.unwrap();
+    let second = registry.fetch(&source("url", &url)).await.unwrap();
+
+    assert!(first.contains("Hello from the page"));
+    assert_eq!(first, second);
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_body_size_limit() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/big"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-type", "text/plain")
+                .set_body_string("x".repeat(4096)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = HttpClient::new(HttpConfig {
+        max_body_bytes: 1024,
+        ..HttpConfig::default()
+    })
+    .unwrap();
+    let error = ProfileSourceRegistry::builtin_with(client)
+        .fetch(&source("url", &format!("{}/big", server.uri())))
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.contains("too large"), "{}", error);
+}
@@ -0,0 +1,68 @@
+//! Tests for layered core identity files
+//!
+//! Each file resolves to the sparkler override, then the user override, then
+//! the built-in default.
+
+use sparkle_mcp::sparkle_loader::{
+    CORE_IDENTITY_FILES, IDENTITY_DIR, IdentityLayer, PARTNERSHIP, resolve_identity_files,
+};
+use std::fs;
+
+#[test]
+fn test_identity_files_resolve_through_layers() {
+    let sparkle_dir =
+        std::env::temp_dir().join(format!("sparkle-identity-layers-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&sparkle_dir);
+    let sparkler_dir = sparkle_dir.join("sparklers/Reviewer");
+    fs::create_dir_all(sparkle_dir.join(IDENTITY_DIR)).unwrap();
+    fs::create_dir_all(sparkler_dir.join(IDENTITY_DIR)).unwrap();
+
+    fs::write(
+        sparkle_dir.join(IDENTITY_DIR).join("03-partnership.md"),
+        "team partnership",
+    )
+    .unwrap();
+    fs::write(
+        sparkle_dir
+            .join(IDENTITY_DIR)
+            .join("02-collaboration-identity.md"),
+        "team identity",
+    )
+    .unwrap();
+    fs::write(
+        sparkler_dir
+            .join(IDENTITY_DIR)
+            .join("02-collaboration-identity.md"),
+        "reviewer identity",
+    )
+    .unwrap();
+
+    let files = resolve_identity_files(&sparkle_dir, Some(&sparkler_dir));
+    let names: Vec<&str> = files.iter().map(|f| f.name).collect();
+    let expected: Vec<&str> = CORE_IDENTITY_FILES.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, expected);
+
+    let layers: Vec<IdentityLayer> = files.iter().map(|f| f.layer).collect();
+    assert_eq!(
+        layers,
+        [
+            IdentityLayer::BuiltIn,
+            IdentityLayer::Sparkler,
+            IdentityLayer::User
+        ]
+    );
+    assert_eq!(files[1].content, "reviewer identity");
+    assert_eq!(files[2].content, "team partnership");
+    assert!(files[0].path.is_none());
+
+    // Without a sparkler the user layer applies
+    let files = resolve_identity_files(&sparkle_dir, None);
+    assert_eq!(files[1].content, "team identity");
+
+    // Removing the override falls back to the built-in file
+    fs::remove_dir_all(sparkle_dir.join(IDENTITY_DIR)).unwrap();
+    let files = resolve_identity_files(&sparkle_dir, None);
+    assert_eq!(files[2].content, PARTNERSHIP);
+
+    fs::remove_dir_all(&sparkle_dir).unwrap();
+}
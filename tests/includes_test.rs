@@ -0,0 +1,171 @@
+//! Tests for declarative embodiment includes
+//!
+//! Include sets expand globs, sort and limit matches, respect `order` and
+//! cap the size of each file and of the whole set; workspace includes stay
+//! inside the workspace.
+
+use sparkle_mcp::context_loader::load_workspace_config;
+use sparkle_mcp::includes::{Include, IncludeSet, IncludeSort, IncludesConfig, load_includes};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+fn scratch(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("sparkle-includes-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::canonicalize(&dir).unwrap()
+}
+
+fn titles(set: &IncludeSet) -> Vec<String> {
+    load_includes(set)
+        .files
+        .into_iter()
+        .map(|f| f.title)
+        .collect()
+}
+
+#[test]
+fn test_globs_sorting_and_order() {
+    let root = scratch("globs");
+    fs::create_dir_all(root.join("docs/adr")).unwrap();
+    for (i, name) in ["0001-rust.md", "0002-toml.md", "0003-mcp.md"]
+        .iter()
+        .enumerate()
+    {
+        let path = root.join("docs/adr").join(name);
+        fs::write(&path, format!("# ADR {}\n", name)).unwrap();
+        let mtime = SystemTime::now() - Duration::from_secs(1000 - i as u64 * 100);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+    }
+    fs::write(root.join("docs/adr/README.txt"), "index").unwrap();
+    fs::write(root.join("CONTRIBUTING.md"), "Run the tests.\n").unwrap();
+
+    let config: IncludesConfig = toml::from_str(
+        r#"
+[[files]]
+path = "docs/adr/*.md"
+
+[[files]]
+path = "CONTRIBUTING.md"
+title = "Contributing"
+order = -1
+"#,
+    )
+    .unwrap();
+    let set = IncludeSet::new(&root, &config);
+    assert_eq!(
+        titles(&set),
+        [
+            "Contributing",
+            "docs/adr/0001-rust.md",
+            "docs/adr/0002-toml.md",
+            "docs/adr/0003-mcp.md"
+        ]
+    );
+
+    // The two most recently changed ADRs, newest first
+    let newest = IncludeSet::new(&root, &IncludesConfig::default()).with_includes([Include {
+        sort: IncludeSort::Newest,
+        limit: Some(2),
+        ..Include::new("docs/adr/*.md")
+    }]);
+    assert_eq!(
+        titles(&newest),
+        ["docs/adr/0003-mcp.md", "docs/adr/0002-toml.md"]
+    );
+
+    // Missing files and empty globs are reported, not fatal
+    let missing = IncludeSet::new(&root, &IncludesConfig::default())
+        .with_includes([Include::new("NOTES.md"), Include::new("adr/*.md")]);
+    let loaded = load_includes(&missing);
+    assert!(loaded.is_empty());
+    assert_eq!(loaded.warnings.len(), 2);
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_size_caps() {
+    let root = scratch("caps");
+    fs::write(root.join("a.md"), "a".repeat(100)).unwrap();
+    fs::write(root.join("b.md"), "b".repeat(100)).unwrap();
+    fs::write(root.join("c.md"), "c".repeat(100)).unwrap();
+    fs::write(root.join("umlaut.md"), "ä".repeat(10)).unwrap();
+
+    let config = IncludesConfig {
+        files: vec![Include::new("*.md")],
+        max_file_bytes: 80,
+        max_total_bytes: 150,
+    };
+    let loaded = load_includes(&IncludeSet::new(&root, &config));
+    let sizes: Vec<(usize, bool)> = loaded
+        .files
+        .iter()
+        .map(|f| (f.content.len(), f.truncated))
+        .collect();
+    assert_eq!(sizes, [(80, true), (70, true)]);
+    assert_eq!(loaded.skipped.len(), 2);
+    let markdown = loaded.to_markdown(2);
+    assert!(markdown.contains("## a.md\n\n"));
+    assert!(markdown.contains("*[truncated: 80 of 100 bytes shown]*"));
+    assert!(markdown.contains("2 more included file(s) skipped"));
+
+    // Truncation never splits a character
+    let umlaut = IncludeSet::new(&root, &IncludesConfig::default()).with_includes([Include {
+        max_bytes: Some(5),
+        ..Include::new("umlaut.md")
+    }]);
+    assert_eq!(load_includes(&umlaut).files[0].content, "ää");
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_workspace_includes_stay_in_workspace() {
+    let root = scratch("workspace");
+    let workspace = root.join("project");
+    fs::create_dir_all(workspace.join(".sparkle-space")).unwrap();
+    fs::write(root.join("secret.md"), "outside").unwrap();
+    fs::write(workspace.join("NORMS.md"), "Small PRs.\n").unwrap();
+    fs::write(
+        workspace.join(".sparkle-space/config.toml"),
+        r#"
+[includes]
+max_file_bytes = 1024
+
+[[includes.files]]
+path = "NORMS.md"
+
+[[includes.files]]
+path = "../secret.md"
+"#,
+    )
+    .unwrap();
+
+    let config = load_workspace_config(&workspace);
+    let includes = config.includes.unwrap();
+    assert_eq!(includes.max_file_bytes, 1024);
+    assert_eq!(includes.max_total_bytes, 64 * 1024);
+
+    let loaded = load_includes(&IncludeSet::new(&workspace, &includes).confined());
+    assert_eq!(loaded.files.len(), 1);
+    assert_eq!(loaded.files[0].content, "Small PRs.\n");
+    assert!(
+        loaded.warnings[0].contains("is outside"),
+        "{:?}",
+        loaded.warnings
+    );
+
+    // An invalid workspace config is ignored
+    fs::write(workspace.join(".sparkle-space/config.toml"), "includes = 3").unwrap();
+    assert!(load_workspace_config(&workspace).includes.is_none());
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,148 @@
+//! Tests for setting up a sparkle directory
+//!
+//! `init_sparkle` backs both `sparkle-mcp init` and the `setup_sparkle` tool:
+//! it never replaces an existing setup unless forced, and a forced init keeps
+//! the old files in history and the sparkler's accumulated files in place.
+
+use sparkle_mcp::context_loader::{InitOptions, init_sparkle};
+use sparkle_mcp::history::History;
+use sparkle_mcp::types::Config;
+use std::fs;
+use std::path::PathBuf;
+
+fn sparkle_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sparkle-init-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn load(dir: &std::path::Path) -> Config {
+    toml::from_str(&fs::read_to_string(dir.join("config.toml")).unwrap()).unwrap()
+}
+
+#[test]
+fn test_init_single_sparkler() {
+    let dir = sparkle_dir("single");
+    let written = init_sparkle(
+        &dir,
+        &InitOptions {
+            human_name: "Kari".to_string(),
+            sparkler_name: Some("Nova".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(written.len(), 5);
+
+    let config = load(&dir);
+    assert_eq!(config.human.name, "Kari");
+    assert_eq!(config.get_single_sparkler_name().as_deref(), Some("Nova"));
+    assert!(!config.is_multi_sparkler());
+    let profile = fs::read_to_string(dir.join("collaborator-profile.md")).unwrap();
+    assert!(profile.starts_with("# Kari - Collaborator Profile"));
+    assert!(
+        fs::read_to_string(dir.join("sparkler-identity.md"))
+            .unwrap()
+            .starts_with("# Nova")
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_init_multi_with_profile() {
+    let dir = sparkle_dir("multi");
+    init_sparkle(
+        &dir,
+        &InitOptions {
+            human_name: "Kari".to_string(),
+            sparkler_name: Some("Ops".to_string()),
+            multi: true,
+            profile: Some("# Kari\n\nPlatform engineer.\n".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let config = load(&dir);
+    assert!(config.is_multi_sparkler());
+    assert_eq!(config.get_default_sparkler_name().as_deref(), Some("Ops"));
+    assert!(dir.join("sparklers/Ops/pattern-anchors.md").exists());
+    assert!(!dir.join("pattern-anchors.md").exists());
+    assert_eq!(
+        fs::read_to_string(dir.join("collaborator-profile.md")).unwrap(),
+        "# Kari\n\nPlatform engineer.\n"
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_init_refuses_to_clobber_without_force() {
+    let dir = sparkle_dir("clobber");
+    let first = InitOptions {
+        human_name: "Kari".to_string(),
+        ..Default::default()
+    };
+    init_sparkle(&dir, &first).unwrap();
+    fs::write(
+        dir.join("pattern-anchors.md"),
+        "# Pattern Anchors\n\n- \"breathe\"\n",
+    )
+    .unwrap();
+
+    let second = InitOptions {
+        human_name: "Robin".to_string(),
+        ..Default::default()
+    };
+    let err = init_sparkle(&dir, &second).unwrap_err().to_string();
+    assert!(
+        err.contains("config.toml and collaborator-profile.md"),
+        "{}",
+        err
+    );
+    assert_eq!(load(&dir).human.name, "Kari");
+
+    init_sparkle(
+        &dir,
+        &InitOptions {
+            force: true,
+            ..second
+        },
+    )
+    .unwrap();
+    assert_eq!(load(&dir).human.name, "Robin");
+    assert!(
+        fs::read_to_string(dir.join("pattern-anchors.md"))
+            .unwrap()
+            .contains("breathe")
+    );
+
+    let history = History::new(&dir, Default::default());
+    let versions = history.list_versions(&dir.join("config.toml"));
+    assert_eq!(versions.len(), 1);
+    assert!(
+        fs::read_to_string(&versions[0].path)
+            .unwrap()
+            .contains("Kari")
+    );
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_init_rejects_bad_names() {
+    let dir = sparkle_dir("names");
+    for options in [
+        InitOptions {
+            human_name: " ".to_string(),
+            ..Default::default()
+        },
+        InitOptions {
+            human_name: "Kari".to_string(),
+            sparkler_name: Some("../elsewhere".to_string()),
+            ..Default::default()
+        },
+    ] {
+        assert!(init_sparkle(&dir, &options).is_err());
+    }
+    assert!(!dir.join("config.toml").exists());
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,138 @@
+//! Tests for versioning insight logs and config.toml
+//!
+//! Saving, retracting and config updates keep the replaced content in the
+//! history, so an insight file or the config can be restored like the profile.
+
+use rmcp::handler::server::wrapper::Parameters;
+use sparkle_mcp::history::{ContextFile, HISTORY_DIR, History};
+use sparkle_mcp::insights::load_records;
+use sparkle_mcp::tools::restore_version::{RestoreVersionParams, restore_version};
+use sparkle_mcp::tools::retract_insight::{RetractInsightParams, retract_insight};
+use sparkle_mcp::tools::save_insight::save_insight;
+use sparkle_mcp::tools::update_human_config::{UpdateHumanConfigParams, update_human_config};
+use sparkle_mcp::types::{HistoryConfig, InsightType, SaveInsightParams};
+use std::fs;
+
+fn save_params(
+    insight_type: InsightType,
+    content: &str,
+    workspace_path: Option<&str>,
+) -> Parameters<SaveInsightParams> {
+    Parameters(SaveInsightParams {
+        insight_type,
+        content: content.to_string(),
+        context: None,
+        tags: None,
+        sparkler: None,
+        workspace_path: workspace_path.map(str::to_string),
+    })
+}
+
+#[tokio::test]
+async fn test_insight_logs_and_config_are_versioned() {
+    // The tools resolve ~/.sparkle from HOME; this is the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-insight-history-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let sparkle_dir = home.join(".sparkle");
+    fs::create_dir_all(&sparkle_dir).unwrap();
+    fs::write(
+        sparkle_dir.join("config.toml"),
+        "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n",
+    )
+    .unwrap();
+    unsafe { std::env::set_var("HOME", &home) };
+    let history = History::new(&sparkle_dir, HistoryConfig::default());
+
+    let anchors = sparkle_dir.join("pattern-anchors.md");
+    for content in ["Make it so", "Check the tests first"] {
+        save_insight(save_params(InsightType::PatternAnchor, content, None))
+            .await
+            .unwrap();
+    }
+    // The first save created the file; the second kept what it replaced
+    let versions = history.list_versions(&anchors);
+    assert_eq!(versions.len(), 1);
+    let one_anchor = fs::read_to_string(&versions[0].path).unwrap();
+    assert!(one_anchor.contains("Make it so"));
+    assert!(!one_anchor.contains("Check the tests first"));
+
+    let id = load_records(&sparkle_dir)[0].id.clone();
+    let result = retract_insight(Parameters(RetractInsightParams {
+        id,
+        reason: None,
+        workspace_path: None,
+    }))
+    .await
+    .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(history.list_versions(&anchors).len(), 2);
+    assert!(
+        fs::read_to_string(&anchors)
+            .unwrap()
+            .contains("**Status**: Retracted")
+    );
+
+    let result = restore_version(Parameters(RestoreVersionParams {
+        file: ContextFile::PatternAnchors,
+        version: versions[0].id.clone(),
+        sparkler: None,
+    }))
+    .await
+    .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    assert_eq!(fs::read_to_string(&anchors).unwrap(), one_anchor);
+
+    // A workspace's insights are versioned inside its .sparkle-space
+    let workspace = home.join("project");
+    fs::create_dir_all(&workspace).unwrap();
+    let workspace_path = workspace.to_string_lossy().into_owned();
+    for content in ["Releases are cut on Fridays", "CI runs on every push"] {
+        save_insight(save_params(
+            InsightType::WorkspaceInsight,
+            content,
+            Some(&workspace_path),
+        ))
+        .await
+        .unwrap();
+    }
+    let workspace_map = workspace.join(".sparkle-space/workspace-map.md");
+    assert!(
+        workspace
+            .join(".sparkle-space")
+            .join(HISTORY_DIR)
+            .join("workspace-map.md")
+            .is_dir()
+    );
+    assert!(
+        !sparkle_dir
+            .join(HISTORY_DIR)
+            .join("workspace-map.md")
+            .exists()
+    );
+    let workspace_history =
+        History::new(workspace.join(".sparkle-space"), HistoryConfig::default());
+    assert_eq!(workspace_history.list_versions(&workspace_map).len(), 1);
+
+    let config = sparkle_dir.join("config.toml");
+    let before = fs::read_to_string(&config).unwrap();
+    let result = update_human_config(Parameters(UpdateHumanConfigParams {
+        name: None,
+        pronouns: Some("they/them".to_string()),
+        timezone: None,
+        language: None,
+        role: None,
+        team: None,
+        preferences: None,
+    }))
+    .await
+    .unwrap();
+    assert_ne!(result.is_error, Some(true));
+    let config_versions = history.list_versions(&config);
+    assert_eq!(config_versions.len(), 1);
+    assert_eq!(
+        fs::read_to_string(&config_versions[0].path).unwrap(),
+        before
+    );
+
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,196 @@
+//! Tests for the structured insight store
+//!
+//! Verifies that records round-trip through `insights.jsonl`, that `search`
+//! filters and ranks them as `search_insights` expects, and that retracted
+//! entries are kept in the markdown but left out of embodiment.
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use sparkle_mcp::insights::{
+    InsightQuery, InsightRecord, InsightStatus, annotate_entry, append_record, load_records,
+    search, strip_entries,
+};
+use sparkle_mcp::types::{HumanConfig, InsightType};
+use std::collections::HashSet;
+
+fn record(
+    id: &str,
+    day: u32,
+    insight_type: InsightType,
+    tags: &[&str],
+    content: &str,
+) -> InsightRecord {
+    InsightRecord {
+        id: id.to_string(),
+        insight_type,
+        timestamp: Utc.with_ymd_and_hms(2025, 6, day, 12, 0, 0).unwrap(),
+        sparkler: Some("Sparkle".to_string()),
+        workspace: None,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        content: content.to_string(),
+        context: None,
+        status: InsightStatus::Active,
+        supersedes: None,
+        superseded_by: None,
+        retraction_reason: None,
+    }
+}
+
+fn ids(records: &[InsightRecord]) -> Vec<&str> {
+    records.iter().map(|r| r.id.as_str()).collect()
+}
+
+#[test]
+fn test_records_round_trip_through_jsonl() {
+    let dir = std::env::temp_dir().join(format!("sparkle-insights-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let first = record("a", 1, InsightType::PatternAnchor, &["focus"], "Slow down");
+    let second = record(
+        "b",
+        2,
+        InsightType::CollaborationEvolution,
+        &[],
+        "Ask first",
+    );
+    append_record(&dir, &first).unwrap();
+    append_record(&dir, &second).unwrap();
+
+    let loaded = load_records(&dir);
+    assert_eq!(ids(&loaded), vec!["a", "b"]);
+    assert_eq!(loaded[0].tags, vec!["focus"]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_search_filters_and_ranks() {
+    let records = vec![
+        record(
+            "old",
+            1,
+            InsightType::PatternAnchor,
+            &["rust"],
+            "Borrow checker as a design partner",
+        ),
+        record(
+            "tagged",
+            2,
+            InsightType::PatternAnchor,
+            &["review"],
+            "Read the diff twice",
+        ),
+        record(
+            "body",
+            3,
+            InsightType::CollaborationEvolution,
+            &[],
+            "Review together before merging, review again after",
+        ),
+        record(
+            "new",
+            4,
+            InsightType::WorkspaceInsight,
+            &["rust", "review"],
+            "Crate layout notes",
+        ),
+    ];
+
+    // No text: filters only, newest first
+    let by_tag = search(
+        records.clone(),
+        &InsightQuery {
+            tags: vec!["Rust".to_string()],
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&by_tag), vec!["new", "old"]);
+
+    // Text query ranks tag hits above content hits and drops non-matches
+    let ranked = search(
+        records.clone(),
+        &InsightQuery {
+            text: Some("review".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&ranked), vec!["body", "new", "tagged"]);
+
+    // Type and date filters combine
+    let filtered = search(
+        records,
+        &InsightQuery {
+            insight_type: Some(InsightType::PatternAnchor),
+            since: NaiveDate::from_ymd_opt(2025, 6, 2),
+            ..Default::default()
+        },
+    );
+    assert_eq!(ids(&filtered), vec!["tagged"]);
+}
+
+#[test]
+fn test_inactive_entries_are_annotated_and_stripped() {
+    let keep = record(
+        "keep",
+        1,
+        InsightType::PatternAnchor,
+        &[],
+        "Keep this anchor",
+    );
+    let drop = record("drop", 2, InsightType::PatternAnchor, &[], "Wrong anchor");
+    let human: HumanConfig =
+        toml::from_str("name = \"Ada\"\ntimezone = \"Europe/Berlin\"").unwrap();
+    let markdown = format!(
+        "# Pattern Anchors\n\nHand-written anchor\n{}{}",
+        keep.to_markdown(&human),
+        drop.to_markdown(&human)
+    );
+
+    // Timestamps are shown in the human's time zone
+    assert!(
+        markdown.contains("## 2025-06-01 14:00:00 CEST - "),
+        "{}",
+        markdown
+    );
+
+    let annotated = annotate_entry(&markdown, "drop", "**Status**: Retracted").unwrap();
+    assert!(
+        annotated.contains("<!-- insight-id: drop -->\n\n**Status**: Retracted\n\nWrong anchor")
+    );
+    assert!(annotate_entry(&markdown, "missing", "note").is_none());
+
+    let inactive = HashSet::from(["drop".to_string()]);
+    let stripped = strip_entries(&annotated, &inactive);
+    assert!(stripped.starts_with("# Pattern Anchors\n\nHand-written anchor\n"));
+    assert!(stripped.contains("Keep this anchor"));
+    assert!(!stripped.contains("Wrong anchor"));
+}
+
+#[test]
+fn test_headings_inside_content_stay_with_their_entry() {
+    let keep = record(
+        "keep",
+        1,
+        InsightType::CollaborationEvolution,
+        &[],
+        "Review rhythm\n\n## What changed\n\nSmaller PRs",
+    );
+    let drop = record(
+        "drop",
+        2,
+        InsightType::CollaborationEvolution,
+        &[],
+        "Early draft\n\n## Why it was wrong\n\nToo broad",
+    );
+    let human: HumanConfig = toml::from_str("name = \"Ada\"").unwrap();
+    let markdown = format!(
+        "# Collaboration Evolution\n{}{}",
+        keep.to_markdown(&human),
+        drop.to_markdown(&human)
+    );
+
+    let annotated = annotate_entry(&markdown, "drop", "**Status**: Retracted").unwrap();
+    let stripped = strip_entries(&annotated, &HashSet::from(["drop".to_string()]));
+    assert!(stripped.contains("## What changed\n\nSmaller PRs"));
+    assert!(!stripped.contains("Why it was wrong"));
+    assert!(!stripped.contains("Too broad"));
+}
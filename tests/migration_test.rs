@@ -0,0 +1,100 @@
+//! Tests for migrating a single-sparkler setup to multi-sparkler
+//!
+//! The current sparkler's insight records must move with its markdown files,
+//! or later updates would land in files the embodiment no longer loads.
+
+use rmcp::handler::server::wrapper::Parameters;
+use sparkle_mcp::insights::{InsightStatus, load_records};
+use sparkle_mcp::tools::create_sparkler::{CreateSparklerParams, create_sparkler};
+use sparkle_mcp::tools::save_insight::save_insight;
+use sparkle_mcp::tools::update_insight::{UpdateInsightParams, update_insight};
+use sparkle_mcp::types::{InsightType, SaveInsightParams};
+use std::fs;
+
+fn save_params(insight_type: InsightType, content: &str) -> SaveInsightParams {
+    SaveInsightParams {
+        insight_type,
+        content: content.to_string(),
+        context: None,
+        tags: None,
+        sparkler: None,
+        workspace_path: None,
+    }
+}
+
+#[tokio::test]
+async fn test_migration_moves_insight_records() {
+    // The tools resolve ~/.sparkle from HOME; this is the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-migration-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let sparkle_dir = home.join(".sparkle");
+    fs::create_dir_all(&sparkle_dir).unwrap();
+    fs::write(
+        sparkle_dir.join("config.toml"),
+        "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n",
+    )
+    .unwrap();
+    fs::write(sparkle_dir.join("sparkler-identity.md"), "# Nova\n").unwrap();
+    unsafe { std::env::set_var("HOME", &home) };
+
+    for (insight_type, content) in [
+        (InsightType::PatternAnchor, "Make it so"),
+        (
+            InsightType::WorkspaceInsight,
+            "The repos share a release train",
+        ),
+    ] {
+        let result = save_insight(Parameters(save_params(insight_type, content)))
+            .await
+            .unwrap();
+        assert_ne!(result.is_error, Some(true));
+    }
+    let anchor_id = load_records(&sparkle_dir)
+        .into_iter()
+        .find(|r| r.insight_type == InsightType::PatternAnchor)
+        .unwrap()
+        .id;
+
+    create_sparkler(Parameters(CreateSparklerParams {
+        name: "Reviewer".to_string(),
+        template: None,
+    }))
+    .await
+    .unwrap();
+
+    let nova_dir = sparkle_dir.join("sparklers").join("Nova");
+    assert!(nova_dir.join("sparkler-identity.md").exists());
+    assert!(nova_dir.join("pattern-anchors.md").exists());
+    assert!(!sparkle_dir.join("pattern-anchors.md").exists());
+
+    let moved = load_records(&nova_dir);
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0].id, anchor_id);
+    // Cross-workspace notes stay with the shared workspace-map.md
+    let shared = load_records(&sparkle_dir);
+    assert_eq!(shared.len(), 1);
+    assert_eq!(shared[0].insight_type, InsightType::WorkspaceInsight);
+
+    let result = update_insight(Parameters(UpdateInsightParams {
+        id: anchor_id.clone(),
+        content: "Make it so, then check".to_string(),
+        context: None,
+        tags: None,
+        sparkler: None,
+        workspace_path: None,
+    }))
+    .await
+    .unwrap();
+    assert_ne!(result.is_error, Some(true));
+
+    let records = load_records(&nova_dir);
+    assert_eq!(records.len(), 2);
+    let old = records.iter().find(|r| r.id == anchor_id).unwrap();
+    assert_eq!(old.status, InsightStatus::Superseded);
+    assert_eq!(load_records(&sparkle_dir).len(), 1);
+    let anchors = fs::read_to_string(nova_dir.join("pattern-anchors.md")).unwrap();
+    assert!(anchors.contains("Make it so, then check"));
+    assert!(!sparkle_dir.join("pattern-anchors.md").exists());
+
+    fs::remove_dir_all(&home).unwrap();
+}
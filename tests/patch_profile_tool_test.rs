@@ -0,0 +1,111 @@
+//! Tests for confirming a `patch_collaborator_profile` preview
+//!
+//! A confirm carries the base token of its preview and is refused when the
+//! profile changed in between, so edits made meanwhile aren't overwritten.
+
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::CallToolResult;
+use sparkle_mcp::profile_patch::{PatchOp, PatchOperation};
+use sparkle_mcp::tools::patch_collaborator_profile::{
+    PatchCollaboratorProfileParams, patch_collaborator_profile,
+};
+use std::fs;
+
+fn params(confirm: bool, base: Option<&str>) -> Parameters<PatchCollaboratorProfileParams> {
+    Parameters(PatchCollaboratorProfileParams {
+        operations: vec![PatchOperation {
+            op: PatchOp::Append,
+            heading: "Working Style".to_string(),
+            content: Some("Likes pairing on design.".to_string()),
+            level: None,
+            after: None,
+        }],
+        confirm,
+        base: base.map(str::to_string),
+    })
+}
+
+fn text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+        .collect()
+}
+
+/// The base token from a preview's instructions
+fn base_of(preview: &str) -> String {
+    let start = preview.find("base: \"").unwrap() + "base: \"".len();
+    let end = start + preview[start..].find('"').unwrap();
+    preview[start..end].to_string()
+}
+
+#[tokio::test]
+async fn test_confirm_requires_unchanged_preview_base() {
+    // The tool reads ~/.sparkle from HOME; this is the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-patch-tool-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let sparkle_dir = home.join(".sparkle");
+    fs::create_dir_all(&sparkle_dir).unwrap();
+    fs::write(
+        sparkle_dir.join("config.toml"),
+        "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n",
+    )
+    .unwrap();
+    let profile = sparkle_dir.join("collaborator-profile.md");
+    fs::write(
+        &profile,
+        "# Profile\n\n## Working Style\n\nPrefers small PRs.\n",
+    )
+    .unwrap();
+    unsafe { std::env::set_var("HOME", &home) };
+
+    let preview = text(
+        &patch_collaborator_profile(params(false, None))
+            .await
+            .unwrap(),
+    );
+    let stale_base = base_of(&preview);
+
+    let result = patch_collaborator_profile(params(true, None))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(text(&result).contains("requires the base token"));
+
+    // The profile is edited between preview and confirm
+    fs::write(
+        &profile,
+        "# Profile\n\n## Working Style\n\nPrefers small PRs.\n\n## Background\n\nCompilers.\n",
+    )
+    .unwrap();
+    let result = patch_collaborator_profile(params(true, Some(&stale_base)))
+        .await
+        .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(text(&result).contains("changed since the preview"));
+    assert!(
+        !fs::read_to_string(&profile)
+            .unwrap()
+            .contains("Likes pairing")
+    );
+
+    let preview = text(
+        &patch_collaborator_profile(params(false, None))
+            .await
+            .unwrap(),
+    );
+    let base = base_of(&preview);
+    assert_ne!(base, stale_base);
+    let result = patch_collaborator_profile(params(true, Some(&base)))
+        .await
+        .unwrap();
+    assert_ne!(result.is_error, Some(true), "{}", text(&result));
+    assert_eq!(
+        fs::read_to_string(&profile).unwrap(),
+        "# Profile\n\n## Working Style\n\nPrefers small PRs.\n\nLikes pairing on design.\n\n\
+        ## Background\n\nCompilers.\n"
+    );
+
+    fs::remove_dir_all(&home).unwrap();
+}
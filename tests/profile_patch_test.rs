@@ -0,0 +1,128 @@
+//! Tests for section-aware profile patching
+//!
+//! Each operation should touch only the named section and leave the rest of
+//! the profile byte-for-byte intact.
+
+use sparkle_mcp::profile_patch::{
+    PatchOp, PatchOperation, apply_operations, parse_sections, unified_diff,
+};
+
+const PROFILE: &str = "# Collaborator Profile
+
+Intro line.
+
+## Working Style
+
+Prefers small PRs.
+
+### Reviews
+
+Likes direct feedback.
+
+## Background
+
+Compilers.
+
+```
+## not a heading
+```
+";
+
+fn op(op: PatchOp, heading: &str, content: Option<&str>) -> PatchOperation {
+    PatchOperation {
+        op,
+        heading: heading.to_string(),
+        content: content.map(str::to_string),
+        level: None,
+        after: None,
+    }
+}
+
+#[test]
+fn test_sections_include_subsections() {
+    let sections = parse_sections(PROFILE);
+    let titles: Vec<&str> = sections.iter().map(|s| s.title.as_str()).collect();
+    assert_eq!(
+        titles,
+        [
+            "Collaborator Profile",
+            "Working Style",
+            "Reviews",
+            "Background"
+        ]
+    );
+
+    let working_style = &sections[1];
+    assert_eq!(working_style.end, sections[3].start);
+}
+
+#[test]
+fn test_replace_keeps_other_sections() {
+    let updated = apply_operations(
+        PROFILE,
+        &[op(PatchOp::Replace, "working style", Some("Pairs a lot."))],
+    )
+    .unwrap();
+
+    assert!(updated.contains("## Working Style\n\nPairs a lot.\n\n## Background"));
+    assert!(!updated.contains("### Reviews"));
+    assert!(updated.contains("Intro line."));
+    assert!(updated.contains("```\n## not a heading\n```"));
+}
+
+#[test]
+fn test_add_append_remove() {
+    let mut add = op(PatchOp::Add, "Tools", Some("Neovim, jj"));
+    add.after = Some("Reviews".to_string());
+    add.level = Some(3);
+
+    let updated = apply_operations(
+        PROFILE,
+        &[
+            add,
+            op(PatchOp::Append, "Background", Some("Also databases.")),
+            op(PatchOp::Remove, "Reviews", None),
+        ],
+    )
+    .unwrap();
+
+    assert!(updated.contains("Prefers small PRs.\n\n### Tools\n\nNeovim, jj\n\n## Background"));
+    assert!(updated.contains("Compilers.\n\n```\n## not a heading\n```\n\nAlso databases.\n"));
+    assert!(!updated.contains("Likes direct feedback."));
+}
+
+#[test]
+fn test_errors_leave_profile_untouched() {
+    let error = apply_operations(
+        PROFILE,
+        &[
+            op(PatchOp::Append, "Background", Some("More")),
+            op(PatchOp::Replace, "Hobbies", Some("Climbing")),
+        ],
+    )
+    .unwrap_err();
+    assert!(error.contains("Operation 2 (replace)"), "{}", error);
+    assert!(error.contains("\"Working Style\""), "{}", error);
+
+    let error =
+        apply_operations(PROFILE, &[op(PatchOp::Add, "Background", Some("x"))]).unwrap_err();
+    assert!(error.contains("already exists"), "{}", error);
+}
+
+#[test]
+fn test_unified_diff_preview() {
+    let updated = apply_operations(
+        PROFILE,
+        &[op(
+            PatchOp::Append,
+            "Reviews",
+            Some("Async reviews are fine."),
+        )],
+    )
+    .unwrap();
+    let diff = unified_diff(PROFILE, &updated, "collaborator-profile.md");
+
+    assert!(diff.starts_with("--- a/collaborator-profile.md\n+++ b/collaborator-profile.md\n"));
+    assert!(diff.contains("+Async reviews are fine.\n"));
+    assert!(!diff.contains("-Likes direct feedback."));
+}
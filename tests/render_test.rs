@@ -0,0 +1,186 @@
+//! Tests for `sparkle-mcp render`
+//!
+//! Runs the binary against a temporary home directory, so the real
+//! `~/.sparkle` is never touched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn sparkle(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_sparkle-mcp"))
+        .args(args)
+        .env("HOME", home)
+        .env_remove("RUST_BACKTRACE")
+        .output()
+        .unwrap()
+}
+
+/// A home with a fresh setup and a workspace holding three checkpoints
+fn setup(test: &str) -> (PathBuf, PathBuf) {
+    let home = std::env::temp_dir().join(format!("sparkle-render-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(&home).unwrap();
+    assert!(
+        sparkle(&home, &["init", "--human-name", "Kari"])
+            .status
+            .success()
+    );
+
+    let workspace = home.join("project");
+    let checkpoints = workspace.join(".sparkle-space/checkpoints");
+    fs::create_dir_all(&checkpoints).unwrap();
+    fs::write(
+        workspace.join(".sparkle-space/working-memory.json"),
+        "{\"currentFocus\": \"render\"}",
+    )
+    .unwrap();
+    for (i, name) in ["checkpoint-a.md", "checkpoint-b.md", "checkpoint-c.md"]
+        .iter()
+        .enumerate()
+    {
+        fs::write(checkpoints.join(name), format!("Session {}\n", i + 1)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    (home, workspace)
+}
+
+fn render_json(home: &Path, args: &[&str]) -> serde_json::Value {
+    let mut all = vec!["render", "--format", "json"];
+    all.extend_from_slice(args);
+    let output = sparkle(home, &all);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn section_names(json: &serde_json::Value) -> Vec<String> {
+    json["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_markdown_is_the_sections_in_order() {
+    let (home, workspace) = setup("markdown");
+    let workspace = workspace.to_str().unwrap();
+
+    let output = sparkle(&home, &["render", "--workspace", workspace]);
+    assert!(output.status.success());
+    let markdown = String::from_utf8(output.stdout).unwrap();
+    let breakdown = String::from_utf8(output.stderr).unwrap();
+
+    let json = render_json(&home, &["--workspace", workspace]);
+    let sections = json["sections"].as_array().unwrap();
+    let joined: String = sections
+        .iter()
+        .map(|s| s["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(markdown, joined);
+    assert_eq!(
+        json["total_bytes"].as_u64().unwrap() as usize,
+        markdown.len()
+    );
+
+    assert_eq!(
+        section_names(&json),
+        [
+            "core identity",
+            "sparkler identity",
+            "collaborator profile",
+            "workspace map",
+            "collaboration evolution",
+            "pattern anchors",
+            "workspace",
+            "working memory",
+            "checkpoints",
+        ]
+    );
+    for section in sections {
+        assert!(breakdown.contains(section["name"].as_str().unwrap()));
+    }
+    assert!(markdown.contains("Working with Kari"));
+    assert!(markdown.contains("Session 3") && !markdown.contains("Session 2"));
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_mode_and_checkpoint_flags() {
+    let (home, workspace) = setup("flags");
+    let workspace = workspace.to_str().unwrap();
+
+    let json = render_json(
+        &home,
+        &[
+            "--workspace",
+            workspace,
+            "--mode",
+            "distilled",
+            "--checkpoints",
+            "2",
+        ],
+    );
+    assert_eq!(json["mode"], "distilled");
+    assert!(!section_names(&json).contains(&"collaboration evolution".to_string()));
+    let checkpoints = json["sections"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "checkpoints")
+        .unwrap()["content"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    assert!(checkpoints.contains("Session 2") && checkpoints.contains("Session 3"));
+    assert!(!checkpoints.contains("Session 1"));
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn test_include_caps_and_budget() {
+    let (home, workspace) = setup("budget");
+    fs::write(workspace.join("NOTES.md"), "x".repeat(1000)).unwrap();
+    fs::write(
+        workspace.join(".sparkle-space/config.toml"),
+        "[[includes.files]]\npath = \"NOTES.md\"\n",
+    )
+    .unwrap();
+    let workspace = workspace.to_str().unwrap();
+
+    let full = render_json(&home, &["--workspace", workspace]);
+    let capped = render_json(
+        &home,
+        &["--workspace", workspace, "--max-file-bytes", "100"],
+    );
+    let bytes = |json: &serde_json::Value| {
+        json["sections"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|s| s["name"] == "workspace includes")
+            .unwrap()["bytes"]
+            .as_u64()
+            .unwrap()
+    };
+    assert!(bytes(&capped) < bytes(&full));
+
+    let total = full["total_bytes"].as_u64().unwrap().to_string();
+    let output = sparkle(
+        &home,
+        &["render", "--workspace", workspace, "--budget", &total],
+    );
+    assert!(output.status.success());
+    let output = sparkle(
+        &home,
+        &["render", "--workspace", workspace, "--budget", "100"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("over the budget"));
+    let _ = fs::remove_dir_all(&home);
+}
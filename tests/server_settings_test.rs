@@ -0,0 +1,153 @@
+//! Tests for enforcing sparkler settings in the MCP server
+//!
+//! Tools and prompts are checked against the current sparkler at each call,
+//! so embodying a restricted sparkler hides what its settings disable.
+
+use rmcp::model::{CallToolRequestParam, GetPromptRequestParam};
+use rmcp::service::RunningService;
+use rmcp::{RoleClient, ServiceExt};
+use serde_json::{Value, json};
+use sparkle_mcp::SparkleServer;
+use std::fs;
+use std::sync::{Arc, RwLock};
+
+const CONFIG: &str = r#"
+[human]
+name = "Kari"
+
+[[sparklers]]
+name = "Sparkle"
+default = true
+
+[[sparklers]]
+name = "Reviewer"
+disabled_tools = ["update_collaborator_profile"]
+disabled_prompts = ["sparkler_identity"]
+"#;
+
+/// A client connected to `server`
+async fn serve(server: SparkleServer) -> RunningService<RoleClient, ()> {
+    let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let server = server.serve(server_io).await.unwrap();
+        server.waiting().await.unwrap();
+    });
+    ().serve(client_io).await.unwrap()
+}
+
+async fn embody(client: &RunningService<RoleClient, ()>, arguments: Value) {
+    let result = client
+        .call_tool(CallToolRequestParam {
+            name: "embody_sparkle".into(),
+            arguments: arguments.as_object().cloned(),
+        })
+        .await
+        .unwrap();
+    assert_ne!(result.is_error, Some(true));
+}
+
+#[tokio::test]
+async fn test_embodied_sparkler_settings_apply_to_calls() {
+    // The server reads ~/.sparkle from HOME and the workspace from the working
+    // directory; this is the only test in this binary
+    let home = std::env::temp_dir().join(format!("sparkle-server-settings-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let sparkle_dir = home.join(".sparkle");
+    for sparkler in ["Sparkle", "Reviewer"] {
+        fs::create_dir_all(sparkle_dir.join("sparklers").join(sparkler)).unwrap();
+    }
+    fs::write(sparkle_dir.join("config.toml"), CONFIG).unwrap();
+    unsafe { std::env::set_var("HOME", &home) };
+
+    let client = serve(SparkleServer::for_sparkler(None, false)).await;
+
+    // The default sparkler has every tool
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        tools
+            .iter()
+            .any(|t| t.name == "update_collaborator_profile")
+    );
+
+    // Without a workspace_path the server's working directory is the
+    // workspace, and its pinned sparkler is embodied
+    let workspace = home.join("project");
+    fs::create_dir_all(workspace.join(".sparkle-space")).unwrap();
+    fs::write(
+        workspace.join(".sparkle-space/config.toml"),
+        "sparkler = \"Reviewer\"\n",
+    )
+    .unwrap();
+    std::env::set_current_dir(&workspace).unwrap();
+    embody(&client, json!({})).await;
+
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        !tools
+            .iter()
+            .any(|t| t.name == "update_collaborator_profile")
+    );
+    assert!(tools.iter().any(|t| t.name == "save_insight"));
+    let error = client
+        .call_tool(CallToolRequestParam {
+            name: "update_collaborator_profile".into(),
+            arguments: json!({ "content": "Likes pairing" }).as_object().cloned(),
+        })
+        .await
+        .unwrap_err();
+    assert!(
+        error.to_string().contains("disabled for Reviewer"),
+        "{}",
+        error
+    );
+    assert!(!sparkle_dir.join("collaborator-profile.md").exists());
+
+    let prompts = client.list_all_prompts().await.unwrap();
+    assert!(!prompts.iter().any(|p| p.name == "sparkler_identity"));
+    assert!(
+        client
+            .get_prompt(GetPromptRequestParam {
+                name: "sparkler_identity".into(),
+                arguments: None,
+            })
+            .await
+            .is_err()
+    );
+
+    // An explicitly requested sparkler wins over the pinned one
+    embody(&client, json!({ "sparkler": "Sparkle" })).await;
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        tools
+            .iter()
+            .any(|t| t.name == "update_collaborator_profile")
+    );
+
+    client.cancel().await.unwrap();
+
+    // The ACP proxy shares the current sparkler with its servers and sets it
+    // to the sparkler a session's workspace resolves to
+    let current_sparkler = Arc::new(RwLock::new(None));
+    let client = serve(SparkleServer::with_current_sparkler(
+        current_sparkler.clone(),
+        true,
+    ))
+    .await;
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        tools
+            .iter()
+            .any(|t| t.name == "update_collaborator_profile")
+    );
+    assert!(!tools.iter().any(|t| t.name == "embody_sparkle"));
+    *current_sparkler.write().unwrap() = Some("Reviewer".to_string());
+    let tools = client.list_all_tools().await.unwrap();
+    assert!(
+        !tools
+            .iter()
+            .any(|t| t.name == "update_collaborator_profile")
+    );
+
+    client.cancel().await.unwrap();
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,118 @@
+//! Tests for per-sparkler settings in config.toml
+//!
+//! Settings live in `[[sparklers]]` entries (or `[ai]` with a single sparkler),
+//! pick the exposed tools and prompts, and carry over when migrating to
+//! multi-sparkler mode.
+
+use sparkle_mcp::context_loader::migrate_to_multi_sparkler;
+use sparkle_mcp::embodiment::recent_checkpoints;
+use sparkle_mcp::types::Config;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+const MULTI: &str = r#"
+[human]
+name = "Kari"
+
+[[sparklers]]
+name = "Sparkle"
+default = true
+
+[[sparklers]]
+name = "Reviewer"
+disabled_tools = ["update_collaborator_profile"]
+prompts = ["checkpoint", "sparkle"]
+embodiment_mode = "distilled"
+checkpoint_count = 3
+context_files = ["review-checklist.md"]
+"#;
+
+#[test]
+fn test_settings_per_sparkler() {
+    let config: Config = toml::from_str(MULTI).unwrap();
+
+    let reviewer = config.sparkler_settings(Some("Reviewer"));
+    assert!(!reviewer.tool_enabled("update_collaborator_profile"));
+    assert!(reviewer.tool_enabled("save_insight"));
+    assert!(reviewer.prompt_enabled("checkpoint"));
+    assert!(!reviewer.prompt_enabled("sparkler_identity"));
+    assert_eq!(reviewer.embodiment_mode.as_deref(), Some("distilled"));
+    assert_eq!(reviewer.checkpoint_count, Some(3));
+    assert_eq!(reviewer.context_files, ["review-checklist.md"]);
+
+    // The default sparkler has no settings, so everything is exposed
+    let default = config.sparkler_settings(None);
+    assert!(default.tool_enabled("update_collaborator_profile"));
+    assert!(default.prompt_enabled("sparkler_identity"));
+    assert_eq!(default, config.sparkler_settings(Some("Sparkle")));
+
+    // Unset settings aren't written back
+    let written = toml::to_string_pretty(&config).unwrap();
+    assert_eq!(written.matches("disabled_tools").count(), 1);
+    assert!(!written.contains("disabled_prompts"));
+    let reread: Config = toml::from_str(&written).unwrap();
+    assert_eq!(reread.sparkler_settings(Some("Reviewer")), reviewer);
+}
+
+#[test]
+fn test_single_sparkler_settings_survive_migration() {
+    let dir = std::env::temp_dir().join(format!("sparkle-settings-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut config: Config = toml::from_str(
+        "[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\ndisabled_tools = [\"fetch_profile_data\"]\ncheckpoint_count = 2\n",
+    )
+    .unwrap();
+    assert!(
+        !config
+            .sparkler_settings(None)
+            .tool_enabled("fetch_profile_data")
+    );
+
+    let migrated = migrate_to_multi_sparkler(&mut config, &dir).unwrap();
+    assert_eq!(migrated.as_deref(), Some("Nova"));
+    let settings = config.sparkler_settings(Some("Nova"));
+    assert!(!settings.tool_enabled("fetch_profile_data"));
+    assert_eq!(settings.checkpoint_count, Some(2));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+fn write_checkpoint(dir: &Path, name: &str, age_secs: u64) {
+    let path = dir.join(name);
+    fs::write(&path, name).unwrap();
+    let mtime = SystemTime::now() - Duration::from_secs(age_secs);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+}
+
+#[test]
+fn test_recent_checkpoints() {
+    let dir = std::env::temp_dir().join(format!("sparkle-checkpoints-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    write_checkpoint(&dir, "a.md", 300);
+    write_checkpoint(&dir, "b.md", 200);
+    write_checkpoint(&dir, "c.md", 100);
+    fs::write(dir.join("notes.txt"), "not a checkpoint").unwrap();
+
+    let names = |count| -> Vec<String> {
+        recent_checkpoints(&dir, count)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    };
+    assert_eq!(names(1), ["c.md"]);
+    assert_eq!(names(2), ["b.md", "c.md"]);
+    assert_eq!(names(10), ["a.md", "b.md", "c.md"]);
+    assert!(names(0).is_empty());
+    assert!(recent_checkpoints(&dir.join("missing"), 1).is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,217 @@
+//! Tests for git-backed sync of the sparkle directory
+//!
+//! Two "machines" sync through a local bare repository; entries appended to
+//! the same log on both sides must merge without conflicts.
+
+use chrono::{TimeZone, Utc};
+use sparkle_mcp::insights::{
+    InsightRecord, InsightStatus, append_record, load_records, write_records,
+};
+use sparkle_mcp::sync::{commit_all, sync};
+use sparkle_mcp::types::{InsightType, SyncConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sparkle-sync-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn append(path: &Path, text: &str) {
+    let mut content = fs::read_to_string(path).unwrap_or_default();
+    content.push_str(text);
+    fs::write(path, content).unwrap();
+}
+
+fn log(dir: &Path) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["log", "--format=%s"])
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_sync_between_machines_through_bare_remote() {
+    let root = scratch("machines");
+    let remote = root.join("remote.git");
+    let laptop = root.join("laptop");
+    let desktop = root.join("desktop");
+    fs::create_dir_all(&laptop).unwrap();
+    assert!(
+        Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()
+            .unwrap()
+            .success()
+    );
+
+    let config = SyncConfig {
+        enabled: true,
+        remote: Some(remote.display().to_string()),
+        branch: "main".to_string(),
+    };
+
+    // Laptop records a change and publishes it
+    let anchors = "pattern-anchors.md";
+    fs::write(laptop.join(anchors), "# Pattern Anchors\n").unwrap();
+    fs::write(laptop.join("sparkle-mcp.log"), "local only").unwrap();
+    assert!(commit_all(&laptop, "main", "Save pattern anchor insight a").unwrap());
+    assert!(!commit_all(&laptop, "main", "Nothing changed").unwrap());
+    let report = sync(&laptop, &config).unwrap();
+    assert_eq!(report.pushed, 1);
+
+    // A fresh desktop picks it up
+    let report = sync(&desktop, &config).unwrap();
+    assert_eq!(report.pulled, 1);
+    assert_eq!(
+        fs::read_to_string(desktop.join(anchors)).unwrap(),
+        "# Pattern Anchors\n"
+    );
+    assert!(!desktop.join("sparkle-mcp.log").exists());
+
+    // Both append to the same log before syncing
+    append(&laptop.join(anchors), "\n## From the laptop\n");
+    commit_all(&laptop, "main", "Save pattern anchor insight b").unwrap();
+    append(&desktop.join(anchors), "\n## From the desktop\n");
+    commit_all(&desktop, "main", "Save pattern anchor insight c").unwrap();
+
+    sync(&laptop, &config).unwrap();
+    let report = sync(&desktop, &config).unwrap();
+    assert_eq!((report.pulled, report.pushed), (1, 1));
+    sync(&laptop, &config).unwrap();
+
+    for machine in [&laptop, &desktop] {
+        let content = fs::read_to_string(machine.join(anchors)).unwrap();
+        assert!(content.contains("## From the laptop"), "{}", content);
+        assert!(content.contains("## From the desktop"), "{}", content);
+    }
+    assert!(log(&laptop).contains("Save pattern anchor insight c"));
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+fn init_remote(root: &Path) -> SyncConfig {
+    let remote = root.join("remote.git");
+    assert!(
+        Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()
+            .unwrap()
+            .success()
+    );
+    SyncConfig {
+        enabled: true,
+        remote: Some(remote.display().to_string()),
+        branch: "main".to_string(),
+    }
+}
+
+#[test]
+fn test_unsynced_machine_starts_from_remote_history() {
+    let root = scratch("fresh");
+    let laptop = root.join("laptop");
+    let desktop = root.join("desktop");
+    fs::create_dir_all(&laptop).unwrap();
+    fs::create_dir_all(&desktop).unwrap();
+    let config = init_remote(&root);
+
+    fs::write(laptop.join("collaborator-profile.md"), "# Kari\n").unwrap();
+    commit_all(&laptop, "main", "Set up sparkle for Kari").unwrap();
+    sync(&laptop, &config).unwrap();
+
+    // The desktop was set up before sync was enabled and never committed
+    fs::write(desktop.join("collaborator-profile.md"), "# Template\n").unwrap();
+    fs::write(desktop.join("notes.md"), "desktop only\n").unwrap();
+    let report = sync(&desktop, &config).unwrap();
+    assert_eq!((report.pulled, report.pushed), (1, 1));
+    assert!(report.committed_local_changes);
+
+    // The remote's version wins; the replaced one is kept locally
+    assert_eq!(
+        fs::read_to_string(desktop.join("collaborator-profile.md")).unwrap(),
+        "# Kari\n"
+    );
+    let versions = fs::read_dir(desktop.join(".history/collaborator-profile.md"))
+        .unwrap()
+        .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(versions, ["# Template\n"]);
+
+    // One history, not two merged ones
+    assert_eq!(
+        log(&desktop),
+        "Sync local changes\nSet up sparkle for Kari\n"
+    );
+    sync(&laptop, &config).unwrap();
+    assert_eq!(
+        fs::read_to_string(laptop.join("notes.md")).unwrap(),
+        "desktop only\n"
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_insight_status_survives_union_merge() {
+    let root = scratch("insights");
+    let laptop = root.join("laptop");
+    let desktop = root.join("desktop");
+    fs::create_dir_all(&laptop).unwrap();
+    fs::create_dir_all(&desktop).unwrap();
+    let config = init_remote(&root);
+
+    let record = |id: &str, status: InsightStatus| InsightRecord {
+        id: id.to_string(),
+        insight_type: InsightType::PatternAnchor,
+        timestamp: Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap(),
+        sparkler: None,
+        workspace: None,
+        tags: Vec::new(),
+        content: format!("Anchor {}", id),
+        context: None,
+        status,
+        supersedes: None,
+        superseded_by: None,
+        retraction_reason: None,
+    };
+
+    write_records(&laptop, &[record("a", InsightStatus::Active)]).unwrap();
+    commit_all(&laptop, "main", "Save pattern anchor insight a").unwrap();
+    sync(&laptop, &config).unwrap();
+    sync(&desktop, &config).unwrap();
+
+    // The laptop retracts a while the desktop saves b
+    write_records(&laptop, &[record("a", InsightStatus::Retracted)]).unwrap();
+    commit_all(&laptop, "main", "Retract insight a").unwrap();
+    append_record(&desktop, &record("b", InsightStatus::Active)).unwrap();
+    commit_all(&desktop, "main", "Save pattern anchor insight b").unwrap();
+
+    sync(&laptop, &config).unwrap();
+    sync(&desktop, &config).unwrap();
+    sync(&laptop, &config).unwrap();
+
+    // The union merge keeps both copies of a; the retracted one wins
+    for machine in [&laptop, &desktop] {
+        let mut statuses: Vec<(String, InsightStatus)> = load_records(machine)
+            .into_iter()
+            .map(|r| (r.id, r.status))
+            .collect();
+        statuses.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            statuses,
+            [
+                ("a".to_string(), InsightStatus::Retracted),
+                ("b".to_string(), InsightStatus::Active)
+            ]
+        );
+    }
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,96 @@
+//! Tests for the template layer used by identity files, starter templates and prompts
+
+use sparkle_mcp::template::{TemplateContext, render};
+use sparkle_mcp::types::Config;
+use std::fs;
+
+fn context(config: &str) -> TemplateContext {
+    let config: Config = toml::from_str(config).unwrap();
+    TemplateContext::from_config(&config, None)
+}
+
+#[test]
+fn test_variables_and_conditionals() {
+    let single =
+        context("[human]\nname = \"Ada\"\n\n[ai]\nname = \"Nova\"\n\n[sync]\nbranch = \"trunk\"\n");
+    let multi = context(
+        "[human]\nname = \"Ada\"\n\n[[sparklers]]\nname = \"Nova\"\ndefault = true\n\n[[sparklers]]\nname = \"Rex\"\n",
+    );
+
+    let template = "[ai.name] works with [human.name] on [config.sync.branch].\n\
+        [#if sparkler.multi]Sparklers: [sparkler.names][else]Only [sparkler.name][/if]\n\
+        [#if !config.sync.enabled]No sync.[/if] [#if ai.name == \"Nova\"]Hi Nova![/if]\n\
+        See [insight] and [config.toml](./config.md).";
+
+    let rendered = render(template, &single);
+    assert_eq!(
+        rendered.text,
+        "Nova works with Ada on trunk.\nOnly Nova\nNo sync. Hi Nova!\nSee [insight] and [config.toml](./config.md)."
+    );
+    assert!(rendered.warnings.is_empty(), "{:?}", rendered.warnings);
+
+    let rendered = render(template, &multi);
+    assert!(
+        rendered.text.contains("Sparklers: Nova, Rex"),
+        "{}",
+        rendered.text
+    );
+    // Sections missing from the config are unknown, and the placeholder is kept
+    assert!(rendered.text.contains("on [config.sync.branch]"));
+    assert_eq!(rendered.warnings.len(), 2, "{:?}", rendered.warnings);
+}
+
+#[test]
+fn test_unknown_placeholders_and_unbalanced_directives_warn() {
+    let context = context("[human]\nname = \"Ada\"\n");
+
+    let rendered = render("Hi [human.nmae]! [/if][#if human.name]open", &context);
+    assert_eq!(rendered.text, "Hi [human.nmae]! [/if]open");
+    assert_eq!(
+        rendered.warnings,
+        [
+            "[/if] without a matching [#if]",
+            "[#if human.name] is never closed with [/if]",
+            "Unknown placeholder [human.nmae]",
+        ]
+    );
+}
+
+#[test]
+fn test_includes_are_rendered_and_confined() {
+    let dir = std::env::temp_dir().join(format!("sparkle-template-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("team.md"), "Team of [human.name]").unwrap();
+    fs::write(dir.join("loop.md"), "[#include loop.md]").unwrap();
+
+    let context = context("[human]\nname = \"Ada\"\n").with_include_dir(&dir);
+    let rendered = render(
+        "[#include team.md]. [#include ../team.md][#include loop.md]",
+        &context,
+    );
+    assert_eq!(rendered.text, "Team of Ada. ");
+    assert_eq!(rendered.warnings.len(), 2, "{:?}", rendered.warnings);
+    assert!(rendered.warnings[0].contains("../team.md] not found"));
+    assert!(rendered.warnings[1].contains("nested more than"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_human_details_are_variables() {
+    let context = context(
+        "[human]\nname = \"Ada\"\npronouns = \"she/her\"\nrole = \"Staff engineer\"\n\n[human.preferences]\nreviews = \"terse\"\npairing = true\n",
+    );
+
+    let rendered = render(
+        "[human.name] ([human.pronouns]), [human.role][#if human.team] on [human.team][/if]. \
+        Reviews: [human.preferences.reviews][#if human.preferences.pairing], likes pairing[/if].",
+        &context,
+    );
+    assert_eq!(
+        rendered.text,
+        "Ada (she/her), Staff engineer. Reviews: terse, likes pairing."
+    );
+    assert!(rendered.warnings.is_empty(), "{:?}", rendered.warnings);
+}
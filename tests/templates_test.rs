@@ -0,0 +1,74 @@
+//! Tests for sparkler starter templates
+//!
+//! Templates are looked up in the personal directory before the team one and
+//! rendered with the human and sparkler names.
+
+use sparkle_mcp::context_loader::create_starter_files;
+use sparkle_mcp::template::TemplateContext;
+use sparkle_mcp::templates::{apply_template, find_template, list_templates};
+use sparkle_mcp::types::Config;
+use std::fs;
+
+#[test]
+fn test_templates_are_found_and_rendered() {
+    let root = std::env::temp_dir().join(format!("sparkle-templates-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let sparkle_dir = root.join("sparkle");
+    let team_dir = root.join("team");
+
+    fs::create_dir_all(team_dir.join("reviewer")).unwrap();
+    fs::write(
+        team_dir.join("reviewer/sparkler-identity.md"),
+        "# [ai.name]\n\nTeam reviewer for [human.name].\n",
+    )
+    .unwrap();
+    fs::create_dir_all(team_dir.join("pair")).unwrap();
+    fs::create_dir_all(sparkle_dir.join("templates/pair")).unwrap();
+    fs::write(
+        sparkle_dir.join("templates/pair/pattern-anchors.md"),
+        "mine\n",
+    )
+    .unwrap();
+
+    let config: Config = toml::from_str(&format!(
+        "[human]\nname = \"Ada\"\n\n[templates]\nteam_dir = {:?}\n",
+        team_dir.display().to_string()
+    ))
+    .unwrap();
+
+    assert_eq!(list_templates(&sparkle_dir, &config), ["pair", "reviewer"]);
+    // The personal template hides the team one of the same name
+    assert_eq!(
+        find_template(&sparkle_dir, &config, "pair").unwrap(),
+        sparkle_dir.join("templates/pair")
+    );
+    let err = find_template(&sparkle_dir, &config, "missing").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Available templates: pair, reviewer"),
+        "{}",
+        err
+    );
+    assert!(find_template(&sparkle_dir, &config, "../team").is_err());
+
+    let sparkler_dir = sparkle_dir.join("sparklers/Rex");
+    fs::create_dir_all(&sparkler_dir).unwrap();
+    create_starter_files(&sparkler_dir, "Rex").unwrap();
+    let template = find_template(&sparkle_dir, &config, "reviewer").unwrap();
+    let context = TemplateContext::from_config(&config, Some("Rex"));
+    let files = apply_template(&template, &sparkler_dir, &context).unwrap();
+
+    assert_eq!(files, ["sparkler-identity.md"]);
+    assert_eq!(
+        fs::read_to_string(sparkler_dir.join("sparkler-identity.md")).unwrap(),
+        "# Rex\n\nTeam reviewer for Ada.\n"
+    );
+    // Files the template doesn't provide keep the generic starter content
+    assert!(
+        fs::read_to_string(sparkler_dir.join("pattern-anchors.md"))
+            .unwrap()
+            .starts_with("# Pattern Anchors")
+    );
+
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,60 @@
+//! Tests for working-memory snapshots
+//!
+//! Each checkpoint keeps the working memory it replaces in
+//! `.sparkle-space/.history/`, which `sparkle-mcp tui` compares.
+
+use rmcp::handler::server::wrapper::Parameters;
+use sparkle_mcp::tools::checkpoint::{session_checkpoint, working_memory_history};
+use sparkle_mcp::types::CheckpointParams;
+use std::fs;
+use std::path::Path;
+
+#[tokio::test]
+async fn test_checkpoint_keeps_previous_working_memory() {
+    // session_checkpoint writes to the current directory; this is the only test in this binary
+    let workspace =
+        std::env::temp_dir().join(format!("sparkle-working-memory-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&workspace);
+    fs::create_dir_all(&workspace).unwrap();
+    std::env::set_current_dir(&workspace).unwrap();
+
+    for focus in ["doctor", "render", "init"] {
+        let result = session_checkpoint(Parameters(CheckpointParams {
+            working_memory: format!("{{\"currentFocus\": \"{}\"}}", focus),
+            checkpoint_content: format!("# Working on {}\n", focus),
+            sparkler: None,
+        }))
+        .await
+        .unwrap();
+        assert_ne!(result.is_error, Some(true));
+    }
+
+    let sparkle_space = Path::new(".sparkle-space");
+    let path = sparkle_space.join("working-memory.json");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "{\"currentFocus\": \"init\"}"
+    );
+
+    // Newest first: what the last checkpoint replaced, then the one before
+    let versions = working_memory_history(sparkle_space).list_versions(&path);
+    let snapshots: Vec<String> = versions
+        .iter()
+        .map(|v| fs::read_to_string(&v.path).unwrap())
+        .collect();
+    assert_eq!(
+        snapshots,
+        [
+            "{\"currentFocus\": \"render\"}",
+            "{\"currentFocus\": \"doctor\"}"
+        ]
+    );
+    assert!(
+        workspace
+            .join(".sparkle-space/.history/working-memory.json")
+            .is_dir()
+    );
+
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    let _ = fs::remove_dir_all(&workspace);
+}
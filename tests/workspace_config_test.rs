@@ -0,0 +1,100 @@
+//! Tests for a workspace's `.sparkle-space/config.toml`
+//!
+//! A workspace can pin a sparkler, opt out of Sparkle and limit how many
+//! checkpoints are kept.
+
+use sparkle_mcp::context_loader::{
+    load_workspace_config, pinned_sparkler, requested_or_pinned_sparkler,
+};
+use sparkle_mcp::tools::checkpoint::prune_checkpoints;
+use sparkle_mcp::types::{Config, WorkspaceConfig};
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+const MULTI: &str = "[human]\nname = \"Kari\"\n\n[[sparklers]]\nname = \"Sparkle\"\ndefault = true\n\n[[sparklers]]\nname = \"Ops\"\n";
+
+#[test]
+fn test_workspace_config_and_pinned_sparkler() {
+    let workspace = std::env::temp_dir().join(format!("sparkle-workspace-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&workspace);
+    fs::create_dir_all(workspace.join(".sparkle-space")).unwrap();
+
+    // No config file: Sparkle is on and nothing is pinned
+    let defaults = load_workspace_config(&workspace);
+    assert!(defaults.enabled);
+    assert!(defaults.sparkler.is_none());
+
+    fs::write(
+        workspace.join(".sparkle-space/config.toml"),
+        "sparkler = \"Ops\"\n\n[checkpoints]\nkeep = 10\nload = 2\n",
+    )
+    .unwrap();
+    let workspace_config = load_workspace_config(&workspace);
+    assert!(workspace_config.enabled);
+    let checkpoints = workspace_config.checkpoints.as_ref().unwrap();
+    assert_eq!((checkpoints.keep, checkpoints.load), (Some(10), Some(2)));
+
+    let config: Config = toml::from_str(MULTI).unwrap();
+    assert_eq!(
+        pinned_sparkler(&config, &workspace_config).as_deref(),
+        Some("Ops")
+    );
+
+    // An explicitly requested sparkler wins over the pinned one
+    assert_eq!(
+        requested_or_pinned_sparkler(Some("Sparkle".to_string()), &config, &workspace_config)
+            .as_deref(),
+        Some("Sparkle")
+    );
+    assert_eq!(
+        requested_or_pinned_sparkler(None, &config, &workspace_config).as_deref(),
+        Some("Ops")
+    );
+
+    // Unknown sparklers and single-sparkler setups fall back to the default
+    let unknown = WorkspaceConfig {
+        sparkler: Some("Nobody".to_string()),
+        ..Default::default()
+    };
+    assert!(pinned_sparkler(&config, &unknown).is_none());
+    let single: Config =
+        toml::from_str("[human]\nname = \"Kari\"\n\n[ai]\nname = \"Nova\"\n").unwrap();
+    assert!(pinned_sparkler(&single, &workspace_config).is_none());
+
+    fs::write(
+        workspace.join(".sparkle-space/config.toml"),
+        "enabled = false\n",
+    )
+    .unwrap();
+    assert!(!load_workspace_config(&workspace).enabled);
+
+    fs::remove_dir_all(&workspace).unwrap();
+}
+
+#[test]
+fn test_prune_checkpoints_keeps_newest() {
+    let dir = std::env::temp_dir().join(format!("sparkle-prune-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for (i, name) in ["checkpoint-1.md", "checkpoint-2.md", "checkpoint-3.md"]
+        .iter()
+        .enumerate()
+    {
+        let path = dir.join(name);
+        fs::write(&path, name).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(300 - i as u64 * 100))
+            .unwrap();
+    }
+
+    let pruned = prune_checkpoints(&dir, 2).unwrap();
+    assert_eq!(pruned, [dir.join("checkpoint-1.md")]);
+    assert!(!dir.join("checkpoint-1.md").exists());
+    assert!(dir.join("checkpoint-3.md").exists());
+    assert!(prune_checkpoints(&dir, 5).unwrap().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}